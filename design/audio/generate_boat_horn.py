@@ -0,0 +1,55 @@
+#!/usr/bin/env python3
+"""
+Generates the horn boats sound when they enter the arena, assets/audio/sfx/boat_horn.ogg.
+
+Two low sawtooth tones a fifth apart are softened with a low pass filter, which sounds like a
+small ship's horn.
+
+Usage: python3 design/audio/generate_boat_horn.py
+"""
+import math
+import os
+
+from vorbis import write_ogg
+
+SAMPLE_RATE = 22050
+DURATION = 1.0
+ATTACK = 0.08
+RELEASE = 0.35
+NOTES = [98.0, 147.0]
+
+OUT_PATH = os.path.join(
+    os.path.dirname(__file__), "..", "..", "assets", "audio", "sfx", "boat_horn.ogg"
+)
+
+
+def envelope(t):
+    if t < ATTACK:
+        return t / ATTACK
+    if t < DURATION:
+        return 1.0
+    return max(0.0, 1.0 - (t - DURATION) / RELEASE)
+
+
+def main():
+    samples = []
+    phases = [0.0] * len(NOTES)
+    filtered = 0.0
+    for i in range(int((DURATION + RELEASE) * SAMPLE_RATE)):
+        t = i / SAMPLE_RATE
+        # the pitch sags a little as the horn starts, like air filling it
+        bend = 1.0 - 0.03 * math.exp(-t / 0.1)
+        value = 0.0
+        for n, freq in enumerate(NOTES):
+            phases[n] = (phases[n] + freq * bend / SAMPLE_RATE) % 1.0
+            value += 2 * phases[n] - 1
+        filtered += 0.08 * (value - filtered)
+        samples.append(filtered * envelope(t))
+
+    loudest = max(abs(sample) for sample in samples)
+    write_ogg(OUT_PATH, [sample / loudest * 0.8 for sample in samples], SAMPLE_RATE)
+    print("Wrote", OUT_PATH)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,223 @@
+#!/usr/bin/env python3
+"""
+Generates the looping music tracks in assets/audio/music.
+
+Every track is 8 bars at 120 bpm over the same Am - F - C - G progression, so the play stems stay
+in sync while they are layered. Notes that ring past the end of a track wrap around to its start,
+which makes every track loop without a click.
+
+Usage: python3 design/audio/generate_music.py
+"""
+import math
+import os
+import random
+
+from vorbis import write_ogg
+
+SAMPLE_RATE = 22050
+BEAT = 0.5
+BAR = 4 * BEAT
+BARS = 8
+LENGTH = int(BARS * BAR * SAMPLE_RATE)
+
+OUT_DIR = os.path.join(os.path.dirname(__file__), "..", "..", "assets", "audio", "music")
+
+# (root of the bass, notes of the chord) for every bar
+PROGRESSION = [
+    (45, [57, 60, 64]),  # Am
+    (41, [53, 57, 60]),  # F
+    (48, [55, 60, 64]),  # C
+    (43, [55, 59, 62]),  # G
+] * 2
+
+
+def frequency(note):
+    return 440.0 * 2 ** ((note - 69) / 12)
+
+
+def oscillator(shape, phase):
+    phase %= 1.0
+    if shape == "sine":
+        return math.sin(2 * math.pi * phase)
+    if shape == "triangle":
+        return 4 * abs(phase - 0.5) - 1
+    if shape == "square":
+        return 1.0 if phase < 0.5 else -1.0
+    if shape == "pulse":
+        return 1.0 if phase < 0.25 else -1.0
+    raise ValueError(shape)
+
+
+class Track:
+    def __init__(self):
+        self.samples = [0.0] * LENGTH
+        self.noise = random.Random(1)
+
+    def add(self, start, values):
+        """Mixes in samples from a time in seconds, wrapping around the end of the loop."""
+        offset = int(start * SAMPLE_RATE)
+        for i, value in enumerate(values):
+            self.samples[(offset + i) % LENGTH] += value
+
+    def note(self, start, duration, note, shape, volume, attack=0.01, release=0.1, vibrato=0.0):
+        freq = frequency(note)
+        total = duration + release
+        values = []
+        phase = 0.0
+        for i in range(int(total * SAMPLE_RATE)):
+            t = i / SAMPLE_RATE
+            if t < attack:
+                envelope = t / attack
+            elif t < duration:
+                envelope = 1.0
+            else:
+                envelope = 1.0 - (t - duration) / release
+            wobble = 1.0 + vibrato * math.sin(2 * math.pi * 5.0 * t)
+            phase += freq * wobble / SAMPLE_RATE
+            values.append(volume * envelope * oscillator(shape, phase))
+        self.add(start, values)
+
+    def pluck(self, start, note, shape, volume, decay=0.25):
+        freq = frequency(note)
+        values = []
+        for i in range(int(decay * 4 * SAMPLE_RATE)):
+            t = i / SAMPLE_RATE
+            values.append(volume * math.exp(-t / decay) * oscillator(shape, freq * t))
+        self.add(start, values)
+
+    def kick(self, start, volume):
+        values = []
+        phase = 0.0
+        for i in range(int(0.35 * SAMPLE_RATE)):
+            t = i / SAMPLE_RATE
+            phase += (45 + 90 * math.exp(-t / 0.04)) / SAMPLE_RATE
+            values.append(volume * math.exp(-t / 0.1) * math.sin(2 * math.pi * phase))
+        self.add(start, values)
+
+    def snare(self, start, volume):
+        values = []
+        for i in range(int(0.2 * SAMPLE_RATE)):
+            t = i / SAMPLE_RATE
+            noise = self.noise.uniform(-1, 1)
+            tone = math.sin(2 * math.pi * 180 * t)
+            values.append(volume * math.exp(-t / 0.06) * (0.7 * noise + 0.3 * tone))
+        self.add(start, values)
+
+    def hat(self, start, volume, decay=0.02):
+        values = []
+        previous = 0.0
+        for i in range(int(decay * 5 * SAMPLE_RATE)):
+            t = i / SAMPLE_RATE
+            noise = self.noise.uniform(-1, 1)
+            # the difference of white noise keeps only its hiss
+            values.append(volume * math.exp(-t / decay) * (noise - previous) / 2)
+            previous = noise
+        self.add(start, values)
+
+    def write(self, name, peak):
+        loudest = max(abs(sample) for sample in self.samples) or 1.0
+        samples = [sample / loudest * peak for sample in self.samples]
+        write_ogg(os.path.join(OUT_DIR, name), samples, SAMPLE_RATE, loop=True)
+        print("Wrote", name)
+
+
+def bars():
+    for bar, (root, chord) in enumerate(PROGRESSION):
+        yield bar * BAR, root, chord
+
+
+def menu():
+    track = Track()
+    for start, root, chord in bars():
+        for note in chord:
+            track.note(start, BAR, note, "triangle", 0.12, attack=0.6, release=0.8)
+        for beat in range(4):
+            note = chord[beat % len(chord)] + 12
+            track.pluck(start + beat * BEAT, note, "sine", 0.2, decay=0.4)
+        track.note(start, BAR * 0.9, root - 12, "sine", 0.25, attack=0.1, release=0.3)
+    track.write("menu.ogg", 0.7)
+
+
+def play_base():
+    track = Track()
+    for start, root, chord in bars():
+        for beat in range(4):
+            time = start + beat * BEAT
+            if beat % 2 == 0:
+                track.kick(time, 0.9)
+            else:
+                track.snare(time, 0.35)
+        for eighth in range(8):
+            time = start + eighth * BEAT / 2
+            track.hat(time, 0.25)
+            octave = 12 if eighth % 4 == 3 else 0
+            track.note(time, BEAT / 2 * 0.8, root + octave, "square", 0.12, release=0.03)
+            track.pluck(time, chord[eighth % 3] + 12, "triangle", 0.12, decay=0.12)
+    track.write("play_base.ogg", 0.8)
+
+
+# a note for every quarter beat of the first 4 bars, None is a rest
+MELODY = [
+    76, None, 72, 74, 76, None, 79, 76,
+    77, None, 76, 72, 69, None, 72, None,
+    72, None, 74, 76, 79, None, 76, 74,
+    74, None, 71, 74, 79, None, 74, None,
+]
+
+
+def play_intensity():
+    track = Track()
+    for bar_start, _, _ in bars():
+        for sixteenth in range(16):
+            time = bar_start + sixteenth * BEAT / 4
+            track.hat(time, 0.3 if sixteenth % 4 == 2 else 0.15, decay=0.03)
+    for repeat in range(2):
+        for step, note in enumerate(MELODY):
+            if note is None:
+                continue
+            time = repeat * 4 * BAR + step * BEAT / 2
+            track.note(time, BEAT / 2 * 0.9, note, "pulse", 0.15, release=0.08, vibrato=0.004)
+    track.write("play_intensity.ogg", 0.6)
+
+
+def play_danger():
+    track = Track()
+    for bar_start, root, _ in bars():
+        # a heartbeat on the first beat
+        track.kick(bar_start, 0.8)
+        track.kick(bar_start + BEAT / 2, 0.5)
+        for sixteenth in range(16):
+            time = bar_start + sixteenth * BEAT / 4
+            # alternating a tritone apart sounds uneasy over every chord
+            note = root + 36 if sixteenth % 2 == 0 else root + 42
+            track.pluck(time, note, "square", 0.06, decay=0.05)
+    track.write("play_danger.ogg", 0.5)
+
+
+# a slow fall over the Am - Dm - E - Am progression, two bars each
+GAME_OVER = [
+    (45, [57, 60, 64], 76),
+    (50, [57, 62, 65], 74),
+    (52, [56, 59, 64], 71),
+    (45, [57, 60, 64], 69),
+]
+
+
+def game_over():
+    track = Track()
+    for i, (root, chord, melody) in enumerate(GAME_OVER):
+        start = i * 2 * BAR
+        for note in chord:
+            track.note(start, 2 * BAR, note, "triangle", 0.1, attack=0.8, release=1.0)
+        track.note(start, 2 * BAR * 0.9, root - 12, "sine", 0.25, attack=0.2, release=0.5)
+        track.note(start + BEAT, 3 * BEAT, melody, "sine", 0.2, attack=0.05, release=0.6)
+        track.note(start + BAR, 2 * BEAT, melody - 2, "sine", 0.15, attack=0.05, release=0.6)
+    track.write("game_over.ogg", 0.7)
+
+
+if __name__ == "__main__":
+    menu()
+    play_base()
+    play_intensity()
+    play_danger()
+    game_over()
@@ -0,0 +1,636 @@
+"""
+A small Ogg Vorbis encoder for the generated sounds, so they ship as .ogg like the rest of the
+game's audio without an encoder having to be installed.
+
+It only encodes mono audio in long blocks and spends its bits evenly across the spectrum instead of
+modelling what can be heard. Each block gets a floor that follows the loudness of its spectrum, and
+the spectrum is stored as small whole numbers relative to that floor. That is plenty for the game's
+synthesized sounds. The codebooks are built for every file from the sound itself.
+
+Usage: vorbis.write_ogg(path, samples, sample_rate), with samples between -1 and 1.
+"""
+import cmath
+import heapq
+import math
+import struct
+
+BLOCK = 2048
+HALF = BLOCK // 2
+
+# the floor has a point at each of these bins, in between it is a straight line in decibels
+FLOOR_MULTIPLIER = 2
+FLOOR_RANGE = 256 // FLOOR_MULTIPLIER
+FLOOR_RANGE_BITS = 10
+FLOOR_POSTS = [
+    0, HALF,
+    2, 4, 6, 8, 11, 14,
+    18, 23, 28, 34, 41, 49,
+    58, 68, 80, 94, 110, 128,
+    150, 176, 206, 240, 280, 330,
+    390, 460, 540, 640, 760, 900,
+]
+FLOOR_CLASS_DIMENSIONS = 6
+
+# the spectrum is split into partitions, each stored with the smallest book its values fit in
+PARTITION_SIZE = 16
+CLASSES_PER_WORD = 2
+# (dimensions, largest value) of the book of each partition class, class 0 is silent
+RESIDUE_BOOKS = [None, (4, 1), (2, 4), (1, 128)]
+
+# how many steps the loudest part of the spectrum around a floor point is quantized to
+STEPS = 12.0
+# quieter parts of a block than this are left out
+DYNAMIC_RANGE_DB = 60.0
+
+FLOOR_BOOK = 0
+CLASS_BOOK = 1
+FIRST_RESIDUE_BOOK = 2
+
+
+# the floor values of the Vorbis specification's table, which grow by the same factor every step
+INVERSE_DB = [math.exp((index - 255) * 0.0629613087) for index in range(256)]
+
+
+class BitWriter:
+    """Packs values least significant bit first, like Vorbis packets are read."""
+
+    def __init__(self):
+        self.data = bytearray()
+        self.pending = 0
+        self.pending_bits = 0
+
+    def write(self, value, bits):
+        self.pending |= (value & ((1 << bits) - 1)) << self.pending_bits
+        self.pending_bits += bits
+        while self.pending_bits >= 8:
+            self.data.append(self.pending & 0xFF)
+            self.pending >>= 8
+            self.pending_bits -= 8
+
+    def write_bytes(self, data):
+        for byte in data:
+            self.write(byte, 8)
+
+    def finish(self):
+        if self.pending_bits:
+            self.data.append(self.pending & 0xFF)
+        return bytes(self.data)
+
+
+# Codebooks
+
+
+def huffman_lengths(counts, max_length=24):
+    """Code lengths for every symbol. Every symbol gets a code, even ones that never appear."""
+    counts = [count + 1 for count in counts]
+    while True:
+        heap = [(count, i, [i]) for i, count in enumerate(counts)]
+        heapq.heapify(heap)
+        lengths = [0] * len(counts)
+        order = len(counts)
+        while len(heap) > 1:
+            count_a, _, symbols_a = heapq.heappop(heap)
+            count_b, _, symbols_b = heapq.heappop(heap)
+            for symbol in symbols_a + symbols_b:
+                lengths[symbol] += 1
+            heapq.heappush(heap, (count_a + count_b, order, symbols_a + symbols_b))
+            order += 1
+        if max(lengths) <= max_length:
+            return lengths
+        # flatten the counts until the longest code is short enough
+        counts = [int(math.sqrt(count)) + 1 for count in counts]
+
+
+def codewords(lengths):
+    """The codeword of every entry, assigned from the lengths the way decoders do."""
+    marker = [0] * 33
+    words = []
+    for length in lengths:
+        entry = marker[length]
+        assert length == 32 or entry >> length == 0, "overpopulated codebook"
+        words.append(entry)
+        for j in range(length, 0, -1):
+            if marker[j] & 1:
+                marker[j] = marker[1] + 1 if j == 1 else marker[j - 1] << 1
+                break
+            marker[j] += 1
+        for j in range(length + 1, 33):
+            if marker[j] >> 1 == entry:
+                entry = marker[j]
+                marker[j] = marker[j - 1] << 1
+            else:
+                break
+    return words
+
+
+def reverse_bits(value, bits):
+    result = 0
+    for _ in range(bits):
+        result = (result << 1) | (value & 1)
+        value >>= 1
+    return result
+
+
+def pack_float(value):
+    """Packs a whole number in the float format of codebook headers."""
+    sign = 0x80000000 if value < 0 else 0
+    return sign | (788 << 21) | abs(int(value))
+
+
+class Codebook:
+    def __init__(self, dimensions, entries, counts, values=None):
+        """`values` are the smallest value and how many values each dimension of a vector has."""
+        self.dimensions = dimensions
+        self.entries = entries
+        self.values = values
+        self.lengths = huffman_lengths(counts)
+        # codewords are read one bit at a time from their first bit, so they are written reversed
+        self.codes = [
+            reverse_bits(word, length) for word, length in zip(codewords(self.lengths), self.lengths)
+        ]
+
+    def write_header(self, writer):
+        writer.write(0x564342, 24)
+        writer.write(self.dimensions, 16)
+        writer.write(self.entries, 24)
+        # neither ordered nor sparse
+        writer.write(0, 1)
+        writer.write(0, 1)
+        for length in self.lengths:
+            writer.write(length - 1, 5)
+
+        if self.values is None:
+            writer.write(0, 4)
+            return
+
+        minimum, count = self.values
+        value_bits = max(1, (count - 1).bit_length())
+        writer.write(1, 4)
+        writer.write(pack_float(minimum), 32)
+        writer.write(pack_float(1), 32)
+        writer.write(value_bits - 1, 4)
+        writer.write(0, 1)
+        for multiplicand in range(count):
+            writer.write(multiplicand, value_bits)
+
+    def write(self, writer, entry):
+        writer.write(self.codes[entry], self.lengths[entry])
+
+
+# Transform
+
+
+def window(n):
+    return math.sin(math.pi / 2 * math.sin(math.pi * (n + 0.5) / BLOCK) ** 2)
+
+
+WINDOW = [window(n) for n in range(BLOCK)]
+
+
+def fft(values):
+    """In place radix 2 FFT."""
+    count = len(values)
+    j = 0
+    for i in range(1, count):
+        bit = count >> 1
+        while j & bit:
+            j ^= bit
+            bit >>= 1
+        j |= bit
+        if i < j:
+            values[i], values[j] = values[j], values[i]
+    size = 2
+    while size <= count:
+        step = complex(math.cos(-2 * math.pi / size), math.sin(-2 * math.pi / size))
+        for start in range(0, count, size):
+            twiddle = 1.0
+            for k in range(size // 2):
+                even = values[start + k]
+                odd = values[start + k + size // 2] * twiddle
+                values[start + k] = even + odd
+                values[start + k + size // 2] = even - odd
+                twiddle *= step
+        size *= 2
+    return values
+
+
+DCT_PRE_TWIDDLES = [cmath.exp(-1j * math.pi * (n + 0.25) / HALF) for n in range(HALF // 2)]
+DCT_POST_TWIDDLES = [cmath.exp(-1j * math.pi * k / HALF) for k in range(HALF // 2)]
+
+
+def dct4(values):
+    """DCT-IV of `HALF` values, X[k] = sum of x[n] cos(pi / M (n + 1/2) (k + 1/2)), done with an FFT
+    of half the size."""
+    size = len(values)
+    folded = [
+        complex(values[2 * n], values[size - 1 - 2 * n]) * DCT_PRE_TWIDDLES[n]
+        for n in range(size // 2)
+    ]
+    fft(folded)
+    result = [0.0] * size
+    for k in range(size // 2):
+        value = folded[k] * DCT_POST_TWIDDLES[k]
+        result[2 * k] = value.real
+        result[size - 1 - 2 * k] = -value.imag
+    return result
+
+
+def mdct(block):
+    """MDCT, X[k] = sum of x[n] cos(2 pi / N (n + 1/2 + N/4) (k + 1/2))."""
+    quarter = BLOCK // 4
+    a = block[:quarter]
+    b = block[quarter:2 * quarter]
+    c = block[2 * quarter:3 * quarter]
+    d = block[3 * quarter:]
+    folded = [-c[quarter - 1 - n] - d[n] for n in range(quarter)]
+    folded += [a[n] - b[quarter - 1 - n] for n in range(quarter)]
+    return dct4(folded)
+
+
+# Floor
+
+
+def low_neighbor(posts, i):
+    return max((j for j in range(i) if posts[j] < posts[i]), key=lambda j: posts[j])
+
+
+def high_neighbor(posts, i):
+    return min((j for j in range(i) if posts[j] > posts[i]), key=lambda j: posts[j])
+
+
+NEIGHBORS = [None, None] + [
+    (low_neighbor(FLOOR_POSTS, i), high_neighbor(FLOOR_POSTS, i)) for i in range(2, len(FLOOR_POSTS))
+]
+
+
+def divide(a, b):
+    """Integer division that rounds towards zero, like C."""
+    return int(a / b) if a * b >= 0 else -(abs(a) // abs(b))
+
+
+def render_point(x0, y0, x1, y1, x):
+    dy = y1 - y0
+    error = abs(dy) * (x - x0)
+    offset = error // (x1 - x0)
+    return y0 - offset if dy < 0 else y0 + offset
+
+
+def render_line(x0, y0, x1, y1, curve):
+    dy = y1 - y0
+    adx = x1 - x0
+    base = divide(dy, adx)
+    sy = base - 1 if dy < 0 else base + 1
+    ady = abs(dy) - abs(base) * adx
+    y = y0
+    error = 0
+    if x0 < HALF:
+        curve[x0] = y
+    for x in range(x0 + 1, min(x1, HALF)):
+        error += ady
+        if error >= adx:
+            error -= adx
+            y += sy
+        else:
+            y += base
+        curve[x] = y
+
+
+def encode_floor(targets):
+    """The coded values of the floor points and the floor curve a decoder will draw from them."""
+    final = list(targets[:2])
+    coded = list(targets[:2])
+    used = [True, True] + [False] * (len(FLOOR_POSTS) - 2)
+
+    for i in range(2, len(FLOOR_POSTS)):
+        low, high = NEIGHBORS[i]
+        predicted = render_point(
+            FLOOR_POSTS[low], final[low], FLOOR_POSTS[high], final[high], FLOOR_POSTS[i]
+        )
+        high_room = FLOOR_RANGE - predicted
+        low_room = predicted
+        room = 2 * min(high_room, low_room)
+        difference = targets[i] - predicted
+
+        if difference == 0:
+            value = 0
+        elif difference > 0:
+            value = 2 * difference if 2 * difference < room else difference + low_room
+        else:
+            value = -2 * difference - 1 if -2 * difference - 1 < room else high_room - 1 - difference
+        coded.append(value)
+
+        if value == 0:
+            final.append(predicted)
+        else:
+            used[low] = used[high] = used[i] = True
+            final.append(targets[i])
+
+    curve = [0] * HALF
+    points = sorted(
+        (FLOOR_POSTS[i], final[i] * FLOOR_MULTIPLIER) for i in range(len(FLOOR_POSTS)) if used[i]
+    )
+    lx, ly = points[0]
+    for hx, hy in points[1:]:
+        render_line(lx, ly, hx, hy, curve)
+        lx, ly = hx, hy
+    if lx < HALF:
+        render_line(lx, ly, HALF, ly, curve)
+
+    return coded, [INVERSE_DB[y] for y in curve]
+
+
+def floor_targets(spectrum):
+    """The floor at each floor point, so the loudest nearby value is about `STEPS` floors high."""
+    magnitudes = [abs(value) for value in spectrum]
+    peak = max(magnitudes)
+    quietest = peak * 10 ** (-DYNAMIC_RANGE_DB / 20)
+
+    ordered = sorted(FLOOR_POSTS)
+    bounds = {}
+    for i, post in enumerate(ordered):
+        start = (ordered[i - 1] + post) // 2 if i > 0 else 0
+        end = (post + ordered[i + 1]) // 2 + 1 if i + 1 < len(ordered) else HALF
+        bounds[post] = (start, max(end, start + 1))
+
+    targets = []
+    for post in FLOOR_POSTS:
+        start, end = bounds[post]
+        loudest = max(max(magnitudes[start:end]), quietest)
+        wanted = loudest / STEPS
+        index = min(
+            range(FLOOR_RANGE),
+            key=lambda y: abs(math.log(INVERSE_DB[y * FLOOR_MULTIPLIER] / wanted)),
+        )
+        targets.append(index)
+    return targets
+
+
+# Encoding
+
+
+class Block:
+    def __init__(self, spectrum):
+        self.floor = None
+        self.classes = []
+        self.residue = []
+        if max(abs(value) for value in spectrum) < 1e-6:
+            return
+
+        targets = floor_targets(spectrum)
+        self.floor, curve = encode_floor(targets)
+        limit = RESIDUE_BOOKS[-1][1]
+        self.residue = [
+            max(-limit, min(limit - 1, round(value / level)))
+            for value, level in zip(spectrum, curve)
+        ]
+        for start in range(0, HALF, PARTITION_SIZE):
+            loudest = max(abs(value) for value in self.residue[start:start + PARTITION_SIZE])
+            self.classes.append(
+                next(
+                    c for c, book in enumerate(RESIDUE_BOOKS)
+                    if (book is None and loudest == 0) or (book is not None and loudest <= book[1])
+                )
+            )
+
+    def vectors(self):
+        """(class, book entry) of every vector of the residue, in the order they are written."""
+        for partition, residue_class in enumerate(self.classes):
+            if residue_class == 0:
+                continue
+            dimensions, largest = RESIDUE_BOOKS[residue_class]
+            values = 2 * largest + 1 if residue_class < len(RESIDUE_BOOKS) - 1 else 2 * largest
+            start = partition * PARTITION_SIZE
+            for offset in range(start, start + PARTITION_SIZE, dimensions):
+                entry = 0
+                for value in reversed(self.residue[offset:offset + dimensions]):
+                    entry = entry * values + value + largest
+                yield partition, residue_class, entry
+
+    def class_words(self):
+        for start in range(0, len(self.classes), CLASSES_PER_WORD):
+            word = 0
+            for residue_class in self.classes[start:start + CLASSES_PER_WORD]:
+                word = word * len(RESIDUE_BOOKS) + residue_class
+            yield start, word
+
+
+def residue_book_shape(residue_class):
+    dimensions, largest = RESIDUE_BOOKS[residue_class]
+    values = 2 * largest + 1 if residue_class < len(RESIDUE_BOOKS) - 1 else 2 * largest
+    return dimensions, values, -largest
+
+
+def build_codebooks(blocks):
+    floor_counts = [0] * FLOOR_RANGE
+    class_counts = [0] * len(RESIDUE_BOOKS) ** CLASSES_PER_WORD
+    residue_counts = {
+        c: [0] * residue_book_shape(c)[1] ** residue_book_shape(c)[0]
+        for c in range(1, len(RESIDUE_BOOKS))
+    }
+    for block in blocks:
+        if block.floor is None:
+            continue
+        for value in block.floor[2:]:
+            floor_counts[value] += 1
+        for _, word in block.class_words():
+            class_counts[word] += 1
+        for _, residue_class, entry in block.vectors():
+            residue_counts[residue_class][entry] += 1
+
+    books = [
+        Codebook(1, FLOOR_RANGE, floor_counts),
+        Codebook(CLASSES_PER_WORD, len(class_counts), class_counts),
+    ]
+    for residue_class in range(1, len(RESIDUE_BOOKS)):
+        dimensions, values, minimum = residue_book_shape(residue_class)
+        books.append(
+            Codebook(
+                dimensions, values ** dimensions, residue_counts[residue_class], (minimum, values)
+            )
+        )
+    return books
+
+
+def identification_header(sample_rate):
+    return (
+        b"\x01vorbis"
+        + struct.pack("<IBIiii", 0, 1, sample_rate, 0, 0, 0)
+        # short blocks of 256 samples, long blocks of 2048
+        + bytes([8 | 11 << 4, 1])
+    )
+
+
+def comment_header():
+    vendor = b"fish-game design/audio/vorbis.py"
+    return b"\x03vorbis" + struct.pack("<I", len(vendor)) + vendor + struct.pack("<I", 0) + b"\x01"
+
+
+def setup_header(books):
+    writer = BitWriter()
+    writer.write(5, 8)
+    writer.write_bytes(b"vorbis")
+
+    writer.write(len(books) - 1, 8)
+    for book in books:
+        book.write_header(writer)
+
+    # one unused time domain transform
+    writer.write(0, 6)
+    writer.write(0, 16)
+
+    # one floor of type 1, its points in partitions that all share one class
+    writer.write(0, 6)
+    writer.write(1, 16)
+    partitions = (len(FLOOR_POSTS) - 2) // FLOOR_CLASS_DIMENSIONS
+    writer.write(partitions, 5)
+    for _ in range(partitions):
+        writer.write(0, 4)
+    writer.write(FLOOR_CLASS_DIMENSIONS - 1, 3)
+    writer.write(0, 2)
+    writer.write(FLOOR_BOOK + 1, 8)
+    writer.write(FLOOR_MULTIPLIER - 1, 2)
+    writer.write(FLOOR_RANGE_BITS, 4)
+    for post in FLOOR_POSTS[2:]:
+        writer.write(post, FLOOR_RANGE_BITS)
+
+    # one residue of type 1 over the whole spectrum
+    writer.write(0, 6)
+    writer.write(1, 16)
+    writer.write(0, 24)
+    writer.write(HALF, 24)
+    writer.write(PARTITION_SIZE - 1, 24)
+    writer.write(len(RESIDUE_BOOKS) - 1, 6)
+    writer.write(CLASS_BOOK, 8)
+    for book in RESIDUE_BOOKS:
+        # only the first pass has books
+        writer.write(0 if book is None else 1, 3)
+        writer.write(0, 1)
+    for residue_class in range(1, len(RESIDUE_BOOKS)):
+        writer.write(FIRST_RESIDUE_BOOK + residue_class - 1, 8)
+
+    # one mapping of the mono channel to the floor and the residue
+    writer.write(0, 6)
+    writer.write(0, 16)
+    writer.write(0, 1)
+    writer.write(0, 1)
+    writer.write(0, 2)
+    writer.write(0, 8)
+    writer.write(0, 8)
+    writer.write(0, 8)
+
+    # one mode, with long blocks
+    writer.write(0, 6)
+    writer.write(1, 1)
+    writer.write(0, 16)
+    writer.write(0, 16)
+    writer.write(0, 8)
+
+    writer.write(1, 1)
+    return writer.finish()
+
+
+def audio_packet(block, books):
+    writer = BitWriter()
+    writer.write(0, 1)
+    # the blocks before and after are long too
+    writer.write(1, 1)
+    writer.write(1, 1)
+
+    if block.floor is None:
+        writer.write(0, 1)
+        return writer.finish()
+
+    writer.write(1, 1)
+    value_bits = (FLOOR_RANGE - 1).bit_length()
+    writer.write(block.floor[0], value_bits)
+    writer.write(block.floor[1], value_bits)
+    for value in block.floor[2:]:
+        books[FLOOR_BOOK].write(writer, value)
+
+    vectors = {}
+    for partition, residue_class, entry in block.vectors():
+        vectors.setdefault(partition, []).append((residue_class, entry))
+    for start, word in block.class_words():
+        books[CLASS_BOOK].write(writer, word)
+        for partition in range(start, start + CLASSES_PER_WORD):
+            for residue_class, entry in vectors.get(partition, []):
+                books[FIRST_RESIDUE_BOOK + residue_class - 1].write(writer, entry)
+
+    return writer.finish()
+
+
+# Ogg
+
+
+def crc32(data):
+    crc = 0
+    for byte in data:
+        crc ^= byte << 24
+        for _ in range(8):
+            crc = ((crc << 1) ^ 0x04C11DB7) if crc & 0x80000000 else crc << 1
+            crc &= 0xFFFFFFFF
+    return crc
+
+
+class OggWriter:
+    SERIAL = 0x46495348
+
+    def __init__(self):
+        self.data = bytearray()
+        self.sequence = 0
+
+    def page(self, packets, granule, first=False, last=False):
+        segments = []
+        for packet in packets:
+            segments += [255] * (len(packet) // 255) + [len(packet) % 255]
+        assert len(segments) <= 255
+        flags = (2 if first else 0) | (4 if last else 0)
+        header = b"OggS" + struct.pack(
+            "<BBqIIIB", 0, flags, granule, self.SERIAL, self.sequence, 0, len(segments)
+        )
+        page = bytearray(header + bytes(segments) + b"".join(packets))
+        page[22:26] = struct.pack("<I", crc32(page))
+        self.data += page
+        self.sequence += 1
+
+
+def write_ogg(path, samples, sample_rate, loop=False):
+    """Encodes mono samples between -1 and 1. The sound of a loop continues from its end into its
+    start, which keeps the seam clean."""
+    length = len(samples)
+
+    def sample(i):
+        if loop:
+            return samples[i % length]
+        return samples[i] if 0 <= i < length else 0.0
+
+    # block j covers the samples from (j - 1) * HALF to (j + 1) * HALF
+    block_count = (length + HALF - 1) // HALF + 1
+    blocks = []
+    for j in range(block_count):
+        windowed = [WINDOW[n] * sample((j - 1) * HALF + n) for n in range(BLOCK)]
+        spectrum = [value * 2 / HALF for value in mdct(windowed)]
+        blocks.append(Block(spectrum))
+
+    books = build_codebooks(blocks)
+    packets = [audio_packet(block, books) for block in blocks]
+
+    ogg = OggWriter()
+    ogg.page([identification_header(sample_rate)], 0, first=True)
+    ogg.page([comment_header(), setup_header(books)], 0)
+    # the first block only primes the decoder
+    ogg.page([packets[0]], 0)
+
+    # pages end on whole packets, and the last one says where the sound ends
+    page, segments = [], 0
+    for j, packet in enumerate(packets[1:], start=1):
+        page.append(packet)
+        segments += len(packet) // 255 + 1
+        if j == len(packets) - 1:
+            ogg.page(page, length, last=True)
+        elif segments > 200:
+            ogg.page(page, j * HALF)
+            page, segments = [], 0
+
+    with open(path, "wb") as file:
+        file.write(ogg.data)
@@ -0,0 +1,293 @@
+/**
+Options for players that are sensitive to some of the game's effects or have trouble telling its
+colours apart. Colours that carry meaning are picked from the `Palette` resource, which follows the
+colour palette chosen in the settings. The game speed assist slows the whole simulation down, and
+runs played with it are flagged on the leaderboard.
+*/
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::settings::Settings;
+use crate::shared::{
+    game::{GameRestarted, GameState, GameStates},
+    stages,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub enum ColorPalettes {
+    #[default]
+    Default,
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+    HighContrast,
+}
+
+const COLOR_PALETTES: [ColorPalettes; 5] = [
+    ColorPalettes::Default,
+    ColorPalettes::Deuteranopia,
+    ColorPalettes::Protanopia,
+    ColorPalettes::Tritanopia,
+    ColorPalettes::HighContrast,
+];
+
+impl ColorPalettes {
+    /// The key of the palette's name in the locale files.
+    pub fn text_key(&self) -> &'static str {
+        match self {
+            ColorPalettes::Default => "palette-default",
+            ColorPalettes::Deuteranopia => "palette-deuteranopia",
+            ColorPalettes::Protanopia => "palette-protanopia",
+            ColorPalettes::Tritanopia => "palette-tritanopia",
+            ColorPalettes::HighContrast => "palette-high-contrast",
+        }
+    }
+
+    /// The palette after this one, wrapping back around to the default.
+    pub fn next(&self) -> Self {
+        let index = COLOR_PALETTES.iter().position(|palette| palette == self);
+        COLOR_PALETTES[index.map_or(0, |index| (index + 1) % COLOR_PALETTES.len())]
+    }
+}
+
+/// How fast the game runs. Ordered from fastest to slowest.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Default, Deserialize, Serialize)]
+pub enum GameSpeeds {
+    #[default]
+    Full,
+    ThreeQuarters,
+    Half,
+}
+
+impl GameSpeeds {
+    pub fn relative_speed(&self) -> f32 {
+        match self {
+            GameSpeeds::Full => 1.0,
+            GameSpeeds::ThreeQuarters => 0.75,
+            GameSpeeds::Half => 0.5,
+        }
+    }
+
+    /// The key of the speed's name in the locale files.
+    pub fn text_key(&self) -> &'static str {
+        match self {
+            GameSpeeds::Full => "game-speed-full",
+            GameSpeeds::ThreeQuarters => "game-speed-three-quarters",
+            GameSpeeds::Half => "game-speed-half",
+        }
+    }
+
+    pub fn is_assisted(&self) -> bool {
+        *self != GameSpeeds::Full
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            GameSpeeds::Full => GameSpeeds::ThreeQuarters,
+            GameSpeeds::ThreeQuarters => GameSpeeds::Half,
+            GameSpeeds::Half => GameSpeeds::Full,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AccessibilitySettings {
+    pub screen_shake: bool,
+    pub hit_stop: bool,
+    pub camera_zoom: bool,
+    pub palette: ColorPalettes,
+    pub game_speed: GameSpeeds,
+}
+
+impl Default for AccessibilitySettings {
+    fn default() -> Self {
+        Self {
+            screen_shake: true,
+            hit_stop: true,
+            camera_zoom: true,
+            palette: ColorPalettes::default(),
+            game_speed: GameSpeeds::default(),
+        }
+    }
+}
+
+/// The colours of everything that tells the player something, e.g. that time is running out.
+#[derive(Debug, Clone, Resource)]
+pub struct Palette {
+    pub id: ColorPalettes,
+    pub score: Color,
+    pub high_score: Color,
+    /// Running out of time and losing.
+    pub danger: Color,
+    pub countdown: Color,
+    pub boost_tracker: Color,
+    pub boost_tracker_border: Color,
+    /// Points, combos and unlocks.
+    pub highlight: Color,
+    pub selected: Color,
+    pub slider_fill: Color,
+}
+
+impl Palette {
+    pub fn new(id: ColorPalettes) -> Self {
+        match id {
+            ColorPalettes::Default => Self {
+                id,
+                score: Color::GREEN,
+                high_score: Color::GOLD,
+                danger: Color::RED,
+                countdown: Color::PINK,
+                boost_tracker: Color::PINK,
+                boost_tracker_border: Color::rgb_u8(255, 105, 180),
+                highlight: Color::GOLD,
+                selected: Color::DARK_GREEN,
+                slider_fill: Color::GREEN,
+            },
+            // blues against oranges and yellows, which stay apart without telling red from green
+            ColorPalettes::Deuteranopia => Self {
+                id,
+                score: Color::rgb_u8(86, 180, 233),
+                high_score: Color::rgb_u8(240, 228, 66),
+                danger: Color::rgb_u8(213, 94, 0),
+                countdown: Color::rgb_u8(0, 114, 178),
+                boost_tracker: Color::rgb_u8(0, 114, 178),
+                boost_tracker_border: Color::rgb_u8(0, 60, 100),
+                highlight: Color::rgb_u8(230, 159, 0),
+                selected: Color::rgb_u8(0, 114, 178),
+                slider_fill: Color::rgb_u8(0, 114, 178),
+            },
+            // reds look dark without red cones, so danger is a bright orange instead
+            ColorPalettes::Protanopia => Self {
+                id,
+                score: Color::rgb_u8(86, 180, 233),
+                high_score: Color::rgb_u8(240, 228, 66),
+                danger: Color::rgb_u8(230, 159, 0),
+                countdown: Color::rgb_u8(0, 114, 178),
+                boost_tracker: Color::rgb_u8(0, 114, 178),
+                boost_tracker_border: Color::rgb_u8(0, 60, 100),
+                highlight: Color::rgb_u8(240, 228, 66),
+                selected: Color::rgb_u8(0, 114, 178),
+                slider_fill: Color::rgb_u8(0, 114, 178),
+            },
+            // reds against teals, which stay apart without telling blue from yellow
+            ColorPalettes::Tritanopia => Self {
+                id,
+                score: Color::rgb_u8(0, 158, 115),
+                high_score: Color::rgb_u8(204, 121, 167),
+                danger: Color::rgb_u8(200, 20, 20),
+                countdown: Color::rgb_u8(0, 110, 110),
+                boost_tracker: Color::rgb_u8(0, 110, 110),
+                boost_tracker_border: Color::rgb_u8(0, 60, 60),
+                highlight: Color::rgb_u8(204, 121, 167),
+                selected: Color::rgb_u8(0, 110, 110),
+                slider_fill: Color::rgb_u8(0, 158, 115),
+            },
+            // dark, saturated colours that stand out against the light sky and water
+            ColorPalettes::HighContrast => Self {
+                id,
+                score: Color::BLACK,
+                high_score: Color::rgb_u8(0, 0, 170),
+                danger: Color::rgb_u8(170, 0, 0),
+                countdown: Color::BLACK,
+                boost_tracker: Color::BLACK,
+                boost_tracker_border: Color::WHITE,
+                highlight: Color::rgb_u8(120, 0, 120),
+                selected: Color::rgb_u8(0, 0, 170),
+                slider_fill: Color::BLACK,
+            },
+        }
+    }
+}
+
+impl FromWorld for Palette {
+    fn from_world(world: &mut World) -> Self {
+        let palette = world
+            .get_resource::<Settings>()
+            .map(|settings| settings.accessibility.palette)
+            .unwrap_or_default();
+        Palette::new(palette)
+    }
+}
+
+/// The slowest game speed used since the run started, which is what the run gets flagged with.
+#[derive(Debug, Default, Resource)]
+pub struct RunAssists {
+    pub game_speed: GameSpeeds,
+}
+
+pub struct AccessibilityPlugin;
+
+impl Plugin for AccessibilityPlugin {
+    fn build(&self, app: &mut App) {
+        debug!("Building accessibility plugin...");
+        app.init_resource::<Palette>()
+            .init_resource::<RunAssists>()
+            .add_systems(
+                Update,
+                (apply_game_speed_system, update_palette_system).in_set(stages::HandleEventsSet),
+            );
+    }
+}
+
+/// Scales game time by the game speed assist, and remembers the slowest speed each run used.
+fn apply_game_speed_system(
+    settings: Res<Settings>,
+    game_state: Res<GameState>,
+    mut restart_reader: EventReader<GameRestarted>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut run_assists: ResMut<RunAssists>,
+) {
+    let game_speed = settings.accessibility.game_speed;
+
+    if restart_reader.read().next().is_some() {
+        run_assists.game_speed = game_speed;
+    }
+
+    if !settings.is_changed() {
+        return;
+    }
+
+    if virtual_time.relative_speed() != game_speed.relative_speed() {
+        debug!("Running the game at {:?} speed", game_speed);
+        virtual_time.set_relative_speed(game_speed.relative_speed());
+    }
+
+    // slowing down for part of a run still counts
+    if let GameStates::Running | GameStates::Paused = game_state.cur_state {
+        run_assists.game_speed = run_assists.game_speed.max(game_speed);
+    }
+}
+
+fn update_palette_system(settings: Res<Settings>, mut palette: ResMut<Palette>) {
+    let id = settings.accessibility.palette;
+    if settings.is_changed() && palette.id != id {
+        debug!("Switching to the {:?} colour palette", id);
+        *palette = Palette::new(id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn palettes_cycle_back_to_the_default() {
+        let mut palette = ColorPalettes::Default;
+        for _ in 0..COLOR_PALETTES.len() {
+            palette = palette.next();
+        }
+        assert_eq!(palette, ColorPalettes::Default);
+        assert_eq!(ColorPalettes::Default.next(), ColorPalettes::Deuteranopia);
+    }
+
+    #[test]
+    fn slower_speeds_win_when_flagging_runs() {
+        assert_eq!(GameSpeeds::Full.max(GameSpeeds::Half), GameSpeeds::Half);
+        assert_eq!(
+            GameSpeeds::Half.max(GameSpeeds::ThreeQuarters),
+            GameSpeeds::Half
+        );
+        assert!(!GameSpeeds::Full.is_assisted());
+    }
+}
@@ -0,0 +1,286 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+
+use crate::objects::boat::BoatTypes;
+use crate::player::events::{PlayerAte, PlayerBonked, PlayerBoosted, PlayerHooked, PlayerStarved};
+use crate::shared::{
+    game::{GameOver, GameRestarted, GameState, Score},
+    stages, storage,
+};
+use crate::stats::LifetimeStats;
+
+const ACHIEVEMENTS_KEY: &str = "achievements";
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub enum AchievementId {
+    FirstBite,
+    FeedingFrenzy,
+    WormHoarder,
+    SteadyFins,
+    Century,
+    Fasting,
+    Caught,
+    YachtRock,
+    StarvingArtist,
+}
+
+#[derive(Debug)]
+pub struct AchievementDefinition {
+    pub id: AchievementId,
+    /// Keys of the name and the description in the locale files.
+    pub name_key: &'static str,
+    pub description_key: &'static str,
+}
+
+pub const ACHIEVEMENTS: &[AchievementDefinition] = &[
+    AchievementDefinition {
+        id: AchievementId::FirstBite,
+        name_key: "achievement-first-bite",
+        description_key: "achievement-first-bite-description",
+    },
+    AchievementDefinition {
+        id: AchievementId::FeedingFrenzy,
+        name_key: "achievement-feeding-frenzy",
+        description_key: "achievement-feeding-frenzy-description",
+    },
+    AchievementDefinition {
+        id: AchievementId::WormHoarder,
+        name_key: "achievement-worm-hoarder",
+        description_key: "achievement-worm-hoarder-description",
+    },
+    AchievementDefinition {
+        id: AchievementId::SteadyFins,
+        name_key: "achievement-steady-fins",
+        description_key: "achievement-steady-fins-description",
+    },
+    AchievementDefinition {
+        id: AchievementId::Century,
+        name_key: "achievement-century",
+        description_key: "achievement-century-description",
+    },
+    AchievementDefinition {
+        id: AchievementId::Fasting,
+        name_key: "achievement-fasting",
+        description_key: "achievement-fasting-description",
+    },
+    AchievementDefinition {
+        id: AchievementId::Caught,
+        name_key: "achievement-caught",
+        description_key: "achievement-caught-description",
+    },
+    AchievementDefinition {
+        id: AchievementId::YachtRock,
+        name_key: "achievement-yacht-rock",
+        description_key: "achievement-yacht-rock-description",
+    },
+    AchievementDefinition {
+        id: AchievementId::StarvingArtist,
+        name_key: "achievement-starving-artist",
+        description_key: "achievement-starving-artist-description",
+    },
+];
+
+pub fn achievement_definition(id: AchievementId) -> &'static AchievementDefinition {
+    ACHIEVEMENTS
+        .iter()
+        .find(|definition| definition.id == id)
+        .expect("Every achievement id should have a definition")
+}
+
+const FEEDING_FRENZY_WORMS: usize = 3;
+const FEEDING_FRENZY_WINDOW: f32 = 5.0;
+const WORM_HOARDER_WORMS: u32 = 100;
+const STEADY_FINS_TIME: f32 = 60.0;
+const CENTURY_SCORE: u32 = 100;
+const FASTING_SCORE: u32 = 30;
+
+/// The achievements that have been unlocked, saved between sessions.
+#[derive(Debug, Default, Deserialize, Serialize, Resource)]
+pub struct AchievementProgress {
+    pub unlocked: HashSet<AchievementId>,
+}
+
+impl AchievementProgress {
+    fn load() -> Self {
+        storage::load_json(ACHIEVEMENTS_KEY).unwrap_or_default()
+    }
+
+    fn save(&self) {
+        storage::save_json(ACHIEVEMENTS_KEY, self);
+    }
+
+    /// Unlocks the achievement and announces it if it has not already been unlocked.
+    fn unlock(
+        &mut self,
+        id: AchievementId,
+        achievement_unlocked_events: &mut EventWriter<AchievementUnlocked>,
+    ) {
+        if self.unlocked.insert(id) {
+            debug!("Unlocked achievement {:?}", id);
+            self.save();
+            achievement_unlocked_events.send(AchievementUnlocked { id });
+        }
+    }
+}
+
+/// Keeps track of what happened during the current run for achievements that span a run.
+#[derive(Debug, Default, Resource)]
+struct RunTracker {
+    elapsed: f32,
+    time_since_boost: f32,
+    worms_eaten: u32,
+    /// When the most recent worms were eaten, in seconds since the start of the run.
+    recent_worm_times: VecDeque<f32>,
+}
+
+#[derive(Debug, Event)]
+pub struct AchievementUnlocked {
+    pub id: AchievementId,
+}
+
+pub struct AchievementsPlugin;
+
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        debug!("Building achievements plugin...");
+        app.insert_resource(AchievementProgress::load())
+            .init_resource::<RunTracker>()
+            .add_event::<AchievementUnlocked>()
+            .add_systems(
+                Update,
+                (track_run_system, eating_achievements_system)
+                    .chain()
+                    .in_set(stages::HandleEventsSet),
+            )
+            .add_systems(
+                Update,
+                (
+                    run_achievements_system,
+                    game_over_achievements_system,
+                    death_achievements_system,
+                )
+                    .after(track_run_system)
+                    .in_set(stages::HandleEventsSet),
+            );
+    }
+}
+
+fn track_run_system(
+    time: Res<Time>,
+    game_state: Res<GameState>,
+    mut restart_reader: EventReader<GameRestarted>,
+    mut player_boosted_reader: EventReader<PlayerBoosted>,
+    mut run_tracker: ResMut<RunTracker>,
+) {
+    if restart_reader.read().next().is_some() {
+        *run_tracker = RunTracker::default();
+    }
+
+    if player_boosted_reader.read().next().is_some() {
+        run_tracker.time_since_boost = 0.0;
+    }
+
+    if game_state.is_running() {
+        run_tracker.elapsed += time.delta_seconds();
+        run_tracker.time_since_boost += time.delta_seconds();
+    }
+}
+
+fn eating_achievements_system(
+    mut player_ate_reader: EventReader<PlayerAte>,
+    mut run_tracker: ResMut<RunTracker>,
+    mut progress: ResMut<AchievementProgress>,
+    mut achievement_unlocked_events: EventWriter<AchievementUnlocked>,
+) {
+    for _ in player_ate_reader.read() {
+        let now = run_tracker.elapsed;
+        run_tracker.worms_eaten += 1;
+        run_tracker.recent_worm_times.push_back(now);
+
+        while run_tracker
+            .recent_worm_times
+            .front()
+            .is_some_and(|&eaten_at| now - eaten_at > FEEDING_FRENZY_WINDOW)
+        {
+            run_tracker.recent_worm_times.pop_front();
+        }
+
+        progress.unlock(AchievementId::FirstBite, &mut achievement_unlocked_events);
+
+        if run_tracker.recent_worm_times.len() >= FEEDING_FRENZY_WORMS {
+            progress.unlock(
+                AchievementId::FeedingFrenzy,
+                &mut achievement_unlocked_events,
+            );
+        }
+    }
+}
+
+/// Checks achievements that depend on progress being made over time.
+fn run_achievements_system(
+    game_state: Res<GameState>,
+    score: Res<Score>,
+    stats: Res<LifetimeStats>,
+    run_tracker: Res<RunTracker>,
+    mut progress: ResMut<AchievementProgress>,
+    mut achievement_unlocked_events: EventWriter<AchievementUnlocked>,
+) {
+    if !game_state.is_running() {
+        return;
+    }
+
+    if run_tracker.time_since_boost >= STEADY_FINS_TIME {
+        progress.unlock(AchievementId::SteadyFins, &mut achievement_unlocked_events);
+    }
+
+    if score.count >= CENTURY_SCORE {
+        progress.unlock(AchievementId::Century, &mut achievement_unlocked_events);
+    }
+
+    if stats.worms_eaten >= WORM_HOARDER_WORMS {
+        progress.unlock(AchievementId::WormHoarder, &mut achievement_unlocked_events);
+    }
+}
+
+fn game_over_achievements_system(
+    score: Res<Score>,
+    run_tracker: Res<RunTracker>,
+    mut game_over_reader: EventReader<GameOver>,
+    mut progress: ResMut<AchievementProgress>,
+    mut achievement_unlocked_events: EventWriter<AchievementUnlocked>,
+) {
+    if game_over_reader.read().next().is_none() {
+        return;
+    }
+
+    if run_tracker.worms_eaten == 0 && score.count >= FASTING_SCORE {
+        progress.unlock(AchievementId::Fasting, &mut achievement_unlocked_events);
+    }
+}
+
+fn death_achievements_system(
+    mut player_hooked_reader: EventReader<PlayerHooked>,
+    mut player_bonked_reader: EventReader<PlayerBonked>,
+    mut player_starved_reader: EventReader<PlayerStarved>,
+    boat_query: Query<&BoatTypes>,
+    mut progress: ResMut<AchievementProgress>,
+    mut achievement_unlocked_events: EventWriter<AchievementUnlocked>,
+) {
+    if player_hooked_reader.read().next().is_some() {
+        progress.unlock(AchievementId::Caught, &mut achievement_unlocked_events);
+    }
+
+    for bonked_event in player_bonked_reader.read() {
+        if let Ok(BoatTypes::Yacht) = boat_query.get(bonked_event.boat_entity) {
+            progress.unlock(AchievementId::YachtRock, &mut achievement_unlocked_events);
+        }
+    }
+
+    if player_starved_reader.read().next().is_some() {
+        progress.unlock(
+            AchievementId::StarvingArtist,
+            &mut achievement_unlocked_events,
+        );
+    }
+}
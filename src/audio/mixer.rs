@@ -0,0 +1,100 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::sfx::SfxVolume;
+use crate::settings::Settings;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum AudioBuses {
+    Master,
+    Music,
+    Sfx,
+}
+
+/// Volume of every bus and whether all audio is muted. Volumes range from 0 to 1 and the music and
+/// sfx buses are scaled by the master bus.
+#[derive(Debug, Copy, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub(crate) struct AudioSettings {
+    pub master: f32,
+    pub music: f32,
+    pub sfx: f32,
+    pub muted: bool,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master: 1.0,
+            music: 0.6,
+            sfx: 1.0,
+            muted: false,
+        }
+    }
+}
+
+impl AudioSettings {
+    pub(crate) fn bus_volume(&self, bus: AudioBuses) -> f32 {
+        match bus {
+            AudioBuses::Master => self.master,
+            AudioBuses::Music => self.music,
+            AudioBuses::Sfx => self.sfx,
+        }
+    }
+
+    pub(crate) fn set_bus_volume(&mut self, bus: AudioBuses, volume: f32) {
+        let volume = volume.clamp(0.0, 1.0);
+
+        match bus {
+            AudioBuses::Master => self.master = volume,
+            AudioBuses::Music => self.music = volume,
+            AudioBuses::Sfx => self.sfx = volume,
+        }
+    }
+
+    /// The volume that sounds on the bus should actually be played at.
+    pub(super) fn output_volume(&self, bus: AudioBuses) -> f32 {
+        if self.muted {
+            return 0.0;
+        }
+
+        match bus {
+            AudioBuses::Master => self.master,
+            _ => self.master * self.bus_volume(bus),
+        }
+    }
+}
+
+pub(super) fn mute_toggle_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<Settings>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyM) {
+        settings.audio.muted = !settings.audio.muted;
+        debug!("Audio muted: {:?}", settings.audio.muted);
+        settings.save();
+    }
+}
+
+/// Applies volume changes to sound effects that are already playing. Music applies the volume of its
+/// bus itself while fading.
+pub(super) fn apply_sfx_volume_system(
+    settings: Res<Settings>,
+    sink_query: Query<(&SfxVolume, Option<&AudioSink>, Option<&SpatialAudioSink>)>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    let bus_volume = settings.audio.output_volume(AudioBuses::Sfx);
+
+    for (sfx_volume, audio_sink, spatial_audio_sink) in sink_query.iter() {
+        if let Some(audio_sink) = audio_sink {
+            audio_sink.set_volume(sfx_volume.0 * bus_volume);
+        }
+
+        if let Some(spatial_audio_sink) = spatial_audio_sink {
+            spatial_audio_sink.set_volume(sfx_volume.0 * bus_volume);
+        }
+    }
+}
@@ -1,16 +1,56 @@
 use bevy::prelude::*;
 
+use crate::objects::boat::Boat;
+use crate::player::events::{
+    PlayerAte, PlayerBonked, PlayerBoosted, PlayerHooked, PlayerStarved, PlayerStunned,
+};
 use crate::shared::stages;
+use sfx::SoundCuesAppExt;
 
-mod sfx;
+pub(crate) mod mixer;
+mod music;
+pub(crate) mod sfx;
 
 pub struct AudioPlugin;
 
 impl Plugin for AudioPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<sfx::SfxHandles>().add_systems(
-            Update,
-            (sfx::play_sfx_system).in_set(stages::PrepareRenderSet),
-        );
+        app.init_asset::<sfx::SoundBank>()
+            .init_asset_loader::<sfx::SoundBankLoader>()
+            .init_resource::<sfx::SoundBankHandle>()
+            .init_resource::<sfx::SoundCooldowns>()
+            .add_event::<sfx::PlaySound>()
+            .add_event_sound::<PlayerHooked>("player_hooked")
+            .add_event_sound::<PlayerStarved>("player_starved")
+            .add_event_sound::<PlayerBonked>("player_bonked")
+            .add_event_sound::<PlayerStunned>("player_stunned")
+            .add_event_sound::<PlayerAte>("player_ate")
+            .add_event_sound::<PlayerBoosted>("player_boosted")
+            .add_spawn_sound::<Boat>("boat_entered")
+            .add_systems(
+                Startup,
+                (music::spawn_music_layers, sfx::spawn_sfx_listener),
+            )
+            .add_systems(
+                Update,
+                (mixer::mute_toggle_system).in_set(stages::HandleEventsSet),
+            )
+            .add_systems(
+                Update,
+                (
+                    sfx::trigger_animation_sounds_system,
+                    sfx::play_sounds_system,
+                )
+                    .chain()
+                    .in_set(stages::PrepareRenderSet),
+            )
+            .add_systems(
+                Update,
+                (
+                    mixer::apply_sfx_volume_system,
+                    (music::update_music_targets_system, music::fade_music_system).chain(),
+                )
+                    .in_set(stages::PrepareRenderSet),
+            );
     }
 }
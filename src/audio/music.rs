@@ -0,0 +1,123 @@
+use bevy::audio::{PlaybackMode, Volume};
+use bevy::prelude::*;
+
+use super::mixer::AudioBuses;
+use crate::player::attributes::HungerCountdown;
+use crate::settings::Settings;
+use crate::shared::game::{Difficulty, GameState, GameStates, MAX_DIFFICULTY};
+
+/// How long it takes for a track to fade completely in or out.
+const CROSSFADE_TIME: f32 = 1.5;
+/// How loud the play tracks are while the game is paused.
+const PAUSED_DUCK_VOLUME: f32 = 0.3;
+/// How little time the player has left before starving when the danger stem starts fading in.
+const HUNGER_DANGER_TIME: f32 = 5.0;
+
+/// Every looping track that makes up the music. The play track is split into stems that are
+/// layered on top of each other to change the intensity of the music.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum MusicLayers {
+    Menu,
+    PlayBase,
+    /// Fades in as the difficulty rises.
+    PlayIntensity,
+    /// Fades in as the player gets close to starving.
+    PlayDanger,
+    GameOver,
+}
+
+const MUSIC_LAYERS: [(MusicLayers, &str); 5] = [
+    (MusicLayers::Menu, "audio/music/menu.ogg"),
+    (MusicLayers::PlayBase, "audio/music/play_base.ogg"),
+    (MusicLayers::PlayIntensity, "audio/music/play_intensity.ogg"),
+    (MusicLayers::PlayDanger, "audio/music/play_danger.ogg"),
+    (MusicLayers::GameOver, "audio/music/game_over.ogg"),
+];
+
+#[derive(Debug, Component)]
+pub(super) struct MusicLayer {
+    layer: MusicLayers,
+    volume: f32,
+    target_volume: f32,
+}
+
+/// Starts every layer at the same time so that the stems of the play track stay in sync. Layers
+/// that shouldn't be heard are kept at zero volume.
+pub(super) fn spawn_music_layers(mut commands: Commands, asset_server: Res<AssetServer>) {
+    for (layer, path) in MUSIC_LAYERS {
+        commands.spawn((
+            AudioBundle {
+                source: asset_server.load(path),
+                settings: PlaybackSettings {
+                    mode: PlaybackMode::Loop,
+                    volume: Volume::new(0.0),
+                    ..Default::default()
+                },
+            },
+            MusicLayer {
+                layer,
+                volume: 0.0,
+                target_volume: 0.0,
+            },
+        ));
+    }
+}
+
+/// Decides how loud every layer should be based on the state of the game.
+pub(super) fn update_music_targets_system(
+    game_state: Res<GameState>,
+    difficulty: Res<Difficulty>,
+    hunger_query: Query<&HungerCountdown>,
+    mut layer_query: Query<&mut MusicLayer>,
+) {
+    let intensity = (difficulty.multiplier.saturating_sub(1)) as f32 / (MAX_DIFFICULTY - 1) as f32;
+
+    let danger = hunger_query
+        .iter()
+        .map(|hunger_countdown| 1.0 - hunger_countdown.time_left / HUNGER_DANGER_TIME)
+        .fold(0.0, f32::max)
+        .clamp(0.0, 1.0);
+
+    let play_volume = match game_state.cur_state {
+        GameStates::Running => 1.0,
+        GameStates::Paused => PAUSED_DUCK_VOLUME,
+        GameStates::Menu | GameStates::GameOver => 0.0,
+    };
+
+    for mut music_layer in layer_query.iter_mut() {
+        music_layer.target_volume = match music_layer.layer {
+            MusicLayers::Menu => match game_state.cur_state {
+                GameStates::Menu => 1.0,
+                _ => 0.0,
+            },
+            MusicLayers::PlayBase => play_volume,
+            MusicLayers::PlayIntensity => play_volume * intensity,
+            MusicLayers::PlayDanger => play_volume * danger,
+            MusicLayers::GameOver => match game_state.cur_state {
+                GameStates::GameOver => 1.0,
+                _ => 0.0,
+            },
+        };
+    }
+}
+
+/// Moves every layer towards its target volume, which crossfades between tracks when the state of
+/// the game changes. Uses real time so that fading still happens while the game is paused.
+pub(super) fn fade_music_system(
+    time: Res<Time<Real>>,
+    settings: Res<Settings>,
+    mut layer_query: Query<(&mut MusicLayer, Option<&AudioSink>)>,
+) {
+    let max_change = time.delta_seconds() / CROSSFADE_TIME;
+
+    for (mut music_layer, audio_sink) in layer_query.iter_mut() {
+        let difference = music_layer.target_volume - music_layer.volume;
+        music_layer.volume += difference.clamp(-max_change, max_change);
+
+        // The sink is only added once the track has finished loading
+        if let Some(audio_sink) = audio_sink {
+            audio_sink
+                .set_volume(music_layer.volume * settings.audio.output_volume(AudioBuses::Music));
+        }
+    }
+}
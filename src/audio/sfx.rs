@@ -1,77 +1,328 @@
+use bevy::asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext};
+use bevy::audio::Volume;
 use bevy::prelude::*;
+use bevy::utils::BoxedFuture;
+use rand::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::marker::PhantomData;
 
-use crate::player::events::{PlayerAte, PlayerBonked, PlayerBoosted, PlayerHooked, PlayerStarved};
+use super::mixer::AudioBuses;
+use crate::player::events::{
+    PlayerAte, PlayerBonked, PlayerBoosted, PlayerHooked, PlayerStarved, PlayerStunned,
+};
+use crate::settings::Settings;
+use crate::shared::{animation::AnimationSound, arena::Arena, stages};
 
-#[derive(Debug, Resource)]
-pub(super) struct SfxHandles {
-    bonked: Handle<AudioSource>,
-    hooked: Handle<AudioSource>,
-    starved: Handle<AudioSource>,
-    eat: Handle<AudioSource>,
-    boost: Handle<AudioSource>,
+const SOUND_BANK_PATH: &str = "audio/sfx.soundbank.json";
+
+/// Asks for a sound cue to be played. Cues are named in the sound bank, and cues without an entry
+/// in the sound bank are silent. Sounds with a position are panned based on where the position is
+/// in the arena.
+#[derive(Debug, Event)]
+pub(crate) struct PlaySound {
+    pub cue: String,
+    pub position: Option<Vec2>,
+}
+
+/// A game event that can be heard.
+pub(crate) trait SoundEvent: Event {
+    /// The entity that makes the sound, which the sound is panned toward.
+    fn source(&self) -> Option<Entity>;
+}
+
+impl SoundEvent for PlayerHooked {
+    fn source(&self) -> Option<Entity> {
+        Some(self.player_entity)
+    }
+}
+
+impl SoundEvent for PlayerStarved {
+    fn source(&self) -> Option<Entity> {
+        Some(self.player_entity)
+    }
+}
+
+impl SoundEvent for PlayerBonked {
+    fn source(&self) -> Option<Entity> {
+        Some(self.player_entity)
+    }
+}
+
+impl SoundEvent for PlayerStunned {
+    fn source(&self) -> Option<Entity> {
+        Some(self.player_entity)
+    }
+}
+
+impl SoundEvent for PlayerAte {
+    fn source(&self) -> Option<Entity> {
+        Some(self.player_entity)
+    }
+}
+
+impl SoundEvent for PlayerBoosted {
+    fn source(&self) -> Option<Entity> {
+        Some(self.player)
+    }
+}
+
+/// The sound bank cue that is played for an event or for a component being added.
+#[derive(Resource)]
+struct SoundCueName<T> {
+    cue: &'static str,
+    source: PhantomData<T>,
+}
+
+/// Connects what happens in the game to sound bank cues. Once connected, the sounds of a cue are
+/// changed in the sound bank alone.
+pub(crate) trait SoundCuesAppExt {
+    /// Plays the cue whenever the event is sent.
+    fn add_event_sound<E: SoundEvent>(&mut self, cue: &'static str) -> &mut Self;
+
+    /// Plays the cue whenever an entity gets the component, like when it is spawned.
+    fn add_spawn_sound<C: Component>(&mut self, cue: &'static str) -> &mut Self;
+}
+
+impl SoundCuesAppExt for App {
+    fn add_event_sound<E: SoundEvent>(&mut self, cue: &'static str) -> &mut Self {
+        self.insert_resource(SoundCueName::<E> {
+            cue,
+            source: PhantomData,
+        })
+        .add_systems(
+            Update,
+            (trigger_event_sounds_system::<E>)
+                .before(play_sounds_system)
+                .in_set(stages::PrepareRenderSet),
+        )
+    }
+
+    fn add_spawn_sound<C: Component>(&mut self, cue: &'static str) -> &mut Self {
+        self.insert_resource(SoundCueName::<C> {
+            cue,
+            source: PhantomData,
+        })
+        .add_systems(
+            Update,
+            (trigger_spawn_sounds_system::<C>)
+                .before(play_sounds_system)
+                .in_set(stages::PrepareRenderSet),
+        )
+    }
+}
+
+fn default_volume() -> f32 {
+    1.0
+}
+
+/// How a sound cue is written in the sound bank file.
+#[derive(Debug, Deserialize)]
+struct SoundCueDefinition {
+    /// Paths to the sounds to randomly pick from.
+    sounds: Vec<String>,
+    #[serde(default = "default_volume")]
+    volume: f32,
+    /// How far the playback speed can randomly stray from normal speed, which changes the pitch.
+    #[serde(default)]
+    pitch_jitter: f32,
+    /// Minimum time in seconds between two plays of the cue.
+    #[serde(default)]
+    cooldown: f32,
+}
+
+#[derive(Debug)]
+struct SoundCue {
+    sounds: Vec<Handle<AudioSource>>,
+    volume: f32,
+    pitch_jitter: f32,
+    cooldown: f32,
+}
+
+#[derive(Debug, Asset, TypePath)]
+pub(super) struct SoundBank {
+    cues: HashMap<String, SoundCue>,
+}
+
+#[derive(Default)]
+pub(super) struct SoundBankLoader;
+
+impl AssetLoader for SoundBankLoader {
+    type Asset = SoundBank;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a Self::Settings,
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+
+            let definitions: HashMap<String, SoundCueDefinition> = serde_json::from_slice(&bytes)?;
+
+            let cues = definitions
+                .into_iter()
+                .map(|(name, definition)| {
+                    let cue = SoundCue {
+                        sounds: definition
+                            .sounds
+                            .into_iter()
+                            .map(|path| load_context.load(path))
+                            .collect(),
+                        volume: definition.volume,
+                        pitch_jitter: definition.pitch_jitter,
+                        cooldown: definition.cooldown,
+                    };
+                    (name, cue)
+                })
+                .collect();
+
+            Ok(SoundBank { cues })
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["soundbank.json"]
+    }
 }
 
-impl FromWorld for SfxHandles {
+#[derive(Debug, Resource)]
+pub(super) struct SoundBankHandle(Handle<SoundBank>);
+
+impl FromWorld for SoundBankHandle {
     fn from_world(world: &mut World) -> Self {
-        let asset_server = world.get_resource_mut::<AssetServer>().unwrap();
-        Self {
-            bonked: asset_server.load("audio/sfx/bonked.ogg"),
-            hooked: asset_server.load("audio/sfx/hooked.ogg"),
-            starved: asset_server.load("audio/sfx/starved.ogg"),
-            eat: asset_server.load("audio/sfx/eat.ogg"),
-            boost: asset_server.load("audio/sfx/boost.ogg"),
-        }
+        let asset_server = world.get_resource::<AssetServer>().unwrap();
+        debug!("Loading sound bank...");
+        Self(asset_server.load(SOUND_BANK_PATH))
     }
 }
 
-// TODO: See if this can be reduced
-#[allow(clippy::too_many_arguments)]
-pub(super) fn play_sfx_system(
-    mut player_hooked_reader: EventReader<PlayerHooked>,
-    mut player_starved_reader: EventReader<PlayerStarved>,
-    mut player_bonked_reader: EventReader<PlayerBonked>,
-    mut player_ate_reader: EventReader<PlayerAte>,
-    mut player_boosted_reader: EventReader<PlayerBoosted>,
-    sfx_handles: Res<SfxHandles>,
-    mut commands: Commands,
+/// When each cue was last played, in seconds since startup.
+#[derive(Debug, Default, Resource)]
+pub(super) struct SoundCooldowns {
+    last_played: HashMap<String, f32>,
+}
+
+/// How loud a playing sound effect is before the mixer is applied.
+#[derive(Debug, Component)]
+pub(super) struct SfxVolume(pub f32);
+
+pub(super) fn spawn_sfx_listener(mut commands: Commands) {
+    // Sound positions are mapped between the ears so that the left and right edges of the arena
+    // are fully panned.
+    commands.spawn((SpatialListener::new(2.0), TransformBundle::default()));
+}
+
+fn position_of(entity: Entity, transform_query: &Query<&GlobalTransform>) -> Option<Vec2> {
+    transform_query
+        .get(entity)
+        .ok()
+        .map(|transform| transform.translation().truncate())
+}
+
+fn trigger_event_sounds_system<E: SoundEvent>(
+    cue_name: Res<SoundCueName<E>>,
+    mut event_reader: EventReader<E>,
+    transform_query: Query<&GlobalTransform>,
+    mut play_sound_events: EventWriter<PlaySound>,
 ) {
-    for _ in player_hooked_reader.read() {
-        debug!("Playing hooked sound effect");
-        commands.spawn(AudioBundle {
-            source: sfx_handles.hooked.clone(),
-            settings: PlaybackSettings::DESPAWN,
+    for event in event_reader.read() {
+        play_sound_events.send(PlaySound {
+            cue: cue_name.cue.to_string(),
+            position: event
+                .source()
+                .and_then(|entity| position_of(entity, &transform_query)),
         });
     }
+}
 
-    for _ in player_starved_reader.read() {
-        debug!("Playing starved sound effect");
-        commands.spawn(AudioBundle {
-            source: sfx_handles.starved.clone(),
-            settings: PlaybackSettings::DESPAWN,
+fn trigger_spawn_sounds_system<C: Component>(
+    cue_name: Res<SoundCueName<C>>,
+    added_query: Query<&Transform, Added<C>>,
+    mut play_sound_events: EventWriter<PlaySound>,
+) {
+    // the global transforms of new entities are only worked out at the end of the frame
+    for transform in added_query.iter() {
+        play_sound_events.send(PlaySound {
+            cue: cue_name.cue.to_string(),
+            position: Some(transform.translation.truncate()),
         });
     }
+}
 
-    for _ in player_bonked_reader.read() {
-        debug!("Playing bonked sound effect");
-        commands.spawn(AudioBundle {
-            source: sfx_handles.bonked.clone(),
-            settings: PlaybackSettings::DESPAWN,
+pub(super) fn trigger_animation_sounds_system(
+    mut animation_sound_reader: EventReader<AnimationSound>,
+    transform_query: Query<&GlobalTransform>,
+    mut play_sound_events: EventWriter<PlaySound>,
+) {
+    for animation_sound in animation_sound_reader.read() {
+        play_sound_events.send(PlaySound {
+            cue: animation_sound.sound.clone(),
+            position: position_of(animation_sound.entity, &transform_query),
         });
     }
+}
 
-    for _ in player_ate_reader.read() {
-        debug!("Playing ate sound effect");
-        commands.spawn(AudioBundle {
-            source: sfx_handles.eat.clone(),
-            settings: PlaybackSettings::DESPAWN,
-        });
-    }
+#[allow(clippy::too_many_arguments)]
+pub(super) fn play_sounds_system(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    arena: Res<Arena>,
+    settings: Res<Settings>,
+    sound_bank_handle: Res<SoundBankHandle>,
+    sound_banks: Res<Assets<SoundBank>>,
+    mut cooldowns: ResMut<SoundCooldowns>,
+    mut play_sound_reader: EventReader<PlaySound>,
+) {
+    let Some(sound_bank) = sound_banks.get(&sound_bank_handle.0) else {
+        return;
+    };
 
-    for _ in player_boosted_reader.read() {
-        debug!("Playing boosted sound effect");
-        commands.spawn(AudioBundle {
-            source: sfx_handles.boost.clone(),
-            settings: PlaybackSettings::DESPAWN,
-        });
+    let mut rng = thread_rng();
+    let now = time.elapsed_seconds();
+
+    for play_sound in play_sound_reader.read() {
+        let Some(cue) = sound_bank.cues.get(&play_sound.cue) else {
+            continue;
+        };
+
+        if let Some(last_played) = cooldowns.last_played.get(&play_sound.cue) {
+            if now - last_played < cue.cooldown {
+                continue;
+            }
+        }
+
+        let Some(sound) = cue.sounds.choose(&mut rng) else {
+            continue;
+        };
+
+        debug!("Playing sound effect {}", play_sound.cue);
+        cooldowns.last_played.insert(play_sound.cue.clone(), now);
+
+        let speed = 1.0 + rng.gen_range(-1.0..=1.0) * cue.pitch_jitter;
+        let mut playback_settings = PlaybackSettings::DESPAWN
+            .with_volume(Volume::new(
+                cue.volume * settings.audio.output_volume(AudioBuses::Sfx),
+            ))
+            .with_speed(speed);
+
+        let mut pan = 0.0;
+        if let Some(position) = play_sound.position {
+            playback_settings = playback_settings.with_spatial(true);
+            pan = (position.x / (arena.width / 2.0)).clamp(-1.0, 1.0);
+        }
+
+        commands.spawn((
+            AudioBundle {
+                source: sound.clone(),
+                settings: playback_settings,
+            },
+            TransformBundle::from_transform(Transform::from_xyz(pan, 0.0, 0.0)),
+            SfxVolume(cue.volume),
+        ));
     }
 }
@@ -0,0 +1,421 @@
+/**
+Scenery behind the arena. The water is drawn as a gradient that gets darker with depth, with
+layers of rocks, seaweed and distant fish in front of it that shift with the fish to give a sense of
+depth. Over the course of a run the sky and water cycle from day to night.
+*/
+use bevy::prelude::*;
+use bevy_prototype_lyon::prelude::*;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use std::f32::consts::PI;
+
+use crate::player::attributes::Player;
+use crate::shared::{
+    arena::{self, Arena},
+    game::{GameRestarted, GameState},
+    render::{LayerOrder, RenderLayer},
+    stages,
+};
+
+/// The scenery is laid out the same way every time, independent of the seed of the run.
+const SCENERY_SEED: u64 = 7;
+
+const GRADIENT_BANDS: u32 = 12;
+const SURFACE_WATER_COLOR: Color = Color::rgb(0.68, 0.85, 0.9);
+const DEEP_WATER_COLOR: Color = Color::rgb(0.16, 0.35, 0.55);
+
+/// How many seconds a full cycle from day to night and back takes.
+const DAY_LENGTH: f32 = 120.0;
+const DAY_SKY_COLOR: Color = Color::rgb(0.9, 0.79, 0.68);
+const NIGHT_SKY_COLOR: Color = Color::rgb(0.1, 0.1, 0.22);
+/// Multiplied with the colors of everything underwater at the darkest point of the night.
+const NIGHT_WATER_TINT: Color = Color::rgb(0.35, 0.4, 0.6);
+
+/// How far around the fish can be seen at the darkest point of the night.
+const NIGHT_VISIBILITY_RADIUS: f32 = 120.0;
+const DAY_VISIBILITY_RADIUS: f32 = 800.0;
+const MAX_DARKNESS_ALPHA: f32 = 0.85;
+/// The darkness is drawn as a thick ring built with this inner radius and scaled to the visibility.
+const DARKNESS_INNER_RADIUS: f32 = 100.0;
+const DARKNESS_RING_WIDTH: f32 = 1000.0;
+
+const SEAWEED_SWAY_SPEED: f32 = 1.5;
+const SEAWEED_SWAY_ANGLE: f32 = 0.08;
+
+pub struct BackgroundPlugin;
+
+impl Plugin for BackgroundPlugin {
+    fn build(&self, app: &mut App) {
+        debug!("Building background plugin...");
+        app.init_resource::<DayNightCycle>()
+            .add_systems(
+                Startup,
+                (spawn_water_gradient, spawn_scenery, spawn_darkness)
+                    .after(arena::initialize_arena),
+            )
+            .add_systems(
+                Update,
+                (advance_day_night_cycle, reset_day_night_cycle_on_restart)
+                    .in_set(stages::EmitEventsSet),
+            )
+            .add_systems(
+                Update,
+                (
+                    parallax_system,
+                    drift_distant_fish_system,
+                    sway_seaweed_system,
+                    tint_sky_system,
+                    tint_water_system,
+                    darkness_follow_player_system,
+                )
+                    .in_set(stages::PrepareRenderSet),
+            );
+    }
+}
+
+/// How far along the run is in its cycle from day to night.
+#[derive(Debug, Default, Resource)]
+struct DayNightCycle {
+    time: f32,
+}
+
+impl DayNightCycle {
+    /// 0 during the day and 1 at the darkest point of the night.
+    fn darkness(&self) -> f32 {
+        (1.0 - (self.time / DAY_LENGTH * 2.0 * PI).cos()) / 2.0
+    }
+}
+
+/// Moves the entity opposite to the fish, closer layers move more.
+#[derive(Debug, Component)]
+struct ParallaxLayer {
+    factor: f32,
+    base_x: f32,
+}
+
+/// The untinted color of something underwater.
+#[derive(Debug, Component)]
+struct WaterTint {
+    base_color: Color,
+}
+
+#[derive(Debug, Component)]
+struct DistantFish {
+    speed: f32,
+}
+
+#[derive(Debug, Component)]
+struct Seaweed {
+    sway_offset: f32,
+}
+
+/// A ring of night around the fish. It is the top layer of the background, so it darkens the
+/// water and the scenery while the objects and the fish stay visible.
+#[derive(Debug, Component)]
+struct Darkness;
+
+fn lerp_color(from: Color, to: Color, amount: f32) -> Color {
+    Color::rgba(
+        from.r() + (to.r() - from.r()) * amount,
+        from.g() + (to.g() - from.g()) * amount,
+        from.b() + (to.b() - from.b()) * amount,
+        from.a() + (to.a() - from.a()) * amount,
+    )
+}
+
+fn spawn_water_gradient(mut commands: Commands, arena: Res<Arena>) {
+    let band_height = arena.height / GRADIENT_BANDS as f32;
+    let top = arena.height / 2.0 + arena.offset;
+
+    for band in 0..GRADIENT_BANDS {
+        let color = lerp_color(
+            SURFACE_WATER_COLOR,
+            DEEP_WATER_COLOR,
+            band as f32 / (GRADIENT_BANDS - 1) as f32,
+        );
+
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color,
+                    custom_size: Some(Vec2::new(arena.width, band_height)),
+                    ..Default::default()
+                },
+                transform: Transform::from_xyz(0.0, top - band_height * (band as f32 + 0.5), 0.0),
+                ..Default::default()
+            },
+            WaterTint { base_color: color },
+            RenderLayer::Background,
+            LayerOrder(0),
+        ));
+    }
+}
+
+fn spawn_scenery(mut commands: Commands, arena: Res<Arena>) {
+    let mut rng = ChaCha8Rng::seed_from_u64(SCENERY_SEED);
+    let bottom = arena.offset - arena.height / 2.0;
+    let top = arena.offset + arena.height / 2.0;
+    // cover the extra space that is revealed when the layers shift
+    let half_width = arena.width * 0.75;
+
+    // distant fish
+    for _ in 0..6 {
+        let length: f32 = rng.gen_range(10.0..18.0);
+        let mut builder = PathBuilder::new();
+        builder.move_to(Vec2::new(-length / 2.0, 0.0));
+        builder.line_to(Vec2::new(0.0, length / 4.0));
+        builder.line_to(Vec2::new(length / 2.0, 0.0));
+        builder.line_to(Vec2::new(0.0, -length / 4.0));
+        builder.close();
+        // tail
+        builder.move_to(Vec2::new(-length / 2.0, 0.0));
+        builder.line_to(Vec2::new(-length * 0.8, length / 4.0));
+        builder.line_to(Vec2::new(-length * 0.8, -length / 4.0));
+        builder.close();
+
+        let x = rng.gen_range(-half_width..half_width);
+        let speed: f32 = rng.gen_range(8.0..20.0) * if rng.gen() { 1.0 } else { -1.0 };
+        let color = Color::rgba(0.1, 0.25, 0.4, 0.5);
+
+        commands.spawn((
+            ShapeBundle {
+                path: builder.build(),
+                spatial: SpatialBundle {
+                    transform: Transform {
+                        translation: Vec3::new(x, rng.gen_range(bottom + 60.0..top - 40.0), 0.0),
+                        scale: Vec3::new(speed.signum(), 1.0, 1.0),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Fill::color(color),
+            WaterTint { base_color: color },
+            ParallaxLayer {
+                factor: 0.05,
+                base_x: x,
+            },
+            DistantFish { speed },
+            RenderLayer::Background,
+            LayerOrder(1),
+        ));
+    }
+
+    // rocks
+    let mut x = -half_width;
+    while x < half_width {
+        let width = rng.gen_range(30.0..70.0);
+        let height = rng.gen_range(10.0..35.0);
+        let mut builder = PathBuilder::new();
+        builder.move_to(Vec2::new(-width / 2.0, 0.0));
+        builder.line_to(Vec2::new(-width / 3.0, height * 0.7));
+        builder.line_to(Vec2::new(rng.gen_range(-width / 6.0..width / 6.0), height));
+        builder.line_to(Vec2::new(width / 3.0, height * 0.6));
+        builder.line_to(Vec2::new(width / 2.0, 0.0));
+        builder.close();
+
+        let shade = rng.gen_range(0.3..0.45);
+        let color = Color::rgb(shade, shade + 0.03, shade + 0.07);
+
+        commands.spawn((
+            ShapeBundle {
+                path: builder.build(),
+                spatial: SpatialBundle::from_transform(Transform::from_xyz(x, bottom, 0.0)),
+                ..Default::default()
+            },
+            Fill::color(color),
+            WaterTint { base_color: color },
+            ParallaxLayer {
+                factor: 0.1,
+                base_x: x,
+            },
+            RenderLayer::Background,
+            LayerOrder(2),
+        ));
+
+        x += width * rng.gen_range(0.8..1.6);
+    }
+
+    // seaweed
+    let mut x = -half_width;
+    while x < half_width {
+        let height = rng.gen_range(40.0..110.0);
+        let segments = 4;
+        let mut wiggle = 6.0;
+        let mut builder = PathBuilder::new();
+        builder.move_to(Vec2::ZERO);
+        for segment in 1..=segments {
+            builder.line_to(Vec2::new(wiggle, height * segment as f32 / segments as f32));
+            wiggle = -wiggle;
+        }
+
+        let color = Color::rgb(0.18, rng.gen_range(0.45..0.6), 0.3);
+
+        commands.spawn((
+            ShapeBundle {
+                path: builder.build(),
+                spatial: SpatialBundle::from_transform(Transform::from_xyz(x, bottom, 0.0)),
+                ..Default::default()
+            },
+            Stroke {
+                color,
+                options: StrokeOptions::default()
+                    .with_line_width(4.0)
+                    .with_line_cap(LineCap::Round)
+                    .with_line_join(LineJoin::Round),
+            },
+            WaterTint { base_color: color },
+            ParallaxLayer {
+                factor: 0.2,
+                base_x: x,
+            },
+            Seaweed {
+                sway_offset: rng.gen_range(0.0..2.0 * PI),
+            },
+            RenderLayer::Background,
+            LayerOrder(3),
+        ));
+
+        x += rng.gen_range(25.0..80.0);
+    }
+}
+
+fn spawn_darkness(mut commands: Commands) {
+    let ring = GeometryBuilder::build_as(&shapes::Circle {
+        radius: DARKNESS_INNER_RADIUS + DARKNESS_RING_WIDTH / 2.0,
+        center: Vec2::ZERO,
+    });
+
+    commands.spawn((
+        ShapeBundle {
+            path: ring,
+            ..Default::default()
+        },
+        Stroke::new(Color::NONE, DARKNESS_RING_WIDTH),
+        Darkness,
+        RenderLayer::Background,
+        LayerOrder(4),
+    ));
+}
+
+fn advance_day_night_cycle(
+    time: Res<Time>,
+    game_state: Res<GameState>,
+    mut cycle: ResMut<DayNightCycle>,
+) {
+    if game_state.is_running() {
+        cycle.time = (cycle.time + time.delta_seconds()) % DAY_LENGTH;
+    }
+}
+
+fn reset_day_night_cycle_on_restart(
+    mut restart_reader: EventReader<GameRestarted>,
+    mut cycle: ResMut<DayNightCycle>,
+) {
+    if restart_reader.read().next().is_some() {
+        cycle.time = 0.0;
+    }
+}
+
+fn parallax_system(
+    player_query: Query<&Transform, (With<Player>, Without<ParallaxLayer>)>,
+    mut layer_query: Query<(&ParallaxLayer, &mut Transform)>,
+) {
+    let Some(player_transform) = player_query.iter().next() else {
+        return;
+    };
+
+    for (parallax_layer, mut transform) in layer_query.iter_mut() {
+        transform.translation.x =
+            parallax_layer.base_x - player_transform.translation.x * parallax_layer.factor;
+    }
+}
+
+/// Distant fish swim across the background and wrap around when they leave it.
+fn drift_distant_fish_system(
+    time: Res<Time>,
+    arena: Res<Arena>,
+    mut fish_query: Query<(&DistantFish, &mut ParallaxLayer)>,
+) {
+    let half_width = arena.width * 0.75;
+
+    for (distant_fish, mut parallax_layer) in fish_query.iter_mut() {
+        parallax_layer.base_x += distant_fish.speed * time.delta_seconds();
+
+        if parallax_layer.base_x > half_width {
+            parallax_layer.base_x = -half_width;
+        } else if parallax_layer.base_x < -half_width {
+            parallax_layer.base_x = half_width;
+        }
+    }
+}
+
+fn sway_seaweed_system(time: Res<Time>, mut seaweed_query: Query<(&Seaweed, &mut Transform)>) {
+    for (seaweed, mut transform) in seaweed_query.iter_mut() {
+        let angle = (time.elapsed_seconds() * SEAWEED_SWAY_SPEED + seaweed.sway_offset).sin()
+            * SEAWEED_SWAY_ANGLE;
+        transform.rotation = Quat::from_rotation_z(angle);
+    }
+}
+
+fn tint_sky_system(cycle: Res<DayNightCycle>, mut clear_color: ResMut<ClearColor>) {
+    if cycle.is_changed() {
+        clear_color.0 = lerp_color(DAY_SKY_COLOR, NIGHT_SKY_COLOR, cycle.darkness());
+    }
+}
+
+fn tint_water_system(
+    cycle: Res<DayNightCycle>,
+    mut sprite_query: Query<(&WaterTint, &mut Sprite)>,
+    mut fill_query: Query<(&WaterTint, &mut Fill)>,
+    mut stroke_query: Query<(&WaterTint, &mut Stroke)>,
+) {
+    if !cycle.is_changed() {
+        return;
+    }
+
+    let tint = lerp_color(Color::WHITE, NIGHT_WATER_TINT, cycle.darkness());
+    let apply_tint = |base_color: Color| {
+        Color::rgba(
+            base_color.r() * tint.r(),
+            base_color.g() * tint.g(),
+            base_color.b() * tint.b(),
+            base_color.a(),
+        )
+    };
+
+    for (water_tint, mut sprite) in sprite_query.iter_mut() {
+        sprite.color = apply_tint(water_tint.base_color);
+    }
+
+    for (water_tint, mut fill) in fill_query.iter_mut() {
+        fill.color = apply_tint(water_tint.base_color);
+    }
+
+    for (water_tint, mut stroke) in stroke_query.iter_mut() {
+        stroke.color = apply_tint(water_tint.base_color);
+    }
+}
+
+/// Closes the darkness in around the fish as the night gets darker.
+fn darkness_follow_player_system(
+    cycle: Res<DayNightCycle>,
+    player_query: Query<&Transform, (With<Player>, Without<Darkness>)>,
+    mut darkness_query: Query<(&mut Transform, &mut Stroke), With<Darkness>>,
+) {
+    let Some(player_transform) = player_query.iter().next() else {
+        return;
+    };
+
+    let darkness = cycle.darkness();
+    let visibility_radius =
+        DAY_VISIBILITY_RADIUS + (NIGHT_VISIBILITY_RADIUS - DAY_VISIBILITY_RADIUS) * darkness;
+
+    for (mut transform, mut stroke) in darkness_query.iter_mut() {
+        transform.translation.x = player_transform.translation.x;
+        transform.translation.y = player_transform.translation.y;
+        transform.scale = Vec3::splat(visibility_radius / DARKNESS_INNER_RADIUS);
+        stroke.color = Color::rgba(0.0, 0.0, 0.0, MAX_DARKNESS_ALPHA * darkness);
+    }
+}
@@ -0,0 +1,178 @@
+/**
+Effects applied to the main camera to make hits feel heavier: the screen shakes when the fish gets
+bonked, the game briefly freezes when the fish gets hooked, and the camera slowly zooms in on the
+boat that won once the game is over. Every effect can be turned off in the accessibility settings.
+*/
+use bevy::prelude::*;
+use rand::prelude::*;
+
+use crate::player::events::{PlayerBonked, PlayerHooked, PlayerStunned};
+use crate::settings::Settings;
+use crate::shared::{
+    game::{GameOver, GameRestarted},
+    stages, MainCamera,
+};
+
+const BONKED_TRAUMA: f32 = 0.8;
+const STUNNED_TRAUMA: f32 = 0.4;
+/// How much trauma goes away every second.
+const TRAUMA_DECAY: f32 = 1.5;
+/// How far the camera moves at full trauma, in arena units.
+const MAX_SHAKE_OFFSET: f32 = 12.0;
+
+/// How long the game freezes for when the fish gets hooked.
+const HIT_STOP_DURATION: f32 = 0.15;
+
+const GAME_OVER_ZOOM: f32 = 0.6;
+/// How long it takes to zoom all the way in on the winning boat.
+const GAME_OVER_ZOOM_TIME: f32 = 2.0;
+
+pub struct CameraEffectsPlugin;
+
+impl Plugin for CameraEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        debug!("Building camera effects plugin...");
+        app.init_resource::<CameraEffects>()
+            .add_systems(
+                Update,
+                (
+                    add_trauma_system,
+                    hit_stop_system,
+                    zoom_to_winner_system,
+                    reset_camera_effects_on_restart,
+                )
+                    .in_set(stages::HandleEventsSet),
+            )
+            .add_systems(
+                Update,
+                (apply_camera_effects_system).in_set(stages::PrepareRenderSet),
+            );
+    }
+}
+
+#[derive(Debug, Default, Resource)]
+struct CameraEffects {
+    /// How much the camera is shaking, from 0 to 1.
+    trauma: f32,
+    hit_stop: Option<Timer>,
+    zoom_target: Option<Entity>,
+    /// Where the winning boat was last seen, so the camera stays put once it leaves the arena.
+    zoom_point: Vec2,
+    /// How far into the zoom towards the winning boat the camera is, from 0 to 1.
+    zoom_progress: f32,
+}
+
+fn add_trauma_system(
+    settings: Res<Settings>,
+    mut player_bonked_reader: EventReader<PlayerBonked>,
+    mut player_stunned_reader: EventReader<PlayerStunned>,
+    mut camera_effects: ResMut<CameraEffects>,
+) {
+    let mut trauma = 0.0;
+
+    for _ in player_bonked_reader.read() {
+        trauma += BONKED_TRAUMA;
+    }
+
+    for _ in player_stunned_reader.read() {
+        trauma += STUNNED_TRAUMA;
+    }
+
+    if settings.accessibility.screen_shake && trauma > 0.0 {
+        camera_effects.trauma = (camera_effects.trauma + trauma).min(1.0);
+    }
+}
+
+/// Freezes game time for a moment when the fish gets hooked. Real time keeps going so the freeze
+/// can end.
+fn hit_stop_system(
+    settings: Res<Settings>,
+    real_time: Res<Time<Real>>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut player_hooked_reader: EventReader<PlayerHooked>,
+    mut camera_effects: ResMut<CameraEffects>,
+) {
+    if player_hooked_reader.read().next().is_some() && settings.accessibility.hit_stop {
+        debug!("Starting hit stop");
+        camera_effects.hit_stop = Some(Timer::from_seconds(HIT_STOP_DURATION, TimerMode::Once));
+        virtual_time.pause();
+    }
+
+    if let Some(hit_stop) = camera_effects.hit_stop.as_mut() {
+        if hit_stop.tick(real_time.delta()).finished() {
+            camera_effects.hit_stop = None;
+            virtual_time.unpause();
+        }
+    }
+}
+
+fn zoom_to_winner_system(
+    settings: Res<Settings>,
+    mut game_over_reader: EventReader<GameOver>,
+    mut camera_effects: ResMut<CameraEffects>,
+) {
+    for game_over_event in game_over_reader.read() {
+        if settings.accessibility.camera_zoom {
+            camera_effects.zoom_target = game_over_event.winning_boat;
+        }
+    }
+}
+
+fn reset_camera_effects_on_restart(
+    mut restart_reader: EventReader<GameRestarted>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut camera_effects: ResMut<CameraEffects>,
+) {
+    if restart_reader.read().next().is_some() {
+        if camera_effects.hit_stop.is_some() {
+            virtual_time.unpause();
+        }
+
+        *camera_effects = CameraEffects::default();
+    }
+}
+
+fn apply_camera_effects_system(
+    real_time: Res<Time<Real>>,
+    settings: Res<Settings>,
+    mut camera_effects: ResMut<CameraEffects>,
+    target_query: Query<&GlobalTransform, Without<MainCamera>>,
+    mut camera_query: Query<(&mut Transform, &mut OrthographicProjection), With<MainCamera>>,
+) {
+    let delta = real_time.delta_seconds();
+
+    if !settings.accessibility.screen_shake {
+        camera_effects.trauma = 0.0;
+    }
+    camera_effects.trauma = (camera_effects.trauma - TRAUMA_DECAY * delta).max(0.0);
+
+    if let Some(target_transform) = camera_effects
+        .zoom_target
+        .and_then(|target| target_query.get(target).ok())
+    {
+        camera_effects.zoom_point = target_transform.translation().truncate();
+    }
+
+    if settings.accessibility.camera_zoom && camera_effects.zoom_target.is_some() {
+        camera_effects.zoom_progress =
+            (camera_effects.zoom_progress + delta / GAME_OVER_ZOOM_TIME).min(1.0);
+    } else {
+        camera_effects.zoom_progress = 0.0;
+    }
+
+    let mut rng = thread_rng();
+    // shake grows with the square of trauma so small hits stay subtle
+    let shake = camera_effects.trauma * camera_effects.trauma * MAX_SHAKE_OFFSET;
+    let shake_offset = Vec2::new(rng.gen_range(-1.0..=1.0), rng.gen_range(-1.0..=1.0)) * shake;
+
+    // ease out so the zoom slows down as it gets closer
+    let zoom = 1.0 - (1.0 - camera_effects.zoom_progress).powi(3);
+
+    for (mut transform, mut projection) in camera_query.iter_mut() {
+        let focus = camera_effects.zoom_point * zoom;
+
+        transform.translation.x = focus.x + shake_offset.x;
+        transform.translation.y = focus.y + shake_offset.y;
+        projection.scale = 1.0 + (GAME_OVER_ZOOM - 1.0) * zoom;
+    }
+}
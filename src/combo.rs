@@ -0,0 +1,175 @@
+/**
+Combos reward playing with style. Eating worms in quick succession raises the combo multiplier that
+worm points are scored with, and slipping past hooks scores style points on top. The combo is lost
+when the fish gets touched by a boat, or when it goes too long without eating or near missing.
+*/
+use bevy::prelude::*;
+
+use crate::modes::CurrentGameMode;
+use crate::modifiers::Modifiers;
+use crate::player::events::{PlayerAte, PlayerNearMissed, PlayerStunned};
+use crate::shared::{
+    game::{GameRestarted, GameState, Score},
+    stages,
+};
+
+/// Seconds the combo is kept alive for after eating a worm or a near miss.
+const COMBO_WINDOW: f32 = 3.0;
+const MAX_COMBO_MULTIPLIER: u32 = 5;
+/// Style points for a near miss, before the combo multiplier.
+const NEAR_MISS_POINTS: u32 = 3;
+
+#[derive(Debug, Resource)]
+pub struct Combo {
+    /// Worms eaten since the combo started.
+    pub streak: u32,
+    timer: Timer,
+}
+
+impl Default for Combo {
+    fn default() -> Self {
+        Self {
+            streak: 0,
+            timer: Timer::from_seconds(COMBO_WINDOW, TimerMode::Once),
+        }
+    }
+}
+
+impl Combo {
+    /// Multiplier for points scored while the combo lasts.
+    pub fn multiplier(&self) -> u32 {
+        self.streak.clamp(1, MAX_COMBO_MULTIPLIER)
+    }
+
+    fn extend(&mut self) {
+        self.streak += 1;
+        self.timer.reset();
+    }
+
+    fn keep_alive(&mut self) {
+        self.timer.reset();
+    }
+
+    fn reset(&mut self) {
+        if self.streak > 0 {
+            debug!("Lost a combo of {}", self.streak);
+        }
+        *self = Self::default();
+    }
+}
+
+/// Sent whenever points are scored somewhere in the world, e.g. to show where they came from.
+#[derive(Debug, Event)]
+pub struct PointsScored {
+    pub points: u32,
+    pub position: Vec2,
+}
+
+pub struct ComboPlugin;
+
+impl Plugin for ComboPlugin {
+    fn build(&self, app: &mut App) {
+        debug!("Building combo plugin...");
+        app.init_resource::<Combo>()
+            .add_event::<PointsScored>()
+            .add_systems(Update, (combo_timeout_system).in_set(stages::EmitEventsSet))
+            .add_systems(
+                Update,
+                (score_combo_system, reset_combo_on_restart).in_set(stages::HandleEventsSet),
+            );
+    }
+}
+
+/// Loses the combo when the fish idles for too long.
+fn combo_timeout_system(time: Res<Time>, game_state: Res<GameState>, mut combo: ResMut<Combo>) {
+    if !game_state.is_running() || combo.streak == 0 {
+        return;
+    }
+
+    combo.timer.tick(time.delta());
+
+    if combo.timer.finished() {
+        combo.reset();
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn score_combo_system(
+    current_mode: Res<CurrentGameMode>,
+    modifiers: Res<Modifiers>,
+    mut player_stunned_reader: EventReader<PlayerStunned>,
+    mut player_ate_reader: EventReader<PlayerAte>,
+    mut player_near_missed_reader: EventReader<PlayerNearMissed>,
+    mut combo: ResMut<Combo>,
+    mut score: ResMut<Score>,
+    mut points_scored_events: EventWriter<PointsScored>,
+    player_query: Query<&Transform>,
+) {
+    if player_stunned_reader.read().next().is_some() {
+        combo.reset();
+    }
+
+    let multiplier = modifiers.score_multiplier();
+    let mut scored = Vec::new();
+
+    for ate_event in player_ate_reader.read() {
+        combo.extend();
+        let points = current_mode.active().points_per_worm() * combo.multiplier();
+
+        if let Ok(transform) = player_query.get(ate_event.player_entity) {
+            scored.push((points, transform.translation.truncate()));
+        } else {
+            score.add_points(points, multiplier);
+        }
+    }
+
+    for near_missed_event in player_near_missed_reader.read() {
+        combo.keep_alive();
+        scored.push((
+            NEAR_MISS_POINTS * combo.multiplier(),
+            near_missed_event.position,
+        ));
+    }
+
+    for (points, position) in scored {
+        score.add_points(points, multiplier);
+        debug!("Score: {:?}", score.count);
+
+        points_scored_events.send(PointsScored {
+            points: (points as f32 * multiplier).round() as u32,
+            position,
+        });
+    }
+}
+
+fn reset_combo_on_restart(
+    mut restart_reader: EventReader<GameRestarted>,
+    mut combo: ResMut<Combo>,
+) {
+    if restart_reader.read().next().is_some() {
+        combo.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multiplier_grows_with_the_streak_up_to_the_max() {
+        let mut combo = Combo::default();
+        assert_eq!(combo.multiplier(), 1);
+
+        combo.extend();
+        combo.extend();
+        assert_eq!(combo.multiplier(), 2);
+
+        for _ in 0..10 {
+            combo.extend();
+        }
+        assert_eq!(combo.multiplier(), MAX_COMBO_MULTIPLIER);
+
+        combo.reset();
+        assert_eq!(combo.multiplier(), 1);
+    }
+}
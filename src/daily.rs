@@ -0,0 +1,205 @@
+/**
+The daily challenge. Every run in daily seed mode plays the seed of the day, sometimes with a
+modifier of the day instead of the chosen modifiers. Only the first run of each day is ranked: it goes on the
+daily leaderboard and into the history of past days. Any run after that is practice.
+*/
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::modifiers::ModifierId;
+use crate::shared::{
+    game::{GameOver, GameRestarted, Score},
+    rng::{current_day, daily_seed, GameRng, SeedModes},
+    stages, storage,
+};
+
+const DAILY_HISTORY_KEY: &str = "daily_history";
+
+/// The modifiers that the daily challenge can be played with.
+const DAILY_MODIFIERS: &[ModifierId] = &[
+    ModifierId::HeavySink,
+    ModifierId::NoBoosts,
+    ModifierId::FastHunger,
+    ModifierId::MirrorControls,
+    ModifierId::GiantHooks,
+];
+
+/// The modifier of the day with the given seed. About half of the days don't have one.
+pub fn daily_modifier(seed: u32) -> Option<ModifierId> {
+    DAILY_MODIFIERS
+        .get(seed as usize % (DAILY_MODIFIERS.len() * 2))
+        .copied()
+}
+
+/// The result of the ranked run of a day.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DailyResult {
+    /// Days since the unix epoch.
+    pub day: u64,
+    pub score: u32,
+    pub modifier: Option<ModifierId>,
+}
+
+/// The ranked results of past days, saved between sessions.
+#[derive(Debug, Default, Deserialize, Serialize, Resource)]
+pub struct DailyHistory {
+    /// Ordered from the oldest day to the newest.
+    pub results: Vec<DailyResult>,
+}
+
+impl DailyHistory {
+    pub fn load() -> Self {
+        storage::load_json(DAILY_HISTORY_KEY).unwrap_or_else(|| {
+            debug!("No saved daily history found, starting a new one");
+            Self::default()
+        })
+    }
+
+    fn save(&self) {
+        storage::save_json(DAILY_HISTORY_KEY, self);
+    }
+
+    /// Whether the ranked attempt of the day has been used up.
+    pub fn has_played(&self, day: u64) -> bool {
+        self.results.iter().any(|result| result.day == day)
+    }
+}
+
+/// A run of the daily challenge.
+#[derive(Debug, Copy, Clone)]
+pub struct DailyRun {
+    pub day: u64,
+    pub modifier: Option<ModifierId>,
+    /// Only the first run of the day counts towards the daily leaderboard.
+    pub ranked: bool,
+}
+
+/// The daily challenge that is being played, if any.
+#[derive(Debug, Default, Resource)]
+pub struct DailyChallenge {
+    pub run: Option<DailyRun>,
+}
+
+impl DailyChallenge {
+    /// What today's challenge is and whether its ranked attempt is still available.
+    pub fn today(history: &DailyHistory) -> DailyRun {
+        let day = current_day();
+
+        DailyRun {
+            day,
+            modifier: daily_modifier(daily_seed(day)),
+            ranked: !history.has_played(day),
+        }
+    }
+}
+
+pub struct DailyChallengePlugin;
+
+impl Plugin for DailyChallengePlugin {
+    fn build(&self, app: &mut App) {
+        debug!("Building daily challenge plugin...");
+        app.init_resource::<DailyChallenge>()
+            .insert_resource(DailyHistory::load())
+            .add_systems(
+                Update,
+                (start_daily_run_system, record_daily_result_system)
+                    .in_set(stages::HandleEventsSet),
+            );
+    }
+}
+
+/// Starts a daily run when the game restarts in daily seed mode. The ranked attempt of the day is
+/// used up as soon as its run starts, so restarting or quitting halfway doesn't give another try.
+pub(crate) fn start_daily_run_system(
+    rng: Res<GameRng>,
+    mut restart_reader: EventReader<GameRestarted>,
+    mut history: ResMut<DailyHistory>,
+    mut daily_challenge: ResMut<DailyChallenge>,
+) {
+    if restart_reader.read().next().is_none() {
+        return;
+    }
+
+    let SeedModes::Daily = rng.mode else {
+        daily_challenge.run = None;
+        return;
+    };
+
+    let run = DailyChallenge::today(&history);
+    debug!("Starting daily run: {:?}", run);
+
+    if run.ranked {
+        history.results.push(DailyResult {
+            day: run.day,
+            score: 0,
+            modifier: run.modifier,
+        });
+        history.save();
+    }
+
+    daily_challenge.run = Some(run);
+}
+
+fn record_daily_result_system(
+    score: Res<Score>,
+    daily_challenge: Res<DailyChallenge>,
+    mut game_over_reader: EventReader<GameOver>,
+    mut history: ResMut<DailyHistory>,
+) {
+    if game_over_reader.read().next().is_none() {
+        return;
+    }
+
+    let Some(run) = daily_challenge.run.filter(|run| run.ranked) else {
+        return;
+    };
+
+    let Some(result) = history
+        .results
+        .iter_mut()
+        .rev()
+        .find(|result| result.day == run.day)
+    else {
+        return;
+    };
+
+    debug!(
+        "Saving daily result {:?} for day {:?}",
+        score.count, run.day
+    );
+    result.score = score.count;
+    history.save();
+}
+
+/// The calendar date of a day since the unix epoch, formatted as year-month-day.
+pub fn format_day(day: u64) -> String {
+    // From Howard Hinnant's civil_from_days, shifted so that years start in March.
+    let days = day as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day_of_month = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02}", year, month, day_of_month)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_are_formatted_as_dates() {
+        assert_eq!(format_day(0), "1970-01-01");
+        assert_eq!(format_day(11_016), "2000-02-29");
+        assert_eq!(format_day(20_745), "2026-10-19");
+    }
+}
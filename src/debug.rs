@@ -0,0 +1,471 @@
+/**
+Developer tools that are only built with the `dev` feature.
+
+- F1 draws the colliders, fishing lines and movement destinations.
+- F2 shows an overlay with the frame rate, entity count, difficulty, rng seed and player states.
+- F3 opens an inspector to tune the player's stats and the stats of newly spawned boats. Changes
+  last until the game is closed.
+*/
+use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
+use bevy::prelude::*;
+
+use crate::objects::boat::{BoatTuning, Line};
+use crate::player::{
+    attributes::{Player, PlayerStats},
+    states::PlayerState,
+};
+use crate::shared::{
+    collision::Collider,
+    game::Difficulty,
+    movement::Destination,
+    render::FontHandles,
+    rng::{seed_code, GameRng},
+    stages,
+};
+
+const COLLIDER_COLOR: Color = Color::LIME_GREEN;
+const LINE_COLOR: Color = Color::FUCHSIA;
+const DESTINATION_COLOR: Color = Color::CYAN;
+const DESTINATION_RADIUS: f32 = 4.0;
+
+/// How much a value changes with each press of an inspector button.
+const INSPECTOR_STEP: f32 = 1.1;
+
+pub struct DebugPlugin;
+
+impl Plugin for DebugPlugin {
+    fn build(&self, app: &mut App) {
+        debug!("Building debug plugin...");
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin);
+        }
+
+        app.init_resource::<DebugSettings>()
+            .add_systems(Startup, (spawn_debug_overlay, spawn_inspector))
+            .add_systems(
+                Update,
+                (debug_toggle_system, inspector_button_system).in_set(stages::HandleEventsSet),
+            )
+            .add_systems(
+                Update,
+                (
+                    draw_debug_gizmos_system,
+                    update_debug_overlay,
+                    update_inspector_text,
+                    show_debug_panels,
+                )
+                    .in_set(stages::PrepareRenderSet),
+            );
+    }
+}
+
+#[derive(Debug, Default, Resource)]
+struct DebugSettings {
+    gizmos: bool,
+    overlay: bool,
+    inspector: bool,
+}
+
+fn debug_toggle_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<DebugSettings>,
+) {
+    if keyboard_input.just_pressed(KeyCode::F1) {
+        settings.gizmos = !settings.gizmos;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::F2) {
+        settings.overlay = !settings.overlay;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::F3) {
+        settings.inspector = !settings.inspector;
+    }
+}
+
+fn draw_debug_gizmos_system(
+    settings: Res<DebugSettings>,
+    mut gizmos: Gizmos,
+    collider_query: Query<(&Collider, &GlobalTransform)>,
+    line_query: Query<(&Line, &GlobalTransform)>,
+    destination_query: Query<(&Destination, Option<&Parent>)>,
+    parent_query: Query<&GlobalTransform>,
+) {
+    if !settings.gizmos {
+        return;
+    }
+
+    for (collider, transform) in collider_query.iter() {
+        gizmos.rect_2d(
+            transform.translation().truncate(),
+            0.0,
+            Vec2::new(collider.width, collider.height),
+            COLLIDER_COLOR,
+        );
+    }
+
+    for (line, transform) in line_query.iter() {
+        gizmos.line_2d(
+            transform.transform_point(line.start_point).truncate(),
+            transform.transform_point(line.end_point).truncate(),
+            LINE_COLOR,
+        );
+    }
+
+    // destinations are relative to the parent of the entity that is moving
+    for (destination, parent) in destination_query.iter() {
+        let point = parent
+            .and_then(|parent| parent_query.get(parent.get()).ok())
+            .map(|parent_transform| parent_transform.transform_point(destination.point))
+            .unwrap_or(destination.point);
+
+        gizmos.circle_2d(point.truncate(), DESTINATION_RADIUS, DESTINATION_COLOR);
+    }
+}
+
+#[derive(Component)]
+struct DebugOverlayText;
+
+#[derive(Component)]
+struct InspectorRootNode;
+
+fn debug_text_style(fonts: &FontHandles) -> TextStyle {
+    TextStyle {
+        font: fonts.main_font.clone(),
+        font_size: 16.0,
+        color: Color::WHITE,
+    }
+}
+
+fn debug_panel_style() -> Style {
+    Style {
+        position_type: PositionType::Absolute,
+        flex_direction: FlexDirection::Column,
+        padding: UiRect::all(Val::Px(5.0)),
+        ..Default::default()
+    }
+}
+
+fn spawn_debug_overlay(mut commands: Commands, fonts: Res<FontHandles>) {
+    commands.spawn((
+        TextBundle {
+            text: Text::from_section("", debug_text_style(&fonts)),
+            style: Style {
+                left: Val::Px(10.0),
+                top: Val::Px(60.0),
+                ..debug_panel_style()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.7).into(),
+            visibility: Visibility::Hidden,
+            z_index: ZIndex::Global(100),
+            ..Default::default()
+        },
+        DebugOverlayText,
+    ));
+}
+
+fn update_debug_overlay(
+    settings: Res<DebugSettings>,
+    diagnostics: Res<DiagnosticsStore>,
+    difficulty: Res<Difficulty>,
+    rng: Res<GameRng>,
+    entity_query: Query<Entity>,
+    player_query: Query<&PlayerState>,
+    mut text_query: Query<&mut Text, With<DebugOverlayText>>,
+) {
+    if !settings.overlay {
+        return;
+    }
+
+    let fps = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|fps| fps.smoothed())
+        .unwrap_or(0.0);
+
+    let player_states: Vec<String> = player_query
+        .iter()
+        .map(|player_state| format!("{:?}", player_state.current_state))
+        .collect();
+
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = format!(
+            "FPS: {:.0}\nEntities: {}\nDifficulty: {}\nSeed: {}\nPlayer states: {}",
+            fps,
+            entity_query.iter().count(),
+            difficulty.multiplier,
+            seed_code(rng.seed),
+            player_states.join(", "),
+        );
+    }
+}
+
+/// A stat that can be changed from the inspector.
+struct InspectorField<T> {
+    name: &'static str,
+    value: fn(&mut T) -> &mut f32,
+}
+
+const PLAYER_STAT_FIELDS: &[InspectorField<PlayerStats>] = &[
+    InspectorField {
+        name: "Boost speed",
+        value: |stats| &mut stats.boost_speed,
+    },
+    InspectorField {
+        name: "Boost duration",
+        value: |stats| &mut stats.boost_duration,
+    },
+    InspectorField {
+        name: "Boost cooldown",
+        value: |stats| &mut stats.boost_cooldown,
+    },
+    InspectorField {
+        name: "Boost invulnerability",
+        value: |stats| &mut stats.boost_invulnerability,
+    },
+    InspectorField {
+        name: "Leap speed",
+        value: |stats| &mut stats.leap_speed,
+    },
+    InspectorField {
+        name: "Dive speed",
+        value: |stats| &mut stats.dive_speed,
+    },
+    InspectorField {
+        name: "Dive duration",
+        value: |stats| &mut stats.dive_duration,
+    },
+    InspectorField {
+        name: "Stun duration",
+        value: |stats| &mut stats.stun_duration,
+    },
+    InspectorField {
+        name: "Stun recovery",
+        value: |stats| &mut stats.stun_recovery,
+    },
+    InspectorField {
+        name: "Speed",
+        value: |stats| &mut stats.speed,
+    },
+    InspectorField {
+        name: "Acceleration",
+        value: |stats| &mut stats.acceleration,
+    },
+    InspectorField {
+        name: "Traction",
+        value: |stats| &mut stats.traction,
+    },
+    InspectorField {
+        name: "Stop threshold",
+        value: |stats| &mut stats.stop_threshold,
+    },
+];
+
+const BOAT_TUNING_FIELDS: &[InspectorField<BoatTuning>] = &[
+    InspectorField {
+        name: "Boat speed",
+        value: |tuning| &mut tuning.speed,
+    },
+    InspectorField {
+        name: "Boat size",
+        value: |tuning| &mut tuning.size,
+    },
+    InspectorField {
+        name: "Worm chance",
+        value: |tuning| &mut tuning.worm_chance,
+    },
+];
+
+#[derive(Debug, Copy, Clone)]
+enum InspectorTargets {
+    /// Index into `PLAYER_STAT_FIELDS`.
+    PlayerStat(usize),
+    /// Index into `BOAT_TUNING_FIELDS`.
+    BoatTuning(usize),
+    /// The number of poles is a whole number, so it is stepped by one instead of scaled.
+    ExtraPoles,
+}
+
+impl InspectorTargets {
+    fn name(&self) -> &'static str {
+        match *self {
+            InspectorTargets::PlayerStat(index) => PLAYER_STAT_FIELDS[index].name,
+            InspectorTargets::BoatTuning(index) => BOAT_TUNING_FIELDS[index].name,
+            InspectorTargets::ExtraPoles => "Extra poles",
+        }
+    }
+}
+
+#[derive(Component)]
+struct InspectorButton {
+    target: InspectorTargets,
+    /// Whether the button raises or lowers the value.
+    raise: bool,
+}
+
+#[derive(Component)]
+struct InspectorValueText {
+    target: InspectorTargets,
+}
+
+fn spawn_inspector(mut commands: Commands, fonts: Res<FontHandles>) {
+    let text_style = debug_text_style(&fonts);
+
+    let targets = (0..PLAYER_STAT_FIELDS.len())
+        .map(InspectorTargets::PlayerStat)
+        .chain((0..BOAT_TUNING_FIELDS.len()).map(InspectorTargets::BoatTuning))
+        .chain([InspectorTargets::ExtraPoles]);
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    right: Val::Px(10.0),
+                    top: Val::Px(60.0),
+                    row_gap: Val::Px(2.0),
+                    ..debug_panel_style()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.7).into(),
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Global(100),
+                ..Default::default()
+            },
+            InspectorRootNode,
+        ))
+        .with_children(|builder| {
+            builder.spawn(TextBundle::from_section(
+                "Player stats apply now and to new runs.\nBoat stats apply to new boats.",
+                text_style.clone(),
+            ));
+
+            for target in targets {
+                builder
+                    .spawn(NodeBundle {
+                        style: Style {
+                            column_gap: Val::Px(5.0),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    })
+                    .with_children(|builder| {
+                        for (label, raise) in [("[-]", false), ("[+]", true)] {
+                            builder
+                                .spawn((
+                                    ButtonBundle {
+                                        background_color: Color::NONE.into(),
+                                        ..Default::default()
+                                    },
+                                    InspectorButton { target, raise },
+                                ))
+                                .with_children(|builder| {
+                                    builder
+                                        .spawn(TextBundle::from_section(label, text_style.clone()));
+                                });
+                        }
+
+                        builder.spawn((
+                            TextBundle::from_section("", text_style.clone()),
+                            InspectorValueText { target },
+                        ));
+                    });
+            }
+        });
+}
+
+fn inspector_button_system(
+    mut boat_tuning: ResMut<BoatTuning>,
+    mut player_stats: ResMut<PlayerStats>,
+    mut player_query: Query<&mut Player>,
+    interaction_query: Query<(&Interaction, &InspectorButton), Changed<Interaction>>,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let scale = if button.raise {
+            INSPECTOR_STEP
+        } else {
+            1.0 / INSPECTOR_STEP
+        };
+
+        match button.target {
+            InspectorTargets::PlayerStat(index) => {
+                let field = &PLAYER_STAT_FIELDS[index];
+                *(field.value)(&mut player_stats) *= scale;
+
+                // the player that is out already changes too
+                let value = *(field.value)(&mut player_stats);
+                for mut player in player_query.iter_mut() {
+                    *(field.value)(&mut player.stats) = value;
+                }
+            }
+            InspectorTargets::BoatTuning(index) => {
+                *(BOAT_TUNING_FIELDS[index].value)(&mut boat_tuning) *= scale;
+            }
+            InspectorTargets::ExtraPoles => {
+                boat_tuning.extra_poles += if button.raise { 1 } else { -1 };
+            }
+        }
+    }
+}
+
+fn update_inspector_text(
+    settings: Res<DebugSettings>,
+    boat_tuning: Res<BoatTuning>,
+    player_stats: Res<PlayerStats>,
+    mut text_query: Query<(&mut Text, &InspectorValueText)>,
+) {
+    if !settings.inspector {
+        return;
+    }
+
+    // the fields only hand out mutable references, so they are read from copies
+    let mut boat_tuning = boat_tuning.clone();
+    let mut player_stats = player_stats.clone();
+
+    for (mut text, value_text) in text_query.iter_mut() {
+        let value = match value_text.target {
+            InspectorTargets::PlayerStat(index) => {
+                format!(
+                    "{:.3}",
+                    (PLAYER_STAT_FIELDS[index].value)(&mut player_stats)
+                )
+            }
+            InspectorTargets::BoatTuning(index) => {
+                format!("{:.3}", (BOAT_TUNING_FIELDS[index].value)(&mut boat_tuning))
+            }
+            InspectorTargets::ExtraPoles => format!("{:+}", boat_tuning.extra_poles),
+        };
+
+        text.sections[0].value = format!("{}: {}", value_text.target.name(), value);
+    }
+}
+
+fn show_debug_panels(
+    settings: Res<DebugSettings>,
+    mut overlay_query: Query<&mut Visibility, (With<DebugOverlayText>, Without<InspectorRootNode>)>,
+    mut inspector_query: Query<
+        &mut Visibility,
+        (With<InspectorRootNode>, Without<DebugOverlayText>),
+    >,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    let visibility = |is_shown: bool| {
+        if is_shown {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        }
+    };
+
+    for mut overlay_visibility in overlay_query.iter_mut() {
+        *overlay_visibility = visibility(settings.overlay);
+    }
+
+    for mut inspector_visibility in inspector_query.iter_mut() {
+        *inspector_visibility = visibility(settings.inspector);
+    }
+}
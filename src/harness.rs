@@ -0,0 +1,306 @@
+/**
+Runs the game without a window, renderer, audio or assets, so tests can play through runs. A
+`TestGame` has a fixed seed, is stepped a frame at a time with the same time passing every frame,
+and is played with scripted key presses. Tests then check the events that were sent and the state
+of resources like the score.
+*/
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use bevy::ecs::schedule::{ExecutorKind, Schedules};
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+
+use crate::leaderboard::{LeaderboardPlugin, ScoreSaved};
+use crate::modes::GameModesPlugin;
+use crate::modifiers::ModifiersPlugin;
+use crate::objects::ObjectPlugins;
+use crate::player::{events::PlayerHooked, PlayerPlugin};
+use crate::shared::{
+    game::{GameOver, GameRestarted},
+    rng::{GameRng, SeedModes},
+    SharedPlugin,
+};
+
+/// How much time passes every frame.
+pub const FRAME_TIME: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+/// How many of an event were sent since the game started.
+#[derive(Resource)]
+struct Received<E: Event> {
+    count: usize,
+    event: PhantomData<E>,
+}
+
+impl<E: Event> Default for Received<E> {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            event: PhantomData,
+        }
+    }
+}
+
+fn count_events_system<E: Event>(mut reader: EventReader<E>, mut received: ResMut<Received<E>>) {
+    received.count += reader.read().count();
+}
+
+/// The game with only its rules, played by a script.
+pub struct TestGame {
+    pub app: App,
+}
+
+impl TestGame {
+    /// Builds the game with every run using the seed. The game starts on the menu, like it does
+    /// for players, until it is restarted.
+    pub fn new(seed: u32) -> Self {
+        let mut app = App::new();
+
+        app.add_plugins((MinimalPlugins, TransformPlugin, HierarchyPlugin))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME_TIME))
+            .init_resource::<ButtonInput<KeyCode>>()
+            .insert_resource(GameRng::from_mode(SeedModes::Fixed(seed)))
+            .add_plugins((
+                SharedPlugin,
+                ModifiersPlugin,
+                GameModesPlugin,
+                PlayerPlugin,
+                ObjectPlugins,
+                LeaderboardPlugin,
+            ));
+
+        let mut game = Self { app };
+        game.watch::<GameRestarted>();
+        game.watch::<PlayerHooked>();
+        game.watch::<GameOver>();
+        game.watch::<ScoreSaved>();
+
+        // storage is kept per thread in tests, so systems have to run on the test's own thread
+        for (_, schedule) in game.app.world.resource_mut::<Schedules>().iter_mut() {
+            schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+        }
+
+        game.app.finish();
+        game.app.cleanup();
+        // time only starts passing from the second frame on
+        game.step(1);
+        game
+    }
+
+    /// Starts counting an event, see `received`.
+    pub fn watch<E: Event>(&mut self) {
+        self.app
+            .init_resource::<Received<E>>()
+            .add_systems(Last, count_events_system::<E>);
+    }
+
+    /// How many of a watched event were sent so far.
+    pub fn received<E: Event>(&self) -> usize {
+        self.app.world.resource::<Received<E>>().count
+    }
+
+    pub fn resource<R: Resource>(&self) -> &R {
+        self.app.world.resource::<R>()
+    }
+
+    /// Holds a key down from the next frame on, until it is released.
+    pub fn press(&mut self, key: KeyCode) {
+        self.app
+            .world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(key);
+    }
+
+    pub fn release(&mut self, key: KeyCode) {
+        self.app
+            .world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .release(key);
+    }
+
+    /// Runs the game for a number of frames.
+    pub fn step(&mut self, frames: u32) {
+        for _ in 0..frames {
+            self.app.update();
+            // keys stay held, but were only just pressed or released for one frame
+            self.app
+                .world
+                .resource_mut::<ButtonInput<KeyCode>>()
+                .clear();
+        }
+    }
+
+    /// Runs the game until the event is sent, for at most a number of frames. Returns whether it
+    /// was sent.
+    pub fn step_until<E: Event>(&mut self, max_frames: u32) -> bool {
+        let received = self.received::<E>();
+        for _ in 0..max_frames {
+            self.step(1);
+            if self.received::<E>() > received {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Starts a new run the way the player does, by pressing restart.
+    pub fn restart(&mut self) {
+        self.press(KeyCode::KeyR);
+        self.step(1);
+        self.release(KeyCode::KeyR);
+    }
+
+    /// Frames that take up the time.
+    pub fn frames(seconds: f32) -> u32 {
+        (seconds / FRAME_TIME.as_secs_f32()).ceil() as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objects::boat::{Boat, Hook};
+    use crate::player::attributes::{HungerCountdown, Player};
+    use crate::player::events::PlayerStarved;
+    use crate::shared::{
+        arena::Arena,
+        game::{Difficulty, GameState, Score},
+        storage,
+    };
+
+    fn player_position(game: &mut TestGame) -> Vec3 {
+        game.app
+            .world
+            .query_filtered::<&Transform, With<Player>>()
+            .single(&game.app.world)
+            .translation
+    }
+
+    fn boat_positions(game: &mut TestGame) -> Vec<Vec3> {
+        let mut positions: Vec<Vec3> = game
+            .app
+            .world
+            .query_filtered::<&Transform, With<Boat>>()
+            .iter(&game.app.world)
+            .map(|transform| transform.translation)
+            .collect();
+        positions.sort_by(|a, b| a.x.total_cmp(&b.x));
+        positions
+    }
+
+    /// Holds the key that moves the player along an axis toward a target that far away.
+    fn steer(game: &mut TestGame, offset: f32, back: KeyCode, forward: KeyCode) {
+        if offset < -4.0 {
+            game.release(forward);
+            game.press(back);
+        } else if offset > 4.0 {
+            game.release(back);
+            game.press(forward);
+        } else {
+            game.release(back);
+            game.release(forward);
+        }
+    }
+
+    #[test]
+    fn runs_score_and_get_harder_over_time() {
+        let mut game = TestGame::new(1);
+        game.step(TestGame::frames(2.0));
+        assert!(!game.resource::<GameState>().is_running());
+        assert_eq!(game.resource::<Score>().count, 0);
+
+        game.restart();
+        assert_eq!(game.received::<GameRestarted>(), 1);
+        assert!(game.resource::<GameState>().is_running());
+        assert_eq!(game.resource::<Difficulty>().multiplier, 1);
+
+        game.step(TestGame::frames(11.0));
+        assert!(game.resource::<Score>().count >= 10);
+        assert_eq!(game.resource::<Difficulty>().multiplier, 2);
+    }
+
+    #[test]
+    fn the_same_seed_spawns_the_same_boats() {
+        let mut games = [TestGame::new(7), TestGame::new(7)];
+        for game in games.iter_mut() {
+            game.restart();
+            game.step(TestGame::frames(6.0));
+        }
+
+        let [first, second] = &mut games;
+        let boats = boat_positions(first);
+        assert!(!boats.is_empty());
+        assert_eq!(boats, boat_positions(second));
+    }
+
+    #[test]
+    fn keys_move_the_player() {
+        let mut game = TestGame::new(1);
+        game.restart();
+        let start = player_position(&mut game);
+
+        game.press(KeyCode::KeyD);
+        game.step(TestGame::frames(0.5));
+        game.release(KeyCode::KeyD);
+        assert!(player_position(&mut game).x > start.x);
+    }
+
+    #[test]
+    fn getting_hooked_ends_the_run_and_saves_the_score() {
+        let mut game = TestGame::new(3);
+        game.restart();
+        game.step(TestGame::frames(3.0));
+
+        // wait for a boat to bring its hook into the arena, then swim into it
+        let reach = game.resource::<Arena>().width / 2.0 - 32.0;
+        let mut hooked = false;
+        for _ in 0..TestGame::frames(15.0) {
+            let hook = game
+                .app
+                .world
+                .query_filtered::<&GlobalTransform, With<Hook>>()
+                .iter(&game.app.world)
+                .map(GlobalTransform::translation)
+                .find(|position| position.x.abs() < reach);
+            if let Some(hook) = hook {
+                let offset = hook - player_position(&mut game);
+                steer(&mut game, offset.x, KeyCode::KeyA, KeyCode::KeyD);
+                steer(&mut game, offset.y, KeyCode::KeyS, KeyCode::KeyW);
+            }
+            if game.step_until::<PlayerHooked>(1) {
+                hooked = true;
+                break;
+            }
+        }
+        assert!(hooked, "The player never reached a hook");
+
+        assert!(game.step_until::<GameOver>(2));
+        assert_eq!(game.received::<PlayerHooked>(), 1);
+        assert_eq!(game.received::<ScoreSaved>(), 1);
+        assert!(storage::load("scores").is_some());
+        assert!(!game.resource::<GameState>().is_running());
+
+        let score = game.resource::<Score>().count;
+        game.step(TestGame::frames(2.0));
+        assert_eq!(game.resource::<Score>().count, score);
+    }
+
+    #[test]
+    fn starving_ends_the_run() {
+        let mut game = TestGame::new(5);
+        game.watch::<PlayerStarved>();
+        game.restart();
+
+        // skip ahead to the last second before the hunger runs out, before any boat shows up
+        game.app
+            .world
+            .query::<&mut HungerCountdown>()
+            .single_mut(&mut game.app.world)
+            .time_left = 1.0;
+
+        assert!(game.step_until::<GameOver>(TestGame::frames(1.5)));
+        assert_eq!(game.received::<PlayerStarved>(), 1);
+        assert_eq!(game.received::<PlayerHooked>(), 0);
+        assert_eq!(game.received::<ScoreSaved>(), 1);
+    }
+}
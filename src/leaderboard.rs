@@ -1,68 +1,85 @@
 use serde::{Deserialize, Serialize};
-
-#[cfg(not(target_arch = "wasm32"))]
-use std::{
-    fs::OpenOptions,
-    io::{Read, Write},
-};
+use std::collections::HashMap;
 
 use bevy::prelude::*;
 
+use crate::accessibility::{GameSpeeds, RunAssists};
+use crate::daily::DailyChallenge;
+use crate::localization::Localization;
+use crate::modes::{game_mode, CurrentGameMode, GameModeId, GAME_MODES};
+use crate::modifiers::{ModifierId, Modifiers};
 use crate::shared::{
     game::{GameOver, Score},
-    stages,
+    stages, storage,
 };
 
-#[derive(Deserialize, Serialize, Resource)]
+/// A score on a leaderboard, tagged with the modifiers and game speed it was set with.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(from = "StoredScore")]
+pub struct ScoreEntry {
+    pub score: u32,
+    pub modifiers: Vec<ModifierId>,
+    pub game_speed: GameSpeeds,
+}
+
+/// Scores saved before modifiers existed are plain numbers.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredScore {
+    Plain(u32),
+    Tagged {
+        score: u32,
+        #[serde(default)]
+        modifiers: Vec<ModifierId>,
+        #[serde(default)]
+        game_speed: GameSpeeds,
+    },
+}
+
+impl From<StoredScore> for ScoreEntry {
+    fn from(stored: StoredScore) -> Self {
+        match stored {
+            StoredScore::Plain(score) => ScoreEntry {
+                score,
+                modifiers: Vec::new(),
+                game_speed: GameSpeeds::default(),
+            },
+            StoredScore::Tagged {
+                score,
+                modifiers,
+                game_speed,
+            } => ScoreEntry {
+                score,
+                modifiers,
+                game_speed,
+            },
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
 pub struct LocalScores {
-    pub scores: Vec<u32>,
+    pub scores: Vec<ScoreEntry>,
     // in case the filename is changed or something
     #[serde(skip)]
     lookup: String,
 }
 
 impl LocalScores {
-    #[cfg(not(target_arch = "wasm32"))]
-    pub fn new(key: &str) -> Self {
-        let filename = key.to_owned() + ".json";
-        debug!("Filename: {:?}", filename);
-
-        let mut contents = String::new();
-
-        let mut file = OpenOptions::new()
-            .read(true)
-            .write(true)
-            .create(true)
-            .open(filename.clone())
-            .unwrap();
-
-        file.read_to_string(&mut contents).unwrap();
-
-        Self::load_scores_from_json(&contents, &filename)
-    }
-
-    #[cfg(target_arch = "wasm32")]
     pub fn new(key: &str) -> Self {
-        let window = web_sys::window().unwrap();
-
-        if let Ok(Some(local_storage)) = window.local_storage() {
-            if let Ok(Some(value)) = local_storage.get_item(key) {
-                Self::load_scores_from_json(&value, key)
-            } else {
-                debug!("Key {:?} not found in local storage", key);
-                Self::load_scores_from_json("", key)
-            }
+        if let Some(scores_json) = storage::load(key) {
+            Self::load_scores_from_json(&scores_json, key)
         } else {
-            panic!("Could not get local storage")
+            debug!("Key {:?} not found in storage", key);
+            Self::load_scores_from_json("", key)
         }
     }
 
     fn load_scores_from_json(scores_json: &str, lookup: &str) -> Self {
-        if let Ok(loaded_scores) = serde_json::from_str::<LocalScores>(&scores_json) {
+        if let Ok(loaded_scores) = serde_json::from_str::<LocalScores>(scores_json) {
             debug!("Found existing scores in file");
             let mut existing_scores = loaded_scores.scores;
-            existing_scores.sort();
-            existing_scores.reverse();
+            existing_scores.sort_by_key(|entry| std::cmp::Reverse(entry.score));
 
             Self {
                 scores: existing_scores,
@@ -74,79 +91,132 @@ impl LocalScores {
                 scores_json
             );
             Self {
-                scores: Vec::<u32>::new(),
+                scores: Vec::<ScoreEntry>::new(),
                 lookup: lookup.to_string(),
             }
         }
     }
 
-    #[cfg(not(target_arch = "wasm32"))]
     fn save_scores(&mut self) {
-        let mut file = OpenOptions::new()
-            .create(true)
-            .write(true)
-            .open(self.lookup.clone())
-            .unwrap();
-
-        let serialized_scores = serde_json::to_string_pretty::<Self>(&self).unwrap();
-        debug!("Writing {:?} to file {:?}", serialized_scores, self.lookup);
-        file.write_all(serialized_scores.as_bytes()).unwrap();
-    }
-
-    #[cfg(target_arch = "wasm32")]
-    fn save_scores(&mut self) {
-        let window = web_sys::window().unwrap();
-
-        if let Ok(Some(local_storage)) = window.local_storage() {
-            let serialized_scores = serde_json::to_string_pretty::<Self>(&self).unwrap();
-            if let Ok(_) = local_storage.set_item(&self.lookup, &serialized_scores) {
-                debug!("Updated scores in local storage to {:?}", serialized_scores);
-            } else {
-                panic!("Could not save in local storage")
-            }
-        } else {
-            panic!("Could not get local storage")
-        }
+        let serialized_scores = serde_json::to_string_pretty::<Self>(self).unwrap();
+        storage::save(&self.lookup, &serialized_scores);
     }
 
     pub fn high_score(&self) -> Option<u32> {
         if self.scores.is_empty() {
             None
         } else {
-            Some(self.scores[0])
+            Some(self.scores[0].score)
         }
     }
 
-    pub fn add_new_score(&mut self, score: u32, score_saved_events: &mut EventWriter<ScoreSaved>) {
-        self.scores.push(score);
-        self.scores.sort();
-        self.scores.reverse();
-        self.save_scores();
-
-        score_saved_events.send(ScoreSaved {
+    /// Adds the score and returns its place on the leaderboard.
+    pub fn add_new_score(
+        &mut self,
+        score: u32,
+        modifiers: &[ModifierId],
+        game_speed: GameSpeeds,
+    ) -> usize {
+        self.scores.push(ScoreEntry {
             score,
-            score_index: self.scores.iter().position(|&r| r == score).unwrap(),
+            modifiers: modifiers.to_vec(),
+            game_speed,
         });
+        self.scores
+            .sort_by_key(|entry| std::cmp::Reverse(entry.score));
+        self.save_scores();
+
+        self.scores.iter().position(|r| r.score == score).unwrap()
     }
 }
 
-impl Default for LocalScores {
+/// Which leaderboard a score goes on.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LeaderboardId {
+    /// Regular runs are ranked against runs of the same game mode.
+    Mode(GameModeId),
+    /// Ranked daily challenge runs.
+    Daily,
+}
+
+impl LeaderboardId {
+    /// The leaderboard of the run being played. Practice daily runs aren't ranked anywhere.
+    pub fn current(
+        daily_challenge: &DailyChallenge,
+        current_mode: &CurrentGameMode,
+    ) -> Option<Self> {
+        match daily_challenge.run {
+            None => Some(LeaderboardId::Mode(current_mode.active().id())),
+            Some(run) if run.ranked => Some(LeaderboardId::Daily),
+            Some(_) => None,
+        }
+    }
+
+    pub fn title(&self, localization: &Localization) -> String {
+        match self {
+            LeaderboardId::Mode(GameModeId::Endless) => {
+                localization.get("leaderboard-title").to_string()
+            }
+            LeaderboardId::Mode(id) => localization.format(
+                "leaderboard-title-mode",
+                &[("mode", &localization.get(game_mode(*id).name_key()))],
+            ),
+            LeaderboardId::Daily => localization.get("leaderboard-title-daily").to_string(),
+        }
+    }
+}
+
+/// Every leaderboard, each saved under its own key.
+#[derive(Resource)]
+pub struct Leaderboards {
+    modes: HashMap<GameModeId, LocalScores>,
+    daily: LocalScores,
+}
+
+impl Leaderboards {
+    pub fn get(&self, id: LeaderboardId) -> &LocalScores {
+        match id {
+            LeaderboardId::Mode(mode) => &self.modes[&mode],
+            LeaderboardId::Daily => &self.daily,
+        }
+    }
+
+    fn get_mut(&mut self, id: LeaderboardId) -> &mut LocalScores {
+        match id {
+            LeaderboardId::Mode(mode) => self.modes.get_mut(&mode).unwrap(),
+            LeaderboardId::Daily => &mut self.daily,
+        }
+    }
+}
+
+impl Default for Leaderboards {
     fn default() -> Self {
-        Self::new("scores")
+        Self {
+            modes: GAME_MODES
+                .iter()
+                .map(|mode| (mode.id(), LocalScores::new(mode.leaderboard_key())))
+                .collect(),
+            daily: LocalScores::new("daily_scores"),
+        }
     }
 }
 
 #[derive(Debug, Event)]
 pub struct ScoreSaved {
+    #[allow(dead_code)]
     score: u32,
+    #[allow(dead_code)]
     score_index: usize,
+    pub leaderboard: LeaderboardId,
 }
 
 pub struct LeaderboardPlugin;
 
 impl Plugin for LeaderboardPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<LocalScores>()
+        app.init_resource::<Leaderboards>()
+            .init_resource::<DailyChallenge>()
+            .init_resource::<RunAssists>()
             .add_systems(
                 Update,
                 (update_local_scores_system).in_set(stages::HandleEventsSet),
@@ -155,17 +225,57 @@ impl Plugin for LeaderboardPlugin {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn update_local_scores_system(
     score: Res<Score>,
+    daily_challenge: Res<DailyChallenge>,
+    current_mode: Res<CurrentGameMode>,
+    modifiers: Res<Modifiers>,
+    run_assists: Res<RunAssists>,
     mut game_over_reader: EventReader<GameOver>,
-    mut local_scores: ResMut<LocalScores>,
+    mut leaderboards: ResMut<Leaderboards>,
     mut score_saved_events: EventWriter<ScoreSaved>,
 ) {
     if let Some(_game_over_event) = game_over_reader.read().next() {
+        let Some(leaderboard) = LeaderboardId::current(&daily_challenge, &current_mode) else {
+            debug!("Not saving score of a practice daily run");
+            return;
+        };
+        let scores = leaderboards.get_mut(leaderboard);
+
         debug!(
             "Saving score new score ({:?}) to file {:?}",
-            score.count, local_scores.lookup
+            score.count, scores.lookup
+        );
+        let score_index =
+            scores.add_new_score(score.count, modifiers.active(), run_assists.game_speed);
+
+        score_saved_events.send(ScoreSaved {
+            score: score.count,
+            score_index,
+            leaderboard,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_saved_without_modifiers_still_load() {
+        let scores = LocalScores::load_scores_from_json(
+            r#"{"scores": [3, 10, {"score": 7, "modifiers": ["TinyFish"], "game_speed": "Half"}]}"#,
+            "test",
         );
-        local_scores.add_new_score(score.count, &mut score_saved_events);
+
+        let loaded: Vec<(u32, usize)> = scores
+            .scores
+            .iter()
+            .map(|entry| (entry.score, entry.modifiers.len()))
+            .collect();
+        assert_eq!(loaded, vec![(10, 0), (7, 1), (3, 0)]);
+        assert!(scores.scores[1].game_speed.is_assisted());
+        assert!(!scores.scores[0].game_speed.is_assisted());
     }
 }
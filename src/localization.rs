@@ -0,0 +1,373 @@
+/**
+Translations of the game's text. Each language has a locale file mapping keys to the text they stand
+for, e.g. `"menu-start": "Press [Enter] to start"`, and text can have `{name}` placeholders that are
+filled in when it is formatted. English is built into the game and is used for any key a locale file
+is missing, so there is always something to show before the files have loaded.
+
+The language is picked in the settings, or follows the system's language when it is left on
+automatic. Text the main font has no glyphs for is drawn with the fallback font instead.
+*/
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use ab_glyph::Font as _;
+use bevy::asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext};
+use bevy::prelude::*;
+use bevy::utils::BoxedFuture;
+use serde::{Deserialize, Serialize};
+
+use crate::settings::Settings;
+use crate::shared::{render::FontHandles, stages};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default, Deserialize, Serialize)]
+pub enum Languages {
+    #[default]
+    English,
+    Spanish,
+    German,
+}
+
+pub const LANGUAGES: [Languages; 3] = [Languages::English, Languages::Spanish, Languages::German];
+
+impl Languages {
+    /// The ISO 639-1 code of the language, which its locale file is named after.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Languages::English => "en",
+            Languages::Spanish => "es",
+            Languages::German => "de",
+        }
+    }
+
+    /// The name of the language in the language itself, so players can find theirs without
+    /// reading the current one.
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Languages::English => "English",
+            Languages::Spanish => "Español",
+            Languages::German => "Deutsch",
+        }
+    }
+
+    /// Reads a browser language tag like "es-ES" or a system locale like "de_DE.UTF-8".
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let code = tag.get(..2)?.to_ascii_lowercase();
+        LANGUAGES
+            .into_iter()
+            .find(|language| language.code() == code)
+    }
+
+    fn locale_path(&self) -> String {
+        format!("locale/{}.locale.json", self.code())
+    }
+}
+
+/// The language of the system, from the locale environment variables.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn system_language() -> Languages {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|variable| std::env::var(variable).ok())
+        .find_map(|tag| Languages::from_tag(&tag))
+        .unwrap_or_default()
+}
+
+/// The language of the system, from the browser's preferred language.
+#[cfg(target_arch = "wasm32")]
+pub fn system_language() -> Languages {
+    web_sys::window()
+        .and_then(|window| window.navigator().language())
+        .and_then(|tag| Languages::from_tag(&tag))
+        .unwrap_or_default()
+}
+
+/// The language the settings ask for, where no language means following the system.
+fn chosen_language(settings: &Settings) -> Languages {
+    settings.language.unwrap_or_else(system_language)
+}
+
+/// The text of every key in one language.
+#[derive(Debug, Clone, Default, Deserialize, Asset, TypePath)]
+pub struct LocaleStrings(HashMap<String, String>);
+
+#[derive(Default)]
+struct LocaleStringsLoader;
+
+impl AssetLoader for LocaleStringsLoader {
+    type Asset = LocaleStrings;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a Self::Settings,
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+
+            Ok(serde_json::from_slice(&bytes)?)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["locale.json"]
+    }
+}
+
+#[derive(Debug, Resource)]
+struct LocaleHandles(HashMap<Languages, Handle<LocaleStrings>>);
+
+impl FromWorld for LocaleHandles {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.get_resource::<AssetServer>().unwrap();
+        debug!("Loading locale files...");
+        Self(
+            LANGUAGES
+                .into_iter()
+                .map(|language| (language, asset_server.load(language.locale_path())))
+                .collect(),
+        )
+    }
+}
+
+/// The text of the current language.
+#[derive(Debug, Resource)]
+pub struct Localization {
+    language: Languages,
+    strings: LocaleStrings,
+    english: LocaleStrings,
+}
+
+impl Localization {
+    pub fn new(language: Languages) -> Self {
+        Self {
+            language,
+            strings: LocaleStrings::default(),
+            english: serde_json::from_str(include_str!("../assets/locale/en.locale.json"))
+                .expect("Could not parse the built-in English locale."),
+        }
+    }
+
+    pub fn language(&self) -> Languages {
+        self.language
+    }
+
+    /// The text of a key, or the key itself when no language has it so that it stands out.
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.strings
+            .0
+            .get(key)
+            .or_else(|| self.english.0.get(key))
+            .map_or(key, String::as_str)
+    }
+
+    /// The text of a key with its `{name}` placeholders filled in.
+    pub fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        args.iter()
+            .fold(self.get(key).to_string(), |text, (name, value)| {
+                text.replace(&format!("{{{}}}", name), &value.to_string())
+            })
+    }
+}
+
+impl FromWorld for Localization {
+    fn from_world(world: &mut World) -> Self {
+        let language = world
+            .get_resource::<Settings>()
+            .map(chosen_language)
+            .unwrap_or_default();
+        Localization::new(language)
+    }
+}
+
+/// Text that shows a key, and is translated again whenever the language changes. Only the first
+/// section of the text is translated.
+#[derive(Debug, PartialEq, Eq, Component)]
+pub struct LocalizedText(pub &'static str);
+
+/// Plugin for translating text. Added before the UI so the text of the chosen language is there
+/// when the UI is spawned.
+pub struct LocalizationPlugin;
+
+impl Plugin for LocalizationPlugin {
+    fn build(&self, app: &mut App) {
+        debug!("Building localization plugin...");
+        app.init_asset::<LocaleStrings>()
+            .init_asset_loader::<LocaleStringsLoader>()
+            .init_resource::<LocaleHandles>()
+            .init_resource::<Localization>()
+            .add_systems(
+                Update,
+                (update_localization_system).in_set(stages::HandleEventsSet),
+            )
+            .add_systems(
+                Update,
+                (update_localized_texts_system, font_fallback_system)
+                    .chain()
+                    .in_set(stages::PrepareRenderSet),
+            );
+    }
+}
+
+/// Switches languages when the settings change, and swaps in the locale file of the current
+/// language once it has loaded and whenever it is edited.
+fn update_localization_system(
+    settings: Res<Settings>,
+    mut asset_events: EventReader<AssetEvent<LocaleStrings>>,
+    locales: Res<Assets<LocaleStrings>>,
+    locale_handles: Res<LocaleHandles>,
+    mut localization: ResMut<Localization>,
+) {
+    let language = if settings.is_changed() {
+        chosen_language(&settings)
+    } else {
+        localization.language
+    };
+
+    let locale_id = locale_handles.0[&language].id();
+    let was_loaded = asset_events
+        .read()
+        .any(|event| event.is_added(locale_id) || event.is_modified(locale_id));
+
+    if language == localization.language && !was_loaded {
+        return;
+    }
+
+    debug!("Showing text in {}", language.native_name());
+    localization.language = language;
+    // until the file has loaded, everything is shown in English
+    localization.strings = locales.get(locale_id).cloned().unwrap_or_default();
+}
+
+fn update_localized_texts_system(
+    localization: Res<Localization>,
+    mut text_query: Query<(&mut Text, Ref<LocalizedText>)>,
+) {
+    for (mut text, localized_text) in text_query.iter_mut() {
+        if localization.is_changed() || localized_text.is_changed() {
+            text.sections[0].value = localization.get(localized_text.0).to_string();
+        }
+    }
+}
+
+/// Whether the font can draw every character of the text.
+fn has_glyphs(font: &Font, value: &str) -> bool {
+    value
+        .chars()
+        .filter(|character| !character.is_whitespace())
+        .all(|character| font.font.glyph_id(character).0 != 0)
+}
+
+/// Draws text sections the main font is missing glyphs for with the fallback font, and swaps them
+/// back once their text fits the main font again. Sections in other fonts are left alone. The main
+/// font loads after most text is spawned, so every text is checked again when it does.
+fn font_fallback_system(
+    fonts: Res<FontHandles>,
+    font_assets: Res<Assets<Font>>,
+    mut font_events: EventReader<AssetEvent<Font>>,
+    mut text_query: Query<&mut Text>,
+) {
+    let main_font_loaded = font_events
+        .read()
+        .any(|event| event.is_loaded_with_dependencies(&fonts.main_font));
+
+    let Some(main_font) = font_assets.get(&fonts.main_font) else {
+        return;
+    };
+
+    for mut text in text_query.iter_mut() {
+        if !main_font_loaded && !text.is_changed() {
+            continue;
+        }
+
+        let swaps: Vec<(usize, Handle<Font>)> = text
+            .sections
+            .iter()
+            .enumerate()
+            .filter(|(_, section)| {
+                section.style.font == fonts.main_font || section.style.font == fonts.fallback_font
+            })
+            .filter_map(|(i, section)| {
+                let font = if has_glyphs(main_font, &section.value) {
+                    &fonts.main_font
+                } else {
+                    &fonts.fallback_font
+                };
+                (section.style.font != *font).then(|| (i, font.clone()))
+            })
+            .collect();
+
+        for (i, font) in swaps {
+            text.sections[i].style.font = font;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn languages_are_read_from_tags_and_locales() {
+        assert_eq!(Languages::from_tag("es-ES"), Some(Languages::Spanish));
+        assert_eq!(Languages::from_tag("de_DE.UTF-8"), Some(Languages::German));
+        assert_eq!(Languages::from_tag("EN"), Some(Languages::English));
+        assert_eq!(Languages::from_tag("C"), None);
+    }
+
+    #[test]
+    fn locale_files_only_have_english_keys() {
+        let english = Localization::new(Languages::English).english;
+        for locale in [
+            include_str!("../assets/locale/es.locale.json"),
+            include_str!("../assets/locale/de.locale.json"),
+        ] {
+            let strings: LocaleStrings = serde_json::from_str(locale).unwrap();
+            for key in strings.0.keys() {
+                assert!(english.0.contains_key(key), "Unknown key {:?}", key);
+            }
+        }
+
+        let localization = Localization::new(Languages::English);
+        assert_eq!(
+            localization.format("hud-combo", &[("multiplier", &3)]),
+            "Combo x3"
+        );
+        assert_eq!(localization.get("missing-key"), "missing-key");
+    }
+
+    #[test]
+    fn content_names_are_in_every_locale_file() {
+        let mut keys: Vec<&str> = Vec::new();
+        for mode in crate::modes::GAME_MODES {
+            keys.extend([
+                mode.name_key(),
+                mode.description_key(),
+                mode.goal_message_key(),
+            ]);
+        }
+        for modifier in crate::modifiers::MODIFIERS {
+            keys.extend([modifier.name_key, modifier.description_key]);
+        }
+        for achievement in crate::achievements::ACHIEVEMENTS {
+            keys.extend([achievement.name_key, achievement.description_key]);
+        }
+        for skin in crate::player::skins::SKINS {
+            keys.push(skin.name_key);
+        }
+
+        for locale in [
+            include_str!("../assets/locale/en.locale.json"),
+            include_str!("../assets/locale/es.locale.json"),
+            include_str!("../assets/locale/de.locale.json"),
+        ] {
+            let strings: LocaleStrings = serde_json::from_str(locale).unwrap();
+            for key in &keys {
+                assert!(strings.0.contains_key(*key), "Missing key {:?}", key);
+            }
+        }
+    }
+}
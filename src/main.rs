@@ -2,36 +2,51 @@ use bevy::{
     asset::AssetMetaCheck,
     //    diagnostic::LogDiagnosticsPlugin,
     prelude::*,
-    window::{PresentMode, WindowMode},
 };
+mod accessibility;
+mod achievements;
 mod audio;
+mod background;
+mod camera;
+mod combo;
+mod daily;
+#[cfg(feature = "dev")]
+mod debug;
+#[cfg(test)]
+mod harness;
 mod leaderboard;
+mod localization;
+mod modes;
+mod modifiers;
 mod objects;
+mod particles;
 mod player;
+mod settings;
 mod shared;
+mod stats;
 mod ui;
 
 fn main() {
+    // the window and the log are set up with the saved settings
+    let settings = settings::Settings::load();
+
     let default_plugins = DefaultPlugins
         .set(WindowPlugin {
             primary_window: Some(Window {
                 title: "Stay Off the Line!".to_string(),
-                resolution: (1280., 720.).into(),
-                present_mode: PresentMode::AutoNoVsync,
+                resolution: settings.graphics.resolution(),
+                present_mode: settings.graphics.present_mode(),
                 prevent_default_event_handling: false,
                 #[cfg(target_arch = "wasm32")]
                 canvas: Some("#fish-game".to_string()),
                 resizable: true,
-                mode: WindowMode::Windowed,
+                mode: settings.graphics.window_mode(),
                 ..default()
             }),
             ..default()
         })
         .set(bevy::log::LogPlugin {
-            #[cfg(debug_assertions)]
-            level: bevy::log::Level::DEBUG,
-            #[cfg(not(debug_assertions))]
-            level: bevy::log::Level::ERROR,
+            level: settings.log_level.level(),
             ..default()
         })
         .set(ImagePlugin::default_nearest());
@@ -46,15 +61,35 @@ fn main() {
 
     app.insert_resource(ClearColor(Color::rgb_u8(230, 202, 173)))
         .insert_resource(AssetMetaCheck::Never)
+        .insert_resource(settings)
         .add_plugins((
             default_plugins,
-            shared::SharedPlugin,
+            (shared::SharedPlugin, shared::SharedRenderPlugin),
+            background::BackgroundPlugin,
+            (
+                settings::SettingsPlugin,
+                accessibility::AccessibilityPlugin,
+                localization::LocalizationPlugin,
+            ),
+            camera::CameraEffectsPlugin,
+            daily::DailyChallengePlugin,
             leaderboard::LeaderboardPlugin,
-            player::PlayerPlugin,
-            objects::ObjectPlugins,
+            (
+                modifiers::ModifiersPlugin,
+                modes::GameModesPlugin,
+                combo::ComboPlugin,
+            ),
+            stats::StatsPlugin,
+            achievements::AchievementsPlugin,
+            (player::PlayerPlugin, player::PlayerRenderPlugin),
+            (objects::ObjectPlugins, objects::ObjectRenderPlugin),
+            particles::ParticlesPlugin,
             ui::UIPlugin,
             audio::AudioPlugin,
         ));
 
+    #[cfg(feature = "dev")]
+    app.add_plugins(debug::DebugPlugin);
+
     app.run();
 }
@@ -0,0 +1,408 @@
+/**
+Game modes decide how a run is scored, when it ends besides the fish dying and what the HUD shows
+about it. Endless survival is the default mode. The other modes have a goal, and reaching it ends
+the run with a bonus on top of the score.
+
+A mode is chosen between runs and is used from the next run on. Daily challenge runs are always
+played in endless mode.
+*/
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::daily::{self, DailyChallenge};
+use crate::localization::Localization;
+use crate::modifiers::Modifiers;
+use crate::player::events::PlayerAte;
+use crate::shared::{
+    game::{GameRestarted, GameState, GoalReached, Score, SCORE_PER_WORM},
+    stages, storage,
+};
+
+const GAME_MODE_KEY: &str = "game_mode";
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub enum GameModeId {
+    Endless,
+    WormRush,
+    Survival,
+    Zen,
+}
+
+/// How far along the current run is, for modes to check their goals against.
+#[derive(Debug, Default, Resource)]
+pub struct RunProgress {
+    /// Seconds spent running, not counting pauses.
+    pub time: f32,
+    pub worms_eaten: u32,
+}
+
+/**
+The rules of a game mode. Every rule has a default that matches endless survival, so modes only
+have to override the rules they change.
+*/
+pub trait GameMode: Sync {
+    fn id(&self) -> GameModeId;
+    /// Keys of the name and the description in the locale files.
+    fn name_key(&self) -> &'static str;
+    fn description_key(&self) -> &'static str;
+
+    /// Storage key of the mode's leaderboard.
+    fn leaderboard_key(&self) -> &'static str;
+
+    fn points_per_second(&self) -> u32 {
+        1
+    }
+
+    /// Points for eating a worm, before the combo multiplier.
+    fn points_per_worm(&self) -> u32 {
+        SCORE_PER_WORM
+    }
+
+    /// How many boosts the fish can hold, if the mode limits it.
+    fn max_boosts(&self) -> Option<u8> {
+        None
+    }
+
+    fn worms_give_boosts(&self) -> bool {
+        true
+    }
+
+    fn has_hunger(&self) -> bool {
+        true
+    }
+
+    fn goal_reached(&self, _progress: &RunProgress) -> bool {
+        false
+    }
+
+    /// Points added to the score when the goal is reached.
+    fn goal_bonus(&self, _progress: &RunProgress) -> u32 {
+        0
+    }
+
+    /// Key of the text shown on the game over screen when the goal is reached.
+    fn goal_message_key(&self) -> &'static str {
+        "game-over-done"
+    }
+
+    /// Extra text for the HUD while the mode is being played.
+    fn hud_text(&self, _progress: &RunProgress, _localization: &Localization) -> Option<String> {
+        None
+    }
+}
+
+/// Survive for as long as possible.
+struct Endless;
+
+impl GameMode for Endless {
+    fn id(&self) -> GameModeId {
+        GameModeId::Endless
+    }
+
+    fn name_key(&self) -> &'static str {
+        "mode-endless"
+    }
+
+    fn description_key(&self) -> &'static str {
+        "mode-endless-description"
+    }
+
+    fn leaderboard_key(&self) -> &'static str {
+        "scores"
+    }
+}
+
+const WORM_RUSH_WORMS: u32 = 20;
+/// Reaching the goal after this many seconds gives no time bonus.
+const WORM_RUSH_PAR_TIME: f32 = 120.0;
+const WORM_RUSH_POINTS_PER_SECOND_LEFT: f32 = 10.0;
+
+/// Eat a number of worms as fast as possible.
+struct WormRush;
+
+impl GameMode for WormRush {
+    fn id(&self) -> GameModeId {
+        GameModeId::WormRush
+    }
+
+    fn name_key(&self) -> &'static str {
+        "mode-worm-rush"
+    }
+
+    fn description_key(&self) -> &'static str {
+        "mode-worm-rush-description"
+    }
+
+    fn leaderboard_key(&self) -> &'static str {
+        "scores_worm_rush"
+    }
+
+    fn points_per_second(&self) -> u32 {
+        0
+    }
+
+    fn goal_reached(&self, progress: &RunProgress) -> bool {
+        progress.worms_eaten >= WORM_RUSH_WORMS
+    }
+
+    fn goal_bonus(&self, progress: &RunProgress) -> u32 {
+        ((WORM_RUSH_PAR_TIME - progress.time).max(0.0) * WORM_RUSH_POINTS_PER_SECOND_LEFT) as u32
+    }
+
+    fn goal_message_key(&self) -> &'static str {
+        "game-over-full"
+    }
+
+    fn hud_text(&self, progress: &RunProgress, localization: &Localization) -> Option<String> {
+        Some(localization.format(
+            "hud-worm-rush",
+            &[
+                ("worms", &progress.worms_eaten),
+                ("goal", &WORM_RUSH_WORMS),
+                ("time", &format!("{:.1}", progress.time)),
+            ],
+        ))
+    }
+}
+
+const SURVIVAL_TIME: f32 = 120.0;
+const SURVIVAL_BONUS: u32 = 100;
+
+/// Survive for a while with a single boost.
+struct Survival;
+
+impl GameMode for Survival {
+    fn id(&self) -> GameModeId {
+        GameModeId::Survival
+    }
+
+    fn name_key(&self) -> &'static str {
+        "mode-survival"
+    }
+
+    fn description_key(&self) -> &'static str {
+        "mode-survival-description"
+    }
+
+    fn leaderboard_key(&self) -> &'static str {
+        "scores_survival"
+    }
+
+    fn max_boosts(&self) -> Option<u8> {
+        Some(1)
+    }
+
+    fn worms_give_boosts(&self) -> bool {
+        false
+    }
+
+    fn goal_reached(&self, progress: &RunProgress) -> bool {
+        progress.time >= SURVIVAL_TIME
+    }
+
+    fn goal_bonus(&self, _progress: &RunProgress) -> u32 {
+        SURVIVAL_BONUS
+    }
+
+    fn goal_message_key(&self) -> &'static str {
+        "game-over-survived"
+    }
+
+    fn hud_text(&self, progress: &RunProgress, localization: &Localization) -> Option<String> {
+        let time_left = (SURVIVAL_TIME - progress.time).max(0.0).ceil() as u32;
+
+        Some(localization.format(
+            "hud-survival",
+            &[("time", &format!("{}:{:02}", time_left / 60, time_left % 60))],
+        ))
+    }
+}
+
+/// Swim around without getting hungry.
+struct Zen;
+
+impl GameMode for Zen {
+    fn id(&self) -> GameModeId {
+        GameModeId::Zen
+    }
+
+    fn name_key(&self) -> &'static str {
+        "mode-zen"
+    }
+
+    fn description_key(&self) -> &'static str {
+        "mode-zen-description"
+    }
+
+    fn leaderboard_key(&self) -> &'static str {
+        "scores_zen"
+    }
+
+    fn has_hunger(&self) -> bool {
+        false
+    }
+}
+
+pub const GAME_MODES: &[&dyn GameMode] = &[&Endless, &WormRush, &Survival, &Zen];
+
+pub fn game_mode(id: GameModeId) -> &'static dyn GameMode {
+    *GAME_MODES
+        .iter()
+        .find(|mode| mode.id() == id)
+        .expect("Every game mode should be in GAME_MODES")
+}
+
+/// The mode chosen for the next run and the mode of the run being played.
+#[derive(Debug, Resource)]
+pub struct CurrentGameMode {
+    /// Chosen between runs and saved between sessions.
+    selected: GameModeId,
+    active: GameModeId,
+}
+
+impl CurrentGameMode {
+    pub fn load() -> Self {
+        let selected = storage::load_json(GAME_MODE_KEY).unwrap_or_else(|| {
+            debug!("No saved game mode found, playing endless");
+            GameModeId::Endless
+        });
+
+        Self {
+            selected,
+            active: selected,
+        }
+    }
+
+    pub fn selected(&self) -> &'static dyn GameMode {
+        game_mode(self.selected)
+    }
+
+    /// Moves on to the next mode for the next run.
+    pub fn select_next(&mut self) {
+        let index = GAME_MODES
+            .iter()
+            .position(|mode| mode.id() == self.selected)
+            .unwrap_or(0);
+        self.selected = GAME_MODES[(index + 1) % GAME_MODES.len()].id();
+
+        debug!("Selected game mode {:?}", self.selected);
+        storage::save_json(GAME_MODE_KEY, &self.selected);
+    }
+
+    /// The mode of the run being played.
+    pub fn active(&self) -> &'static dyn GameMode {
+        game_mode(self.active)
+    }
+}
+
+pub struct GameModesPlugin;
+
+impl Plugin for GameModesPlugin {
+    fn build(&self, app: &mut App) {
+        debug!("Building game modes plugin...");
+        app.insert_resource(CurrentGameMode::load())
+            .init_resource::<RunProgress>()
+            .init_resource::<DailyChallenge>()
+            .add_systems(
+                Update,
+                (track_run_progress_system, check_goal_system)
+                    .chain()
+                    .in_set(stages::EmitEventsSet),
+            )
+            .add_systems(
+                Update,
+                (activate_game_mode_on_restart)
+                    .after(daily::start_daily_run_system)
+                    .in_set(stages::HandleEventsSet),
+            );
+    }
+}
+
+/// Decides which mode the new run is played in. Systems that set up the run read the mode while
+/// handling the restart, so they should be ordered after this one.
+pub(crate) fn activate_game_mode_on_restart(
+    daily_challenge: Res<DailyChallenge>,
+    mut restart_reader: EventReader<GameRestarted>,
+    mut current_mode: ResMut<CurrentGameMode>,
+    mut progress: ResMut<RunProgress>,
+) {
+    if restart_reader.read().next().is_none() {
+        return;
+    }
+
+    current_mode.active = if daily_challenge.run.is_some() {
+        GameModeId::Endless
+    } else {
+        current_mode.selected
+    };
+    *progress = RunProgress::default();
+
+    debug!("Starting run in game mode {:?}", current_mode.active);
+}
+
+fn track_run_progress_system(
+    time: Res<Time>,
+    game_state: Res<GameState>,
+    mut player_ate_reader: EventReader<PlayerAte>,
+    mut progress: ResMut<RunProgress>,
+) {
+    if !game_state.is_running() {
+        return;
+    }
+
+    progress.time += time.delta_seconds();
+    progress.worms_eaten += player_ate_reader.read().count() as u32;
+}
+
+/// Ends the run with the goal bonus once the mode's goal is reached.
+fn check_goal_system(
+    game_state: Res<GameState>,
+    current_mode: Res<CurrentGameMode>,
+    modifiers: Res<Modifiers>,
+    progress: Res<RunProgress>,
+    mut score: ResMut<Score>,
+    mut goal_reached_events: EventWriter<GoalReached>,
+) {
+    if !game_state.is_running() {
+        return;
+    }
+
+    let mode = current_mode.active();
+    if !mode.goal_reached(&progress) {
+        return;
+    }
+
+    let bonus = mode.goal_bonus(&progress);
+    debug!(
+        "Reached the goal of {:?} with a bonus of {}",
+        mode.id(),
+        bonus
+    );
+
+    score.add_points(bonus, modifiers.score_multiplier());
+    goal_reached_events.send(GoalReached);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn worm_rush_bonus_rewards_fast_runs() {
+        let mode = game_mode(GameModeId::WormRush);
+        let progress = RunProgress {
+            time: 100.0,
+            worms_eaten: WORM_RUSH_WORMS,
+        };
+
+        assert!(mode.goal_reached(&progress));
+        assert_eq!(mode.goal_bonus(&progress), 200);
+        assert_eq!(
+            mode.goal_bonus(&RunProgress {
+                time: 500.0,
+                ..progress
+            }),
+            0
+        );
+    }
+}
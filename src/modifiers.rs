@@ -0,0 +1,242 @@
+/**
+Modifiers change the rules of a run, like how fast the fish sinks or how big the hooks are. They
+are chosen between runs and take effect when the next run starts. Each modifier scales the score
+of the run, so that harder modifiers are worth more points and easier ones are worth less.
+
+Daily challenge runs ignore the chosen modifiers and use the modifier of the day instead.
+*/
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::daily::{self, DailyChallenge};
+use crate::shared::{game::GameRestarted, stages, storage};
+
+const MODIFIERS_KEY: &str = "modifiers";
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub enum ModifierId {
+    LowGravity,
+    InfiniteBoosts,
+    FastHunger,
+    MirrorControls,
+    GiantHooks,
+    TinyFish,
+    HeavySink,
+    NoBoosts,
+}
+
+#[derive(Debug)]
+pub struct ModifierDefinition {
+    pub id: ModifierId,
+    /// Keys of the name and the description in the locale files.
+    pub name_key: &'static str,
+    pub description_key: &'static str,
+    /// What the score of a run with the modifier is multiplied by.
+    pub score_multiplier: f32,
+}
+
+pub const MODIFIERS: &[ModifierDefinition] = &[
+    ModifierDefinition {
+        id: ModifierId::LowGravity,
+        name_key: "modifier-low-gravity",
+        description_key: "modifier-low-gravity-description",
+        score_multiplier: 0.8,
+    },
+    ModifierDefinition {
+        id: ModifierId::InfiniteBoosts,
+        name_key: "modifier-infinite-boosts",
+        description_key: "modifier-infinite-boosts-description",
+        score_multiplier: 0.5,
+    },
+    ModifierDefinition {
+        id: ModifierId::FastHunger,
+        name_key: "modifier-fast-hunger",
+        description_key: "modifier-fast-hunger-description",
+        score_multiplier: 1.5,
+    },
+    ModifierDefinition {
+        id: ModifierId::MirrorControls,
+        name_key: "modifier-mirror-controls",
+        description_key: "modifier-mirror-controls-description",
+        score_multiplier: 1.3,
+    },
+    ModifierDefinition {
+        id: ModifierId::GiantHooks,
+        name_key: "modifier-giant-hooks",
+        description_key: "modifier-giant-hooks-description",
+        score_multiplier: 1.5,
+    },
+    ModifierDefinition {
+        id: ModifierId::TinyFish,
+        name_key: "modifier-tiny-fish",
+        description_key: "modifier-tiny-fish-description",
+        score_multiplier: 0.8,
+    },
+    ModifierDefinition {
+        id: ModifierId::HeavySink,
+        name_key: "modifier-heavy-sink",
+        description_key: "modifier-heavy-sink-description",
+        score_multiplier: 1.3,
+    },
+    ModifierDefinition {
+        id: ModifierId::NoBoosts,
+        name_key: "modifier-no-boosts",
+        description_key: "modifier-no-boosts-description",
+        score_multiplier: 1.5,
+    },
+];
+
+pub fn modifier_definition(id: ModifierId) -> &'static ModifierDefinition {
+    MODIFIERS
+        .iter()
+        .find(|modifier| modifier.id == id)
+        .expect("Every modifier should have a definition")
+}
+
+/// The score multiplier of a set of modifiers.
+pub fn score_multiplier(modifiers: &[ModifierId]) -> f32 {
+    modifiers
+        .iter()
+        .map(|&id| modifier_definition(id).score_multiplier)
+        .product()
+}
+
+/// The modifiers chosen for the next run and the modifiers of the run being played.
+#[derive(Debug, Default, Resource)]
+pub struct Modifiers {
+    /// Chosen between runs and saved between sessions, in the order of `MODIFIERS`.
+    selected: Vec<ModifierId>,
+    active: Vec<ModifierId>,
+}
+
+impl Modifiers {
+    pub fn load() -> Self {
+        let selected = storage::load_json(MODIFIERS_KEY).unwrap_or_else(|| {
+            debug!("No saved modifiers found, playing without any");
+            Vec::new()
+        });
+
+        Self {
+            active: selected.clone(),
+            selected,
+        }
+    }
+
+    fn save(&self) {
+        storage::save_json(MODIFIERS_KEY, &self.selected);
+    }
+
+    pub fn selected(&self) -> &[ModifierId] {
+        &self.selected
+    }
+
+    pub fn is_selected(&self, id: ModifierId) -> bool {
+        self.selected.contains(&id)
+    }
+
+    /// Turns a modifier on or off for the next run.
+    pub fn toggle(&mut self, id: ModifierId) {
+        if self.is_selected(id) {
+            self.selected.retain(|&selected| selected != id);
+        } else {
+            self.selected.push(id);
+            self.selected.sort_by_key(|&selected| {
+                MODIFIERS
+                    .iter()
+                    .position(|modifier| modifier.id == selected)
+            });
+        }
+
+        debug!("Selected modifiers: {:?}", self.selected);
+        self.save();
+    }
+
+    /// The modifiers of the run being played.
+    pub fn active(&self) -> &[ModifierId] {
+        &self.active
+    }
+
+    pub fn is_active(&self, id: ModifierId) -> bool {
+        self.active.contains(&id)
+    }
+
+    pub fn score_multiplier(&self) -> f32 {
+        score_multiplier(&self.active)
+    }
+
+    /// How much faster than usual the fish sinks.
+    pub fn sink_weight_scale(&self) -> f32 {
+        let mut scale = 1.0;
+        if self.is_active(ModifierId::LowGravity) {
+            scale *= 0.5;
+        }
+        if self.is_active(ModifierId::HeavySink) {
+            scale *= 2.0;
+        }
+        scale
+    }
+
+    /// How much faster than usual the fish gets hungry.
+    pub fn hunger_drain_rate(&self) -> f32 {
+        if self.is_active(ModifierId::FastHunger) {
+            2.0
+        } else {
+            1.0
+        }
+    }
+
+    pub fn hook_size_scale(&self) -> f32 {
+        if self.is_active(ModifierId::GiantHooks) {
+            2.0
+        } else {
+            1.0
+        }
+    }
+
+    pub fn player_size_scale(&self) -> f32 {
+        if self.is_active(ModifierId::TinyFish) {
+            0.5
+        } else {
+            1.0
+        }
+    }
+}
+
+pub struct ModifiersPlugin;
+
+impl Plugin for ModifiersPlugin {
+    fn build(&self, app: &mut App) {
+        debug!("Building modifiers plugin...");
+        app.insert_resource(Modifiers::load())
+            .init_resource::<DailyChallenge>()
+            .add_systems(
+                Update,
+                (activate_modifiers_on_restart)
+                    .after(daily::start_daily_run_system)
+                    .in_set(stages::HandleEventsSet),
+            );
+    }
+}
+
+/// Decides which modifiers the new run is played with. Systems that set up the run read the
+/// modifiers while handling the restart, so they should be ordered after this one.
+pub(crate) fn activate_modifiers_on_restart(
+    daily_challenge: Res<DailyChallenge>,
+    mut restart_reader: EventReader<GameRestarted>,
+    mut modifiers: ResMut<Modifiers>,
+) {
+    if restart_reader.read().next().is_none() {
+        return;
+    }
+
+    modifiers.active = match daily_challenge.run {
+        Some(run) => run.modifier.into_iter().collect(),
+        None => modifiers.selected.clone(),
+    };
+
+    debug!(
+        "Starting run with modifiers {:?} (score x{})",
+        modifiers.active,
+        modifiers.score_multiplier()
+    );
+}
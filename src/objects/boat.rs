@@ -2,13 +2,15 @@ use bevy::prelude::*;
 use bevy_prototype_lyon::prelude::*;
 use std::collections::HashMap;
 
+use crate::modifiers::Modifiers;
 use crate::player::{
     attributes::Player,
     events::{PlayerAte, PlayerBonked, PlayerHooked},
 };
 use crate::shared::{
-    animation::{Animation, AnimationFrame, AnimationState},
+    animation::{Animation, AnimationSet, AnimationState},
     arena::Arena,
+    atlas::SpriteAtlas,
     collision::Collider,
     game::{Difficulty, GameOver, GameRestarted, GameState},
     movement::{Destination, Follow, Velocity},
@@ -19,16 +21,44 @@ use crate::shared::{
 use rand::Rng;
 use rand_chacha::ChaCha8Rng;
 
-#[derive(Debug)]
-enum BoatTypes {
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Component)]
+pub(crate) enum BoatTypes {
     Dinghy,
     Fishingboat,
     Speedboat,
     Yacht,
 }
 
+/**
+Changes to the stats of every boat that spawns, so that boats can be tuned from the debug
+inspector while the game is running. Only exists in `dev` builds.
+*/
+#[cfg(feature = "dev")]
+#[derive(Debug, Clone, Resource)]
+pub(crate) struct BoatTuning {
+    pub speed: f32,
+    /// Scales both the width and height of boats.
+    pub size: f32,
+    pub worm_chance: f32,
+    /// Added to the number of fishing poles, boats always keep at least one.
+    pub extra_poles: i32,
+}
+
+#[cfg(feature = "dev")]
+impl Default for BoatTuning {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            size: 1.0,
+            worm_chance: 1.0,
+            extra_poles: 0,
+        }
+    }
+}
+
 #[derive(Debug, Component)]
 struct BoatStats {
+    boat_type: BoatTypes,
     num_poles: u8,
     speed: f32,
     width: f32,
@@ -38,37 +68,102 @@ struct BoatStats {
 
 #[derive(Debug, Resource)]
 pub(super) struct BoatMaterials {
-    boat: Handle<Image>,
+    /// The sprite atlas that every boat sprite comes from.
+    atlas_image: Handle<Image>,
+    atlas_layout: Handle<TextureAtlasLayout>,
+    boat: usize,
     line: Color,
-    worm: Animation,
-    hook: Handle<Image>,
+    /// Shown before the worm animation has loaded.
+    worm_sprite: usize,
+    /// Empty until the object animation file has loaded.
+    worm: Option<Animation>,
+    hook: usize,
+    animation_set: Handle<AnimationSet>,
 }
 
+const OBJECT_ANIMATIONS_PATH: &str = "animations/objects.animations.json";
+
 impl FromWorld for BoatMaterials {
     fn from_world(world: &mut World) -> Self {
-        let asset_server = world.get_resource::<AssetServer>().unwrap();
+        let sprite_atlas = world.get_resource::<SpriteAtlas>().unwrap();
 
         BoatMaterials {
-            boat: asset_server.load("sprites/boat/boat.png"),
+            atlas_image: sprite_atlas.image.clone(),
+            atlas_layout: sprite_atlas.layout.clone(),
+            boat: sprite_atlas.index("boat"),
             line: Color::BLACK,
-            worm: Animation {
-                should_loop: true,
-                frames: vec![
-                    AnimationFrame {
-                        material_handle: asset_server.load("sprites/worm/worm1.png"),
-                        time: 0.5,
-                    },
-                    AnimationFrame {
-                        material_handle: asset_server.load("sprites/worm/worm2.png"),
-                        time: 0.5,
-                    },
-                ],
-            },
-            hook: asset_server.load("sprites/hook/hook.png"),
+            worm_sprite: sprite_atlas.index("worm1"),
+            worm: None,
+            hook: sprite_atlas.index("hook"),
+            // worms keep their still sprite without an asset server, e.g. in tests
+            animation_set: world
+                .get_resource::<AssetServer>()
+                .map(|asset_server| asset_server.load(OBJECT_ANIMATIONS_PATH))
+                .unwrap_or_default(),
         }
     }
 }
 
+/// Picks up the worm animation once the object animation file loads, and again whenever the file
+/// changes. Worms that were spawned before the file loaded start wriggling, and worms that
+/// already have an animation keep it.
+pub(super) fn load_object_animations_system(
+    mut commands: Commands,
+    mut asset_events: EventReader<AssetEvent<AnimationSet>>,
+    animation_sets: Res<Assets<AnimationSet>>,
+    sprite_atlas: Res<SpriteAtlas>,
+    mut boat_materials: ResMut<BoatMaterials>,
+    worm_query: Query<(&Worm, Entity), Without<AnimationState>>,
+) {
+    let animation_set_id = boat_materials.animation_set.id();
+    let was_loaded = asset_events
+        .read()
+        .any(|event| event.is_added(animation_set_id) || event.is_modified(animation_set_id));
+
+    if !was_loaded {
+        return;
+    }
+
+    let Some(animation_set) = animation_sets.get(animation_set_id) else {
+        return;
+    };
+
+    debug!("Building object animations...");
+    boat_materials.worm = match animation_set.get("worm") {
+        Some(definition) => Some(definition.resolve(|name| sprite_atlas.index(name))),
+        None => {
+            error!("No worm animation in {}", OBJECT_ANIMATIONS_PATH);
+            None
+        }
+    };
+
+    let Some(worm_animation) = &boat_materials.worm else {
+        return;
+    };
+
+    for (worm, worm_entity) in worm_query.iter() {
+        commands
+            .entity(worm_entity)
+            .insert(AnimationState::from_animation(
+                worm_animation,
+                worm.animation_speed,
+            ));
+    }
+}
+
+#[cfg(feature = "dev")]
+impl BoatStats {
+    fn tuned(mut self, tuning: &BoatTuning) -> Self {
+        self.speed *= tuning.speed;
+        self.width *= tuning.size;
+        self.height *= tuning.size;
+        self.worm_chance = (self.worm_chance * tuning.worm_chance).min(1.0);
+        self.num_poles =
+            (self.num_poles as i32 + tuning.extra_poles).clamp(1, u8::MAX as i32) as u8;
+        self
+    }
+}
+
 fn boat_stats_factory(difficulty: u8, rng: &mut ChaCha8Rng) -> BoatStats {
     let boat_type = match rng.gen_range(1..difficulty + 1) {
         1 => BoatTypes::Dinghy,
@@ -80,6 +175,7 @@ fn boat_stats_factory(difficulty: u8, rng: &mut ChaCha8Rng) -> BoatStats {
 
     match boat_type {
         BoatTypes::Dinghy => BoatStats {
+            boat_type,
             num_poles: 1,
             speed: (rng.gen_range(30..40) + (5 * difficulty)) as f32,
             width: 45.0,
@@ -87,6 +183,7 @@ fn boat_stats_factory(difficulty: u8, rng: &mut ChaCha8Rng) -> BoatStats {
             worm_chance: 0.5,
         },
         BoatTypes::Fishingboat => BoatStats {
+            boat_type,
             num_poles: rng.gen_range(1..3) + difficulty,
             speed: (rng.gen_range(40..50) + (5 * difficulty)) as f32,
             width: 65.0,
@@ -94,6 +191,7 @@ fn boat_stats_factory(difficulty: u8, rng: &mut ChaCha8Rng) -> BoatStats {
             worm_chance: 0.8,
         },
         BoatTypes::Speedboat => BoatStats {
+            boat_type,
             num_poles: rng.gen_range(1..2) + difficulty,
             speed: (rng.gen_range(75..100) + (5 * difficulty)) as f32,
             width: 75.0,
@@ -101,6 +199,7 @@ fn boat_stats_factory(difficulty: u8, rng: &mut ChaCha8Rng) -> BoatStats {
             worm_chance: 0.4,
         },
         BoatTypes::Yacht => BoatStats {
+            boat_type,
             num_poles: rng.gen_range(3..6) + difficulty,
             speed: (rng.gen_range(60..75) + (5 * difficulty)) as f32,
             width: 128.0,
@@ -117,6 +216,8 @@ pub struct Boat;
 pub struct Worm {
     #[allow(dead_code)]
     line_entity: Entity,
+    /// How fast the worm wriggles, so worms on the same line don't move in step.
+    animation_speed: f32,
 }
 
 #[derive(Component)]
@@ -126,8 +227,8 @@ pub struct Hook {
 
 #[derive(Component)]
 pub struct Line {
-    start_point: Vec3,
-    end_point: Vec3,
+    pub(crate) start_point: Vec3,
+    pub(crate) end_point: Vec3,
 }
 
 #[derive(Debug, Resource)]
@@ -144,6 +245,8 @@ pub(super) fn boat_spawner_system(
     game_state: Res<GameState>,
     difficulty: Res<Difficulty>,
     boat_materials: Res<BoatMaterials>,
+    #[cfg(feature = "dev")] boat_tuning: Res<BoatTuning>,
+    modifiers: Res<Modifiers>,
     mut rng: ResMut<GameRng>,
     mut boat_spawner: ResMut<BoatSpawner>,
 ) {
@@ -156,13 +259,23 @@ pub(super) fn boat_spawner_system(
     if boat_spawner.spawn_timer.finished() {
         for _ in 0..rng.rng.gen_range(1..difficulty.multiplier + 1) {
             let stats = boat_stats_factory(difficulty.multiplier, &mut rng.rng);
-            spawn_boat(stats, &mut commands, &boat_materials, &arena, &mut rng.rng);
+            #[cfg(feature = "dev")]
+            let stats = stats.tuned(&boat_tuning);
+            spawn_boat(
+                stats,
+                HOOK_SIZE * modifiers.hook_size_scale(),
+                &mut commands,
+                &boat_materials,
+                &arena,
+                &mut rng.rng,
+            );
         }
     }
 }
 
 fn spawn_boat(
     stats: BoatStats,
+    hook_size: f32,
     commands: &mut Commands,
     boat_materials: &BoatMaterials,
     arena: &Arena,
@@ -206,9 +319,14 @@ fn spawn_boat(
             },
             //SideScrollDirection(facing_right),
             Boat,
+            stats.boat_type,
             RenderLayer::Objects,
-            SpriteBundle {
-                texture: boat_materials.boat.clone(),
+            SpriteSheetBundle {
+                texture: boat_materials.atlas_image.clone(),
+                atlas: TextureAtlas {
+                    layout: boat_materials.atlas_layout.clone(),
+                    index: boat_materials.boat,
+                },
                 sprite: Sprite {
                     custom_size: Some(Vec2::new(stats.width, stats.height)),
                     ..Default::default()
@@ -222,7 +340,7 @@ fn spawn_boat(
             },
         ))
         .with_children(|parent| {
-            spawn_lines(&stats, rng, parent, boat_materials);
+            spawn_lines(&stats, hook_size, rng, parent, boat_materials);
         });
 }
 
@@ -234,13 +352,12 @@ const WORM_SIZE: f32 = 16.0;
 
 fn spawn_lines(
     boat_stats: &BoatStats,
+    hook_size: f32,
     rng: &mut ChaCha8Rng,
     parent: &mut ChildBuilder,
     boat_materials: &BoatMaterials,
 ) {
     // all poles start above the top of the boat at the same y position
-    let hook_material = boat_materials.hook.clone();
-    let worm_animation = boat_materials.worm.clone();
 
     for i in 1..boat_stats.num_poles + 1 {
         // start point of the rod from the start of the boat
@@ -288,7 +405,7 @@ fn spawn_lines(
                 ..default()
             },
             Stroke {
-                color: boat_materials.line.clone(),
+                color: boat_materials.line,
                 options: StrokeOptions::default()
                     .with_line_width(FISHING_LINE_WIDTH)
                     .with_line_cap(LineCap::Round)
@@ -313,7 +430,7 @@ fn spawn_lines(
                     ..default()
                 },
                 Stroke {
-                    color: boat_materials.line.clone(),
+                    color: boat_materials.line,
                     options: StrokeOptions::default()
                         .with_line_width(FISHING_LINE_WIDTH)
                         .with_line_cap(LineCap::Round)
@@ -328,20 +445,24 @@ fn spawn_lines(
 
         // spawn the hook at the end point of the line
         let mut hook_point = line_end_point;
-        hook_point.y -= HOOK_SIZE / 2.0;
+        hook_point.y -= hook_size / 2.0;
 
         parent.spawn((
             Hook { line_entity },
             Collider {
-                width: HOOK_SIZE,
-                height: HOOK_SIZE,
+                width: hook_size,
+                height: hook_size,
             },
-            SpriteBundle {
+            SpriteSheetBundle {
                 sprite: Sprite {
-                    custom_size: Some(Vec2::new(HOOK_SIZE, HOOK_SIZE)),
+                    custom_size: Some(Vec2::new(hook_size, hook_size)),
                     ..default()
                 },
-                texture: hook_material.clone(),
+                texture: boat_materials.atlas_image.clone(),
+                atlas: TextureAtlas {
+                    layout: boat_materials.atlas_layout.clone(),
+                    index: boat_materials.hook,
+                },
                 transform: Transform::from_translation(hook_point),
                 ..Default::default()
             },
@@ -354,24 +475,37 @@ fn spawn_lines(
             let worm_pos = line_mid_point
                 - ((line_mid_point - line_end_point).normalize() * worm_distance_from_mid);
 
-            let worm_initial_animation_frame = worm_animation.frames[0].clone();
-            parent.spawn((
-                Worm { line_entity },
+            let animation_speed = rng.gen::<f32>() * 2.0;
+            let mut worm_commands = parent.spawn((
+                Worm {
+                    line_entity,
+                    animation_speed,
+                },
                 Collider {
                     width: WORM_SIZE,
                     height: WORM_SIZE,
                 },
-                AnimationState::from_animation(&worm_animation, rng.gen::<f32>() * 2.0),
-                SpriteBundle {
+                SpriteSheetBundle {
                     sprite: Sprite {
                         custom_size: Some(Vec2::new(WORM_SIZE, WORM_SIZE)),
                         ..default()
                     },
-                    texture: worm_initial_animation_frame.material_handle.clone(),
+                    texture: boat_materials.atlas_image.clone(),
+                    atlas: TextureAtlas {
+                        layout: boat_materials.atlas_layout.clone(),
+                        index: boat_materials.worm_sprite,
+                    },
                     transform: Transform::from_translation(worm_pos),
                     ..Default::default()
                 },
             ));
+
+            if let Some(worm_animation) = &boat_materials.worm {
+                worm_commands.insert(AnimationState::from_animation(
+                    worm_animation,
+                    animation_speed,
+                ));
+            }
         }
     }
 }
@@ -550,17 +684,17 @@ pub(super) fn player_hooked_handler(
 pub(super) fn redraw_line_when_hook_moves(
     mut commands: Commands,
     boat_materials: Res<BoatMaterials>,
-    hook_query: Query<(&Hook, &Transform), Changed<Transform>>,
+    hook_query: Query<(&Hook, &Transform, &Collider), Changed<Transform>>,
     mut line_query: Query<&mut Line>,
 ) {
-    for (hook_info, changed_transform) in hook_query.iter() {
+    for (hook_info, changed_transform, hook_collider) in hook_query.iter() {
         let line_entity = hook_info.line_entity;
 
         let mut line = line_query.get_mut(line_entity).unwrap();
         line.end_point = changed_transform.translation;
 
         // the line should connect to the top of the hook
-        line.end_point.y += HOOK_SIZE / 2.0;
+        line.end_point.y += hook_collider.height / 2.0;
 
         let mut builder = PathBuilder::new();
         builder.move_to([line.start_point.x, line.start_point.y].into());
@@ -577,7 +711,7 @@ pub(super) fn redraw_line_when_hook_moves(
                 ..default()
             },
             Stroke {
-                color: boat_materials.line.clone(),
+                color: boat_materials.line,
                 options: StrokeOptions::default()
                     .with_line_width(FISHING_LINE_WIDTH)
                     .with_line_cap(LineCap::Round)
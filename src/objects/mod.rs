@@ -29,10 +29,26 @@ impl Plugin for ObjectPlugins {
                 .after(stages::CalculateCollisionsSet),
         )
         .add_systems(
+            Update,
+            (boat::reset_boats_on_restart).in_set(stages::PrepareRenderSet),
+        );
+
+        #[cfg(feature = "dev")]
+        app.init_resource::<boat::BoatTuning>();
+    }
+}
+
+/// Draws boats and worms, and the fishing lines between boats and their hooks.
+pub struct ObjectRenderPlugin;
+
+impl Plugin for ObjectRenderPlugin {
+    fn build(&self, app: &mut App) {
+        debug!("Building object render plugin...");
+        app.add_systems(
             Update,
             (
+                boat::load_object_animations_system,
                 boat::redraw_line_when_hook_moves,
-                boat::reset_boats_on_restart,
             )
                 .in_set(stages::PrepareRenderSet),
         );
@@ -0,0 +1,340 @@
+/**
+Lightweight particle effects that are simulated on the CPU. Every particle is a small sprite that
+moves with the shared movement system, so particles freeze along with the rest of the game.
+*/
+use bevy::prelude::*;
+use rand::prelude::*;
+use std::collections::HashMap;
+use std::f32::consts::PI;
+
+use crate::objects::boat::Boat;
+use crate::player::attributes::Player;
+use crate::player::events::PlayerAte;
+use crate::player::states::{PlayerState, PlayerStates};
+use crate::shared::{
+    arena::Arena,
+    collision::Collider,
+    game::{GameRestarted, GameState, GameStates},
+    movement::Velocity,
+    render::RenderLayer,
+    stages,
+};
+
+/// The most particles that can be alive at once. Bursts are cut short when the budget runs out.
+const MAX_PARTICLES: usize = 300;
+const WAKE_INTERVAL: f32 = 0.05;
+/// Bubbles left behind every second while the fish boosts.
+const BUBBLE_RATE: f32 = 40.0;
+
+/// Describes a group of particles spawned at the same time.
+#[derive(Debug, Clone)]
+struct ParticleBurst {
+    count: usize,
+    color: Color,
+    size: f32,
+    /// The direction particles are launched in, in radians.
+    angle: f32,
+    /// How far from the direction particles can be launched, in radians.
+    spread: f32,
+    min_speed: f32,
+    max_speed: f32,
+    lifetime: f32,
+    /// Vertical acceleration of the particles, negative values pull them down.
+    gravity: f32,
+}
+
+const BUBBLE_TRAIL: ParticleBurst = ParticleBurst {
+    count: 1,
+    color: Color::rgba(0.9, 0.97, 1.0, 0.8),
+    size: 3.0,
+    angle: 0.0,
+    spread: 0.4,
+    min_speed: 10.0,
+    max_speed: 60.0,
+    lifetime: 0.6,
+    gravity: 150.0,
+};
+
+const CRUMB_BURST: ParticleBurst = ParticleBurst {
+    count: 12,
+    color: Color::rgb(0.55, 0.35, 0.25),
+    size: 3.0,
+    angle: PI / 2.0,
+    spread: PI,
+    min_speed: 40.0,
+    max_speed: 140.0,
+    lifetime: 0.5,
+    gravity: -300.0,
+};
+
+const SURFACE_SPLASH: ParticleBurst = ParticleBurst {
+    count: 16,
+    color: Color::rgba(0.68, 0.85, 0.9, 0.9),
+    size: 4.0,
+    angle: PI / 2.0,
+    spread: PI / 4.0,
+    min_speed: 80.0,
+    max_speed: 220.0,
+    lifetime: 0.7,
+    gravity: -500.0,
+};
+
+const WAKE_FOAM: ParticleBurst = ParticleBurst {
+    count: 1,
+    color: Color::rgba(1.0, 1.0, 1.0, 0.8),
+    size: 4.0,
+    angle: -PI / 2.0,
+    spread: PI / 3.0,
+    min_speed: 5.0,
+    max_speed: 20.0,
+    lifetime: 1.0,
+    gravity: 0.0,
+};
+
+#[derive(Debug, Component)]
+struct Particle {
+    timer: Timer,
+    gravity: f32,
+    start_alpha: f32,
+}
+
+#[derive(Debug, Default, Resource)]
+struct ParticleBudget {
+    alive: usize,
+}
+
+impl ParticleBudget {
+    fn spawn_burst(
+        &mut self,
+        commands: &mut Commands,
+        rng: &mut ThreadRng,
+        position: Vec2,
+        burst: &ParticleBurst,
+    ) {
+        let count = burst.count.min(MAX_PARTICLES - self.alive);
+
+        for _ in 0..count {
+            let angle = burst.angle + rng.gen_range(-1.0..=1.0) * burst.spread;
+            let speed = rng.gen_range(burst.min_speed..=burst.max_speed);
+
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: burst.color,
+                        custom_size: Some(Vec2::splat(burst.size)),
+                        ..Default::default()
+                    },
+                    transform: Transform::from_translation(position.extend(0.0)),
+                    ..Default::default()
+                },
+                Velocity((Vec2::from_angle(angle) * speed).extend(0.0)),
+                Particle {
+                    timer: Timer::from_seconds(burst.lifetime, TimerMode::Once),
+                    gravity: burst.gravity,
+                    start_alpha: burst.color.a(),
+                },
+                RenderLayer::Effects,
+            ));
+        }
+
+        self.alive += count;
+    }
+}
+
+pub struct ParticlesPlugin;
+
+impl Plugin for ParticlesPlugin {
+    fn build(&self, app: &mut App) {
+        debug!("Building particles plugin...");
+        app.init_resource::<ParticleBudget>()
+            .add_systems(
+                Update,
+                (
+                    bubble_trail_system,
+                    crumb_burst_system,
+                    surface_splash_system,
+                    boat_wake_system,
+                )
+                    .in_set(stages::PrepareRenderSet),
+            )
+            .add_systems(
+                Update,
+                (update_particles_system, clear_particles_on_restart)
+                    .chain()
+                    .in_set(stages::MovementSet),
+            );
+    }
+}
+
+/// Leaves a steady trail of bubbles behind a fish for as long as it boosts. Bubbles that don't add
+/// up to a whole one yet are carried over to the next frame, so the trail doesn't depend on the
+/// frame rate.
+fn bubble_trail_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    game_state: Res<GameState>,
+    mut budget: ResMut<ParticleBudget>,
+    mut owed_bubbles: Local<HashMap<Entity, f32>>,
+    player_query: Query<(Entity, &Transform, &Velocity, &PlayerState), With<Player>>,
+) {
+    if !game_state.is_running() {
+        return;
+    }
+
+    let mut rng = thread_rng();
+
+    for (player_entity, transform, velocity, player_state) in player_query.iter() {
+        if player_state.current_state != PlayerStates::Boost {
+            owed_bubbles.remove(&player_entity);
+            continue;
+        }
+
+        let owed = owed_bubbles.entry(player_entity).or_default();
+        *owed += BUBBLE_RATE * time.delta_seconds();
+        let count = owed.floor();
+        *owed -= count;
+
+        // bubbles are left behind the fish
+        let trail = ParticleBurst {
+            count: count as usize,
+            angle: (-velocity.0.y).atan2(-velocity.0.x),
+            ..BUBBLE_TRAIL
+        };
+
+        budget.spawn_burst(
+            &mut commands,
+            &mut rng,
+            transform.translation.truncate(),
+            &trail,
+        );
+    }
+}
+
+fn crumb_burst_system(
+    mut commands: Commands,
+    mut budget: ResMut<ParticleBudget>,
+    mut player_ate_reader: EventReader<PlayerAte>,
+    player_query: Query<&Transform>,
+) {
+    let mut rng = thread_rng();
+
+    for ate_event in player_ate_reader.read() {
+        if let Ok(transform) = player_query.get(ate_event.player_entity) {
+            budget.spawn_burst(
+                &mut commands,
+                &mut rng,
+                transform.translation.truncate(),
+                &CRUMB_BURST,
+            );
+        }
+    }
+}
+
+/// Splashes when a fish crosses the surface of the water in either direction.
+fn surface_splash_system(
+    mut commands: Commands,
+    arena: Res<Arena>,
+    mut budget: ResMut<ParticleBudget>,
+    mut last_heights: Local<HashMap<Entity, f32>>,
+    player_query: Query<(Entity, &Transform), With<Player>>,
+) {
+    let surface = arena.height / 2.0 + arena.offset;
+    let mut rng = thread_rng();
+
+    for (player_entity, transform) in player_query.iter() {
+        let height = transform.translation.y;
+
+        if let Some(last_height) = last_heights.insert(player_entity, height) {
+            if (last_height < surface) != (height < surface) {
+                budget.spawn_burst(
+                    &mut commands,
+                    &mut rng,
+                    Vec2::new(transform.translation.x, surface),
+                    &SURFACE_SPLASH,
+                );
+            }
+        }
+    }
+}
+
+fn boat_wake_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    game_state: Res<GameState>,
+    arena: Res<Arena>,
+    mut budget: ResMut<ParticleBudget>,
+    mut wake_timer: Local<Timer>,
+    boat_query: Query<(&Transform, &Velocity, &Collider), With<Boat>>,
+) {
+    if let GameStates::Paused | GameStates::Menu = game_state.cur_state {
+        return;
+    }
+
+    if wake_timer.duration().is_zero() {
+        *wake_timer = Timer::from_seconds(WAKE_INTERVAL, TimerMode::Repeating);
+    }
+
+    if !wake_timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let surface = arena.height / 2.0 + arena.offset;
+    let mut rng = thread_rng();
+
+    for (transform, velocity, collider) in boat_query.iter() {
+        let stern = transform.translation.x - velocity.0.x.signum() * collider.width / 2.0;
+
+        budget.spawn_burst(
+            &mut commands,
+            &mut rng,
+            Vec2::new(stern, surface),
+            &WAKE_FOAM,
+        );
+    }
+}
+
+/// Applies gravity, fades particles out over their lifetime and removes them once they expire.
+fn update_particles_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    game_state: Res<GameState>,
+    mut budget: ResMut<ParticleBudget>,
+    mut particle_query: Query<(Entity, &mut Particle, &mut Velocity, &mut Sprite)>,
+) {
+    // particles keep moving after the game is over, like the boats do
+    if let GameStates::Paused | GameStates::Menu = game_state.cur_state {
+        return;
+    }
+
+    for (entity, mut particle, mut velocity, mut sprite) in particle_query.iter_mut() {
+        particle.timer.tick(time.delta());
+
+        if particle.timer.finished() {
+            commands.entity(entity).despawn();
+            budget.alive -= 1;
+            continue;
+        }
+
+        velocity.0.y += particle.gravity * time.delta_seconds();
+
+        let start_alpha = particle.start_alpha;
+        sprite
+            .color
+            .set_a(start_alpha * (1.0 - particle.timer.fraction()));
+    }
+}
+
+fn clear_particles_on_restart(
+    mut commands: Commands,
+    mut budget: ResMut<ParticleBudget>,
+    mut restart_reader: EventReader<GameRestarted>,
+    particle_query: Query<Entity, With<Particle>>,
+) {
+    if restart_reader.read().next().is_some() {
+        for entity in particle_query.iter() {
+            commands.entity(entity).despawn();
+        }
+
+        budget.alive = 0;
+    }
+}
@@ -2,19 +2,73 @@ use bevy::prelude::*;
 use std::collections::HashSet;
 
 use super::events::{PlayerAte, PlayerStarved};
+use crate::modes::CurrentGameMode;
+use crate::modifiers::{ModifierId, Modifiers};
 use crate::shared::game::GameState;
 
-#[derive(Debug)]
+/// How the player moves. The resource holds the stats every new player spawns with, which the
+/// debug inspector can change while the game is running.
+#[derive(Debug, Clone, Resource)]
 pub(crate) struct PlayerStats {
     pub boost_speed: f32,
     pub boost_duration: f32,
     pub boost_cooldown: f32,
+    pub boost_invulnerability: f32,
+    pub leap_speed: f32,
+    pub dive_speed: f32,
+    pub dive_duration: f32,
+    pub stun_duration: f32,
+    pub stun_recovery: f32,
     pub speed: f32,
     pub acceleration: f32,
     pub traction: f32,
     pub stop_threshold: f32,
 }
 
+impl Default for PlayerStats {
+    fn default() -> Self {
+        Self {
+            boost_speed: 1500.0,
+            boost_duration: 0.1,
+            boost_cooldown: 0.2,
+            boost_invulnerability: 0.15,
+            leap_speed: 200.0,
+            dive_speed: 800.0,
+            dive_duration: 0.2,
+            stun_duration: 0.5,
+            stun_recovery: 2.0,
+            speed: 400.0,
+            acceleration: 0.8,
+            traction: 0.8,
+            stop_threshold: 0.1,
+        }
+    }
+}
+
+/**
+Toggles for the boost balance changes so that they can be turned on and off independently
+while A/B testing how the game feels.
+*/
+#[derive(Debug, Clone, Resource)]
+pub(crate) struct BoostTuning {
+    /// Hooks and boats are ignored while the boost invulnerability timer is running.
+    pub invulnerability: bool,
+    /// A boost pressed during the cooldown is remembered for a few frames.
+    pub input_buffer: bool,
+    /// The player's hitbox is shrunk vertically while boosting.
+    pub shrink_hitbox: bool,
+}
+
+impl Default for BoostTuning {
+    fn default() -> Self {
+        Self {
+            invulnerability: true,
+            input_buffer: true,
+            shrink_hitbox: true,
+        }
+    }
+}
+
 #[derive(Debug, Component)]
 pub(crate) struct Player {
     pub stats: PlayerStats,
@@ -71,12 +125,14 @@ pub(crate) struct HungerCountdown {
 
 pub(super) fn hunger_countdown_system(
     game_state: Res<GameState>,
+    current_mode: Res<CurrentGameMode>,
+    modifiers: Res<Modifiers>,
     mut player_ate_reader: EventReader<PlayerAte>,
     time: Res<Time>,
     mut starved_event_writer: EventWriter<PlayerStarved>,
     mut query: Query<(&mut HungerCountdown, Entity)>,
 ) {
-    if !game_state.is_running() {
+    if !game_state.is_running() || !current_mode.active().has_hunger() {
         return;
     }
 
@@ -95,7 +151,7 @@ pub(super) fn hunger_countdown_system(
             hunger_countdown.time_left += hunger_countdown.extra_time_per_worm;
         }
 
-        hunger_countdown.time_left -= time.delta_seconds();
+        hunger_countdown.time_left -= time.delta_seconds() * modifiers.hunger_drain_rate();
 
         if hunger_countdown.time_left < 0.0 {
             // emit starved event for entity
@@ -106,9 +162,14 @@ pub(super) fn hunger_countdown_system(
 }
 
 pub(super) fn add_boost_system(
+    current_mode: Res<CurrentGameMode>,
     mut player_ate_reader: EventReader<PlayerAte>,
     mut query: Query<&mut BoostSupply>,
 ) {
+    if !current_mode.active().worms_give_boosts() {
+        return;
+    }
+
     for ate_event in player_ate_reader.read() {
         if let Ok(mut boost_supply) = query.get_mut(ate_event.player_entity) {
             debug!("Adding extra time because player ate a worm.");
@@ -116,3 +177,19 @@ pub(super) fn add_boost_system(
         }
     }
 }
+
+/// Tops the boosts back up after every boost while boosts are infinite.
+pub(super) fn refill_infinite_boosts_system(
+    modifiers: Res<Modifiers>,
+    mut query: Query<&mut BoostSupply, Changed<BoostSupply>>,
+) {
+    if !modifiers.is_active(ModifierId::InfiniteBoosts) {
+        return;
+    }
+
+    for mut boost_supply in query.iter_mut() {
+        if boost_supply.count < boost_supply.max_boosts {
+            boost_supply.count = boost_supply.max_boosts;
+        }
+    }
+}
@@ -2,10 +2,12 @@ use bevy::{
     math::bounding::{Aabb2d, IntersectsVolume},
     prelude::*,
 };
+use std::collections::HashMap;
 
 use super::{
-    attributes::Player,
-    events::{PlayerAte, PlayerBonked, PlayerHooked},
+    attributes::{BoostTuning, Player},
+    events::{PlayerAte, PlayerBonked, PlayerHooked, PlayerNearMissed, PlayerStunned},
+    states::{BoostData, PlayerState, PlayerStates, StunData},
 };
 use crate::{
     objects::boat::{Boat, Hook, Worm},
@@ -20,13 +22,13 @@ use crate::{
 pub(super) fn player_bounds_system(
     game_state: Res<GameState>,
     arena: Res<Arena>,
-    mut query: Query<(&Player, &Collider, &mut Transform)>,
+    mut query: Query<(&Player, &Collider, &PlayerState, &mut Transform)>,
 ) {
     if let GameStates::GameOver = game_state.cur_state {
         return;
     }
 
-    for (_, collider, mut transform) in query.iter_mut() {
+    for (_, collider, player_state, mut transform) in query.iter_mut() {
         let new_pos = &mut transform.translation;
 
         let arena_half_width = arena.width / 2.0;
@@ -47,26 +49,62 @@ pub(super) fn player_bounds_system(
             new_pos.y = -arena_half_height + player_half_height;
         }
 
-        // allow floating on the top
-        if new_pos.y > (arena_half_height + arena.offset) {
+        // allow floating on the top, and leaping out of the water
+        if player_state.current_state != PlayerStates::Leap
+            && new_pos.y > (arena_half_height + arena.offset)
+        {
             new_pos.y = arena_half_height + arena.offset;
         }
     }
 }
 
+/// Height multiplier applied to the player's hitbox while boosting.
+const BOOST_HITBOX_HEIGHT_SCALE: f32 = 0.5;
+
+/// Returns the half size of the player's hitbox, which shrinks vertically while boosting.
+fn player_half_size(collider: &Collider, state: &PlayerState, boost_tuning: &BoostTuning) -> Vec2 {
+    let mut half_size = collider.as_vec2_half_size();
+
+    if boost_tuning.shrink_hitbox && state.current_state == PlayerStates::Boost {
+        half_size.y *= BOOST_HITBOX_HEIGHT_SCALE;
+    }
+
+    half_size
+}
+
+/// Whether the player should currently ignore hazards because of a recent boost.
+fn is_invulnerable(boost_data: Option<&BoostData>, boost_tuning: &BoostTuning) -> bool {
+    boost_tuning.invulnerability && boost_data.is_some_and(BoostData::is_invulnerable)
+}
+
+#[allow(clippy::type_complexity)]
 pub(super) fn player_hook_collision_system(
     game_state: Res<GameState>,
+    boost_tuning: Res<BoostTuning>,
     mut player_hooked_events: ResMut<Events<PlayerHooked>>,
-    player_query: Query<(&Player, &Collider, &Transform, Entity)>,
+    player_query: Query<(
+        &Player,
+        &Collider,
+        &Transform,
+        &PlayerState,
+        Option<&BoostData>,
+        Entity,
+    )>,
     hook_query: Query<(&Hook, &Collider, &GlobalTransform, Entity)>,
 ) {
     if let GameStates::GameOver = game_state.cur_state {
         return;
     }
 
-    for (_, player_collider, player_transform, player_entity) in player_query.iter() {
+    for (_, player_collider, player_transform, player_state, boost_data, player_entity) in
+        player_query.iter()
+    {
+        if is_invulnerable(boost_data, &boost_tuning) {
+            continue;
+        }
+
         let player_pos = player_transform.translation;
-        let player_half_size = player_collider.as_vec2_half_size();
+        let player_half_size = player_half_size(player_collider, player_state, &boost_tuning);
         let player_aabb = Aabb2d::new(player_pos.xy(), player_half_size);
         for (_, hook_collider, hook_transform, hook_entity) in hook_query.iter() {
             let hook_pos = hook_transform.translation();
@@ -85,19 +123,96 @@ pub(super) fn player_hook_collision_system(
     }
 }
 
+/// How close the player has to get to a hook for passing it to count as a near miss.
+const NEAR_MISS_DISTANCE: f32 = 24.0;
+
+/// A hook that the player is currently close to.
+pub(super) struct NearHook {
+    position: Vec2,
+    /// Touching the hook at any point while close to it means it wasn't a miss.
+    touched: bool,
+}
+
+/**
+Tracks the hooks the player gets close to, and sends a near miss once the player gets away from a
+hook without ever touching it. Touching a hook while invulnerable doesn't count as a miss.
+*/
+#[allow(clippy::type_complexity)]
+pub(super) fn player_near_miss_system(
+    game_state: Res<GameState>,
+    boost_tuning: Res<BoostTuning>,
+    mut near_hooks: Local<HashMap<(Entity, Entity), NearHook>>,
+    mut player_near_missed_events: EventWriter<PlayerNearMissed>,
+    player_query: Query<(&Player, &Collider, &Transform, &PlayerState, Entity)>,
+    hook_query: Query<(&Hook, &Collider, &GlobalTransform, Entity)>,
+) {
+    if !game_state.is_running() {
+        near_hooks.clear();
+        return;
+    }
+
+    let mut still_near = HashMap::new();
+
+    for (_, player_collider, player_transform, player_state, player_entity) in player_query.iter() {
+        let player_half_size = player_half_size(player_collider, player_state, &boost_tuning);
+        let player_aabb = Aabb2d::new(player_transform.translation.xy(), player_half_size);
+        let near_aabb = Aabb2d::new(
+            player_transform.translation.xy(),
+            player_half_size + Vec2::splat(NEAR_MISS_DISTANCE),
+        );
+
+        for (_, hook_collider, hook_transform, hook_entity) in hook_query.iter() {
+            let hook_aabb =
+                get_aabb_from_transform_and_collider(&hook_transform.translation(), hook_collider);
+
+            if !check_aabb_collision(&near_aabb, &hook_aabb) {
+                continue;
+            }
+
+            let key = (player_entity, hook_entity);
+            let was_touched = near_hooks.get(&key).is_some_and(|near| near.touched);
+            still_near.insert(
+                key,
+                NearHook {
+                    position: hook_transform.translation().xy(),
+                    touched: was_touched || check_aabb_collision(&player_aabb, &hook_aabb),
+                },
+            );
+        }
+    }
+
+    for ((player_entity, hook_entity), near_hook) in near_hooks.drain() {
+        if near_hook.touched || still_near.contains_key(&(player_entity, hook_entity)) {
+            continue;
+        }
+
+        debug!("Player narrowly missed a hook!");
+        player_near_missed_events.send(PlayerNearMissed {
+            player_entity,
+            hook_entity,
+            position: near_hook.position,
+        });
+    }
+
+    *near_hooks = still_near;
+}
+
 pub(super) fn player_worm_collision_system(
     game_state: Res<GameState>,
+    boost_tuning: Res<BoostTuning>,
     mut player_ate_events: ResMut<Events<PlayerAte>>,
-    player_query: Query<(&Player, &Collider, &Transform, Entity)>,
+    player_query: Query<(&Player, &Collider, &Transform, &PlayerState, Entity)>,
     worm_query: Query<(&Worm, &Collider, &GlobalTransform, Entity)>,
 ) {
     if let GameStates::GameOver = game_state.cur_state {
         return;
     }
 
-    for (_, player_collider, player_transform, player_entity) in player_query.iter() {
-        let player_aabb2d =
-            get_aabb_from_transform_and_collider(&player_transform.translation, &player_collider);
+    for (_, player_collider, player_transform, player_state, player_entity) in player_query.iter() {
+        let player_aabb2d = Aabb2d::new(
+            player_transform.translation.xy(),
+            player_half_size(player_collider, player_state, &boost_tuning),
+        );
 
         for (_, worm_collider, worm_transform, worm_entity) in worm_query.iter() {
             let worm_aabb2d =
@@ -114,30 +229,72 @@ pub(super) fn player_worm_collision_system(
     }
 }
 
+/**
+Touching a boat staggers the player. Touching another boat while still recovering from the
+stagger bonks the player.
+*/
+#[allow(clippy::type_complexity)]
 pub(super) fn player_boat_collision_system(
     game_state: Res<GameState>,
+    boost_tuning: Res<BoostTuning>,
     mut player_bonk_events: ResMut<Events<PlayerBonked>>,
-    player_query: Query<(&Player, &Collider, &Transform, Entity)>,
+    mut player_stunned_events: ResMut<Events<PlayerStunned>>,
+    player_query: Query<(
+        &Player,
+        &Collider,
+        &Transform,
+        &PlayerState,
+        Option<&BoostData>,
+        Option<&StunData>,
+        Entity,
+    )>,
     worm_query: Query<(&Boat, &Collider, &Transform, Entity)>,
 ) {
     if let GameStates::GameOver = game_state.cur_state {
         return;
     }
 
-    for (_, player_collider, player_transform, player_entity) in player_query.iter() {
-        let player_aabb2d =
-            get_aabb_from_transform_and_collider(&player_transform.translation, player_collider);
+    for (
+        _,
+        player_collider,
+        player_transform,
+        player_state,
+        boost_data,
+        stun_data,
+        player_entity,
+    ) in player_query.iter()
+    {
+        if is_invulnerable(boost_data, &boost_tuning)
+            || player_state.current_state == PlayerStates::Stunned
+        {
+            continue;
+        }
+
+        let player_aabb2d = Aabb2d::new(
+            player_transform.translation.xy(),
+            player_half_size(player_collider, player_state, &boost_tuning),
+        );
 
         for (_, boat_collider, boat_transform, boat_entity) in worm_query.iter() {
             let boat_aabb2d =
                 get_aabb_from_transform_and_collider(&boat_transform.translation, boat_collider);
 
-            if check_aabb_collision(&player_aabb2d, &boat_aabb2d) {
-                debug!("Player collided with a boat!");
+            if !check_aabb_collision(&player_aabb2d, &boat_aabb2d) {
+                continue;
+            }
+
+            if stun_data.is_some_and(StunData::is_recovering) {
+                debug!("Player collided with a boat while recovering from a stun!");
                 player_bonk_events.send(PlayerBonked {
                     player_entity,
                     boat_entity,
                 });
+            } else {
+                debug!("Player collided with a boat and got stunned!");
+                player_stunned_events.send(PlayerStunned {
+                    player_entity,
+                    boat_entity,
+                });
             }
         }
     }
@@ -148,5 +305,5 @@ fn get_aabb_from_transform_and_collider(&pos: &Vec3, collider: &Collider) -> Aab
 }
 
 fn check_aabb_collision(box1: &Aabb2d, box2: &Aabb2d) -> bool {
-    return box1.intersects(box2.into());
+    box1.intersects(box2)
 }
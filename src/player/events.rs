@@ -1,5 +1,7 @@
 use bevy::prelude::*;
 
+use super::states::PlayerStates;
+
 #[derive(Debug, Event)]
 pub struct PlayerHooked {
     pub player_entity: Entity,
@@ -17,13 +19,37 @@ pub struct PlayerBonked {
     pub boat_entity: Entity,
 }
 
+#[derive(Debug, Event)]
+pub struct PlayerStunned {
+    pub player_entity: Entity,
+    pub boat_entity: Entity,
+}
+
 #[derive(Debug, Event)]
 pub struct PlayerAte {
     pub player_entity: Entity,
     pub worm_entity: Entity,
 }
 
+/// Sent when a player passes close by a hook without touching it.
+#[derive(Debug, Event)]
+pub struct PlayerNearMissed {
+    #[allow(dead_code)]
+    pub player_entity: Entity,
+    #[allow(dead_code)]
+    pub hook_entity: Entity,
+    /// Where the hook was when the player got away from it.
+    pub position: Vec2,
+}
+
 #[derive(Debug, Event)]
 pub struct PlayerBoosted {
     pub player: Entity,
 }
+
+/// Sent whenever a player moves from one state to another.
+#[derive(Debug, Event)]
+pub struct PlayerStateChanged {
+    pub player_entity: Entity,
+    pub new_state: PlayerStates,
+}
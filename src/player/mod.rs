@@ -1,5 +1,10 @@
+use crate::accessibility::Palette;
+use crate::modes::{self, CurrentGameMode};
+use crate::modifiers::{self, ModifierId, Modifiers};
+use crate::settings::Settings;
 use crate::shared::{
     animation::AnimationState,
+    atlas::SpriteAtlas,
     collision::Collider,
     game::GameRestarted,
     movement::{SideScrollDirection, Velocity},
@@ -15,7 +20,8 @@ mod collision;
 pub(crate) mod events;
 mod movement;
 mod render;
-mod states;
+pub(crate) mod skins;
+pub(crate) mod states;
 
 pub struct PlayerPlugin;
 
@@ -23,13 +29,22 @@ impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         debug!("Building player plugin...");
         // Resources for player sprites and animations
-        app.init_resource::<render::PlayerStateAnimations>()
+        app.init_resource::<skins::PlayerSkins>()
+            .insert_resource(skins::SkinSelection::load())
+            .init_resource::<attributes::BoostTuning>()
+            .init_resource::<attributes::PlayerStats>()
+            // The controls and the colours of the player, kept when the game already has them
+            .init_resource::<Settings>()
+            .init_resource::<Palette>()
             // Events that indicate either a player collided with something/died
             .add_event::<events::PlayerHooked>()
             .add_event::<events::PlayerStarved>()
             .add_event::<events::PlayerBonked>()
+            .add_event::<events::PlayerStunned>()
             .add_event::<events::PlayerAte>()
+            .add_event::<events::PlayerNearMissed>()
             .add_event::<events::PlayerBoosted>()
+            .add_event::<events::PlayerStateChanged>()
             // Startup systems initialize the player and its components
             .add_systems(Startup, init_player)
             // Timer systems
@@ -48,17 +63,34 @@ impl Plugin for PlayerPlugin {
                     attributes::add_boost_system,
                     animations::player_starved_handler,
                     states::swim_movement_system,
-                    reset_player,
+                    states::player_stunned_handler,
+                    reset_player
+                        .after(modifiers::activate_modifiers_on_restart)
+                        .after(modes::activate_game_mode_on_restart),
                     render::despawn_trackers_on_gameover_or_restart,
-                    render::show_countdown_on_restart,
-                    render::hide_countdown_on_game_over,
                 )
                     .in_set(stages::HandleEventsSet),
             )
             // systems that handle input/velocity calculation
             .add_systems(
                 Update,
-                (states::boost_movement_system, movement::sink_system).in_set(stages::MovementSet),
+                (
+                    states::boost_movement_system,
+                    states::leap_movement_system,
+                    states::dive_movement_system,
+                    states::stunned_system,
+                    movement::sink_system,
+                )
+                    .in_set(stages::MovementSet),
+            )
+            // Announce every state change made while handling input and movement
+            .add_systems(
+                Update,
+                (
+                    states::emit_state_changes_system,
+                    attributes::refill_infinite_boosts_system,
+                )
+                    .in_set(stages::FinalizeMovementSet),
             )
             // This system needs to happen before render, but after final position has
             // been calculated to prevent stuttering movement
@@ -72,51 +104,114 @@ impl Plugin for PlayerPlugin {
                 (
                     collision::player_bounds_system,
                     collision::player_hook_collision_system,
+                    collision::player_near_miss_system,
                     collision::player_worm_collision_system,
                     collision::player_boat_collision_system,
                 )
                     .in_set(stages::CalculateCollisionsSet),
-            )
-            // systems that handle final events and presentation
-            .add_systems(
-                Update,
-                (
-                    render::player_state_animation_change_system,
-                    render::update_tracker_display_from_boost_supply,
-                    render::update_coundown_text_system,
-                )
-                    .in_set(stages::PrepareRenderSet),
             );
     }
 }
 
+/// Draws the player: its animations and skin, the boost trackers and the restart countdown.
+pub struct PlayerRenderPlugin;
+
+impl Plugin for PlayerRenderPlugin {
+    fn build(&self, app: &mut App) {
+        debug!("Building player render plugin...");
+        app.add_systems(
+            Update,
+            (
+                render::show_countdown_on_restart,
+                render::hide_countdown_on_game_over,
+            )
+                .in_set(stages::HandleEventsSet),
+        )
+        // systems that handle final events and presentation
+        .add_systems(
+            Update,
+            (
+                skins::load_player_animations_system,
+                render::player_state_animation_change_system,
+                render::settle_finished_animations_system
+                    .before(render::player_state_animation_change_system),
+                skins::apply_skin_selection_system,
+                render::update_tracker_display_from_boost_supply,
+                render::update_tracker_colors_from_palette,
+                render::update_coundown_text_system,
+                render::invulnerability_blink_system,
+            )
+                .in_set(stages::PrepareRenderSet),
+        );
+    }
+}
+
 const PLAYER_WIDTH: f32 = 32.0;
 const PLAYER_HEIGHT: f32 = 32.0;
 const PLAYER_MAX_BOOSTS: u8 = 3;
 
+fn player_size(modifiers: &Modifiers) -> Vec2 {
+    Vec2::new(PLAYER_WIDTH, PLAYER_HEIGHT) * modifiers.player_size_scale()
+}
+
+fn player_max_boosts(modifiers: &Modifiers, current_mode: &CurrentGameMode) -> u8 {
+    if modifiers.is_active(ModifierId::NoBoosts) {
+        0
+    } else {
+        current_mode
+            .active()
+            .max_boosts()
+            .unwrap_or(PLAYER_MAX_BOOSTS)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn init_player(
     mut commands: Commands,
     fonts: Res<FontHandles>,
-    player_state_animations: Res<render::PlayerStateAnimations>,
+    palette: Res<Palette>,
+    sprite_atlas: Res<SpriteAtlas>,
+    player_skins: Res<skins::PlayerSkins>,
+    skin_selection: Res<skins::SkinSelection>,
+    player_stats: Res<attributes::PlayerStats>,
+    modifiers: Res<Modifiers>,
+    current_mode: Res<CurrentGameMode>,
 ) {
-    let player_entity = spawn_player_entity(&mut commands, &player_state_animations);
+    let player_entity = spawn_player_entity(
+        &mut commands,
+        &sprite_atlas,
+        &player_skins,
+        skin_selection.skin,
+        &player_stats,
+        &modifiers,
+        &current_mode,
+    );
 
     // TODO: Break this out into separate systems.
+    let size = player_size(&modifiers);
     render::spawn_player_boost_trackers(
         &mut commands,
-        PLAYER_WIDTH,
-        PLAYER_HEIGHT,
-        PLAYER_MAX_BOOSTS,
+        size.x,
+        size.y,
+        player_max_boosts(&modifiers, &current_mode),
         player_entity,
+        &palette,
     );
     render::add_countdown_text(commands, fonts, player_entity)
 }
 
 // TODO: Make this support more than one player
+#[allow(clippy::too_many_arguments)]
 fn reset_player(
     mut commands: Commands,
     fonts: Res<FontHandles>,
-    player_state_animations: Res<render::PlayerStateAnimations>,
+    palette: Res<Palette>,
+    sprite_atlas: Res<SpriteAtlas>,
+    player_skins: Res<skins::PlayerSkins>,
+    skin_selection: Res<skins::SkinSelection>,
+    player_stats: Res<attributes::PlayerStats>,
+    modifiers: Res<Modifiers>,
+    current_mode: Res<CurrentGameMode>,
     mut restart_reader: EventReader<GameRestarted>,
     player_query: Query<Entity, With<attributes::Player>>,
 ) {
@@ -127,13 +222,23 @@ fn reset_player(
         commands.entity(player_entity).despawn_recursive();
 
         // spawn a new player with new ui components
-        let new_player = spawn_player_entity(&mut commands, &player_state_animations);
+        let new_player = spawn_player_entity(
+            &mut commands,
+            &sprite_atlas,
+            &player_skins,
+            skin_selection.skin,
+            &player_stats,
+            &modifiers,
+            &current_mode,
+        );
+        let size = player_size(&modifiers);
         render::spawn_player_boost_trackers(
             &mut commands,
-            PLAYER_WIDTH,
-            PLAYER_HEIGHT,
-            PLAYER_MAX_BOOSTS,
+            size.x,
+            size.y,
+            player_max_boosts(&modifiers, &current_mode),
             new_player,
+            &palette,
         );
         render::add_countdown_text(commands, fonts, new_player);
     }
@@ -141,61 +246,57 @@ fn reset_player(
 
 fn spawn_player_entity(
     commands: &mut Commands,
-    player_state_animations: &render::PlayerStateAnimations,
+    sprite_atlas: &SpriteAtlas,
+    player_skins: &skins::PlayerSkins,
+    skin: skins::SkinId,
+    stats: &attributes::PlayerStats,
+    modifiers: &Modifiers,
+    current_mode: &CurrentGameMode,
 ) -> Entity {
-    let player_animation = player_state_animations
-        .map
-        .get(&states::PlayerStates::Idle)
-        .unwrap();
-    let first_animation_frame = player_animation.frames[0].clone();
-
-    commands
-        .spawn((
-            attributes::Player {
-                stats: attributes::PlayerStats {
-                    boost_speed: 1500.0,
-                    boost_duration: 0.1,
-                    boost_cooldown: 0.2,
-                    speed: 400.0,
-                    acceleration: 0.8,
-                    traction: 0.8,
-                    stop_threshold: 0.1,
-                },
-            },
-            attributes::Sink { weight: 10.0 },
-            attributes::HungerCountdown {
-                time_left: 30.0,
-                extra_time_per_worm: 3.0,
-            },
-            attributes::BoostSupply {
-                max_boosts: PLAYER_MAX_BOOSTS,
-                count: PLAYER_MAX_BOOSTS,
-            },
-            states::PlayerState {
-                current_state: states::PlayerStates::Idle,
-                blocked_transitions: HashSet::new(),
-            },
-            Velocity(Vec3::ZERO),
-            SideScrollDirection(true),
-            Collider {
-                width: PLAYER_WIDTH,
-                height: PLAYER_HEIGHT,
-            },
-            RenderLayer::Player,
-            SpriteBundle {
-                texture: first_animation_frame.material_handle.clone(),
-                sprite: Sprite {
-                    custom_size: Some(Vec2::new(PLAYER_WIDTH, PLAYER_HEIGHT)),
-                    ..Default::default()
-                },
+    let size = player_size(modifiers);
+    let max_boosts = player_max_boosts(modifiers, current_mode);
+
+    let mut player_commands = commands.spawn((
+        attributes::Player {
+            stats: stats.clone(),
+        },
+        attributes::Sink { weight: 10.0 },
+        attributes::HungerCountdown {
+            time_left: 30.0,
+            extra_time_per_worm: 3.0,
+        },
+        attributes::BoostSupply {
+            max_boosts,
+            count: max_boosts,
+        },
+        states::PlayerState {
+            current_state: states::PlayerStates::Idle,
+            blocked_transitions: HashSet::new(),
+            changes: Vec::new(),
+        },
+        Velocity(Vec3::ZERO),
+        SideScrollDirection(true),
+        Collider {
+            width: size.x,
+            height: size.y,
+        },
+        RenderLayer::Player,
+        SpriteSheetBundle {
+            texture: sprite_atlas.image.clone(),
+            atlas: sprite_atlas.texture_atlas(player_skins.preview_index(skin)),
+            sprite: Sprite {
+                color: skins::skin_definition(skin).tint,
+                custom_size: Some(size),
                 ..Default::default()
             },
-            AnimationState {
-                animation: player_animation.clone(),
-                timer: Timer::from_seconds(first_animation_frame.time, TimerMode::Once),
-                frame_index: 0,
-                speed_multiplier: 1.0,
-            },
-        ))
-        .id()
+            ..Default::default()
+        },
+    ));
+
+    // the player starts animating once its animations have loaded if they haven't yet
+    if let Some(idle_animation) = player_skins.state_animation(skin, states::PlayerStates::Idle) {
+        player_commands.insert(AnimationState::from_animation(idle_animation, 1.0));
+    }
+
+    player_commands.id()
 }
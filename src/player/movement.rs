@@ -1,39 +1,50 @@
 use bevy::prelude::*;
 
 use super::attributes::{Player, Sink};
+use crate::modifiers::{ModifierId, Modifiers};
+use crate::settings::{ControlBindings, InputActions};
 use crate::shared::{
     game::GameState,
     movement::{SideScrollDirection, Velocity},
 };
 
 /**
-Reads keyboard input and adjusts players velocity based on it. Returns
-the target speed of the player.
+Reads keyboard input through the player's bindings and adjusts players velocity based on it. Returns
+the target speed of the player. Left and right are swapped when the controls are mirrored.
 */
 // TODO: Change to use specific player command events
 pub(super) fn move_player_from_input(
     keyboard_input: &ButtonInput<KeyCode>,
+    controls: &ControlBindings,
+    modifiers: &Modifiers,
     player: &Player,
     velocity: &mut Velocity,
     facing: &mut SideScrollDirection,
 ) -> Vec3 {
     let mut target_speed = Vec3::ZERO;
 
-    if keyboard_input.pressed(KeyCode::ArrowLeft) || keyboard_input.pressed(KeyCode::KeyA) {
+    let mut left_pressed = controls.pressed(keyboard_input, InputActions::MoveLeft);
+    let mut right_pressed = controls.pressed(keyboard_input, InputActions::MoveRight);
+
+    if modifiers.is_active(ModifierId::MirrorControls) {
+        std::mem::swap(&mut left_pressed, &mut right_pressed);
+    }
+
+    if left_pressed {
         target_speed.x -= player.stats.speed;
         facing.0 = false;
     }
 
-    if keyboard_input.pressed(KeyCode::ArrowRight) || keyboard_input.pressed(KeyCode::KeyD) {
+    if right_pressed {
         target_speed.x += player.stats.speed;
         facing.0 = true;
     }
 
-    if keyboard_input.pressed(KeyCode::ArrowUp) || keyboard_input.pressed(KeyCode::KeyW) {
+    if controls.pressed(keyboard_input, InputActions::MoveUp) {
         target_speed.y += player.stats.speed;
     }
 
-    if keyboard_input.pressed(KeyCode::ArrowDown) || keyboard_input.pressed(KeyCode::KeyS) {
+    if controls.pressed(keyboard_input, InputActions::MoveDown) {
         target_speed.y -= player.stats.speed;
     }
 
@@ -55,12 +66,16 @@ pub(super) fn move_player_from_input(
 }
 
 /// sinks the player based on their weight
-pub(super) fn sink_system(game_state: Res<GameState>, mut query: Query<(&mut Velocity, &Sink)>) {
+pub(super) fn sink_system(
+    game_state: Res<GameState>,
+    modifiers: Res<Modifiers>,
+    mut query: Query<(&mut Velocity, &Sink)>,
+) {
     if !game_state.is_running() {
         return;
     }
 
     for (mut velocity, sink) in query.iter_mut() {
-        velocity.0.y -= sink.weight;
+        velocity.0.y -= sink.weight * modifiers.sink_weight_scale();
     }
 }
@@ -1,114 +1,141 @@
 use bevy::prelude::*;
-use bevy::utils::Duration;
 use bevy_prototype_lyon::prelude::*;
 use std::collections::HashMap;
 
-use super::attributes::{BoostSupply, HungerCountdown, Player};
-use super::states::{PlayerState, PlayerStates};
+use super::attributes::{BoostSupply, BoostTuning, HungerCountdown, Player};
+use super::events::PlayerStateChanged;
+use super::skins::{PlayerSkins, SkinSelection};
+use super::states::{BoostData, PlayerStates};
+use crate::accessibility::Palette;
+use crate::modes::CurrentGameMode;
 use crate::shared::{
-    animation::{Animation, AnimationFrame, AnimationState},
+    animation::{Animation, AnimationFinished, AnimationSet, AnimationState},
+    atlas::SpriteAtlas,
     game::{GameOver, GameRestarted},
     render::FontHandles,
 };
 
-#[derive(Resource)]
+/// The animation to play for each state of the player.
 pub(super) struct PlayerStateAnimations {
     pub map: HashMap<PlayerStates, Animation>,
 }
 
-impl FromWorld for PlayerStateAnimations {
-    fn from_world(world: &mut World) -> Self {
-        let asset_server = world.get_resource::<AssetServer>().unwrap();
-
-        let swim_1_handle = asset_server.load("sprites/player/fish1.png");
-        let swim_2_handle = asset_server.load("sprites/player/fish2.png");
+/// Names that the player's animation file uses for the frames of whichever skin is worn.
+const SWIM_FRAME_NAMES: [&str; 2] = ["swim1", "swim2"];
+
+const PLAYER_STATES: [PlayerStates; 6] = [
+    PlayerStates::Idle,
+    PlayerStates::Swim,
+    PlayerStates::Boost,
+    PlayerStates::Leap,
+    PlayerStates::Dive,
+    PlayerStates::Stunned,
+];
+
+impl PlayerStateAnimations {
+    /**
+    Builds the animations for every player state from the player's animation file, using the two
+    frames of a swimming fish for the `swim1` and `swim2` frames. Other frames are looked up in the
+    sprite atlas by name.
+    */
+    pub(super) fn from_animation_set(
+        animation_set: &AnimationSet,
+        sprite_atlas: &SpriteAtlas,
+        swim_frames: [usize; 2],
+    ) -> Self {
+        let sprite_index = |name: &str| match SWIM_FRAME_NAMES.iter().position(|&n| n == name) {
+            Some(swim_frame) => swim_frames[swim_frame],
+            None => sprite_atlas.index(name),
+        };
 
         PlayerStateAnimations {
-            map: [
-                (
-                    PlayerStates::Idle,
-                    Animation {
-                        should_loop: true,
-                        frames: vec![AnimationFrame {
-                            material_handle: swim_1_handle.clone(),
-                            time: 999.9,
-                        }],
-                    },
-                ),
-                (
-                    PlayerStates::Swim,
-                    Animation {
-                        should_loop: true,
-                        frames: vec![
-                            AnimationFrame {
-                                material_handle: swim_1_handle.clone(),
-                                time: 0.2,
-                            },
-                            AnimationFrame {
-                                material_handle: swim_2_handle.clone(),
-                                time: 0.2,
-                            },
-                        ],
-                    },
-                ),
-                (
-                    PlayerStates::Boost,
-                    Animation {
-                        should_loop: true,
-                        frames: vec![
-                            AnimationFrame {
-                                material_handle: swim_1_handle,
-                                time: 0.1,
-                            },
-                            AnimationFrame {
-                                material_handle: swim_2_handle,
-                                time: 0.1,
-                            },
-                        ],
-                    },
-                ),
-            ]
-            .iter()
-            .cloned()
-            .collect(),
+            map: PLAYER_STATES
+                .iter()
+                .filter_map(|state| {
+                    let Some(definition) = animation_set.get(state.animation_name()) else {
+                        error!("No animation for player state {:?}", state);
+                        return None;
+                    };
+
+                    Some((*state, definition.resolve(sprite_index)))
+                })
+                .collect(),
         }
     }
 }
 
-/// TODO: State changes might work better as events in the future
+/// Starts the animation of the player's new state whenever the player changes state.
 pub(super) fn player_state_animation_change_system(
-    player_state_animations: Res<PlayerStateAnimations>,
-    mut last_entity_states: Local<HashMap<Entity, PlayerStates>>,
-    mut query: Query<(&mut AnimationState, &PlayerState, Entity)>,
+    player_skins: Res<PlayerSkins>,
+    skin_selection: Res<SkinSelection>,
+    mut state_changed_reader: EventReader<PlayerStateChanged>,
+    mut query: Query<(&mut AnimationState, &mut TextureAtlas)>,
+) {
+    for state_changed in state_changed_reader.read() {
+        let Some(next_animation) = player_skins
+            .animations(skin_selection.skin)
+            .and_then(|animations| animations.map.get(&state_changed.new_state))
+        else {
+            continue;
+        };
+
+        if let Ok((mut animation_state, mut texture_atlas)) =
+            query.get_mut(state_changed.player_entity)
+        {
+            animation_state.play(next_animation);
+            texture_atlas.index = next_animation.frames[0].index;
+        }
+    }
+}
+
+/// One-shot animations, like the kick at the start of a leap, settle into the idle pose once they
+/// finish.
+pub(super) fn settle_finished_animations_system(
+    player_skins: Res<PlayerSkins>,
+    skin_selection: Res<SkinSelection>,
+    mut animation_finished_reader: EventReader<AnimationFinished>,
+    mut query: Query<&mut AnimationState, With<Player>>,
 ) {
-    for (mut animation_state, player_state, entity) in query.iter_mut() {
-        // On the first iteration per entity, its map entry will be empty
-        let cur_player_state = &player_state.current_state;
-        if let Some(prev_player_state) = last_entity_states.get(&entity) {
-            if cur_player_state != prev_player_state {
-                // debug!(
-                //     "State change detected in animation system from {:?} to {:?}",
-                //     prev_player_state, cur_player_state
-                // );
-
-                let next_animation = player_state_animations.map.get(&cur_player_state).unwrap();
-
-                animation_state
-                    .timer
-                    .set_duration(Duration::from_secs_f32(next_animation.frames[0].time));
-                animation_state.timer.reset();
-                // TODO: Find out how to remove this clone
-                animation_state.animation = next_animation.clone();
-                animation_state.frame_index = 0;
-
-                *last_entity_states.get_mut(&entity).unwrap() = *cur_player_state;
+    let Some(idle_animation) =
+        player_skins.state_animation(skin_selection.skin, PlayerStates::Idle)
+    else {
+        return;
+    };
+
+    for animation_finished in animation_finished_reader.read() {
+        // another animation may have started since, e.g. when the player changed states
+        if let Ok(mut animation_state) = query.get_mut(animation_finished.entity) {
+            if animation_state.is_finished() {
+                animation_state.play(idle_animation);
             }
-        } else {
-            last_entity_states.insert(entity, *cur_player_state);
         }
     }
 }
 
+/// Controls how quickly the player's sprite flickers while invulnerable.
+const INVULNERABILITY_BLINK_RATE: f32 = 30.0;
+
+/// Flickers the player's sprite while the invulnerability from a boost is active.
+pub(super) fn invulnerability_blink_system(
+    time: Res<Time>,
+    boost_tuning: Res<BoostTuning>,
+    mut query: Query<(&mut Sprite, Option<&BoostData>), With<Player>>,
+) {
+    for (mut sprite, boost_data) in query.iter_mut() {
+        let is_invulnerable =
+            boost_tuning.invulnerability && boost_data.is_some_and(BoostData::is_invulnerable);
+        let blink_on = (time.elapsed_seconds() * INVULNERABILITY_BLINK_RATE).sin() > 0.0;
+
+        let alpha = if is_invulnerable && blink_on {
+            0.4
+        } else {
+            1.0
+        };
+
+        sprite.color.set_a(alpha);
+    }
+}
+
 /**
     Design:
     * Boost supply tracker component manages the visibility (Draw.is_visible)
@@ -129,9 +156,10 @@ pub(super) fn spawn_player_boost_trackers(
     player_height: f32,
     max_boosts: u8,
     player_entity: Entity,
+    palette: &Palette,
 ) {
-    let tracker_color = Color::PINK;
-    let tracker_border_color = Color::rgb_u8(255, 105, 180);
+    let tracker_color = palette.boost_tracker;
+    let tracker_border_color = palette.boost_tracker_border;
 
     debug!("Adding boost trackers for player {:?}...", player_entity);
 
@@ -147,7 +175,11 @@ pub(super) fn spawn_player_boost_trackers(
         // total number of boosts.
         // Taken from https://bevyengine.org/examples/2D%20Rendering/2d-shapes/. I didn't come up
         // with this math on my own lmao.
-        let x_offset = -extended_width / 2. + i as f32 / (max_boosts - 1) as f32 * extended_width;
+        let x_offset = if max_boosts > 1 {
+            -extended_width / 2. + i as f32 / (max_boosts - 1) as f32 * extended_width
+        } else {
+            0.0
+        };
         tracker_positions.push(Vec2::new(x_offset, tracker_height));
     }
 
@@ -225,6 +257,27 @@ pub(super) fn update_tracker_display_from_boost_supply(
     }
 }
 
+/// Trackers are spawned with the palette's colours, and pick up a new palette while the game is
+/// paused.
+pub(super) fn update_tracker_colors_from_palette(
+    palette: Res<Palette>,
+    mut tracker_query: Query<&mut Fill, With<BoostTracker>>,
+    mut border_query: Query<&mut Stroke, With<BoostTrackerBorder>>,
+) {
+    if !palette.is_changed() {
+        return;
+    }
+
+    for mut fill in tracker_query.iter_mut() {
+        fill.color = palette.boost_tracker;
+    }
+
+    for mut stroke in border_query.iter_mut() {
+        stroke.color = palette.boost_tracker_border;
+    }
+}
+
+#[allow(clippy::type_complexity)]
 pub(super) fn despawn_trackers_on_gameover_or_restart(
     mut commands: Commands,
     mut game_over_reader: EventReader<GameOver>,
@@ -244,6 +297,11 @@ pub(super) fn despawn_trackers_on_gameover_or_restart(
     }
 }
 
+/// Seconds left on the countdown when it starts warning the player.
+const COUNTDOWN_WARNING_TIME: f32 = 5.0;
+const COUNTDOWN_FONT_SIZE: f32 = 70.0;
+const COUNTDOWN_WARNING_FONT_SIZE: f32 = 95.0;
+
 #[derive(Component)]
 pub(super) struct PlayerCountdownText;
 
@@ -264,7 +322,7 @@ pub(super) fn add_countdown_text(
                     "30.0".to_string(),
                     TextStyle {
                         font: fonts.main_font.clone(),
-                        font_size: 70.0,
+                        font_size: COUNTDOWN_FONT_SIZE,
                         ..Default::default()
                     },
                 )
@@ -278,17 +336,29 @@ pub(super) fn add_countdown_text(
 }
 
 pub(super) fn update_coundown_text_system(
+    current_mode: Res<CurrentGameMode>,
+    palette: Res<Palette>,
     mut text_query: Query<&mut Text, With<PlayerCountdownText>>,
     player_query: Query<&HungerCountdown, With<Player>>,
 ) {
     for mut text in text_query.iter_mut() {
+        // there is nothing to count down to in modes without hunger
+        if !current_mode.active().has_hunger() {
+            text.sections[0].value = "".to_string();
+            continue;
+        }
+
         for hunger_countdown in player_query.iter() {
-            text.sections[0].value = format!("{:.1}", hunger_countdown.time_left);
-            if hunger_countdown.time_left < 5.0 {
-                text.sections[0].style.color = Color::RED;
+            let style = &mut text.sections[0].style;
+            // growing the text too means running out of time isn't shown by colour alone
+            if hunger_countdown.time_left < COUNTDOWN_WARNING_TIME {
+                style.color = palette.danger;
+                style.font_size = COUNTDOWN_WARNING_FONT_SIZE;
             } else {
-                text.sections[0].style.color = Color::PINK;
+                style.color = palette.countdown;
+                style.font_size = COUNTDOWN_FONT_SIZE;
             }
+            text.sections[0].value = format!("{:.1}", hunger_countdown.time_left);
         }
     }
 }
@@ -0,0 +1,285 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::attributes::Player;
+use super::render::PlayerStateAnimations;
+use super::states::{PlayerState, PlayerStates};
+use crate::localization::Localization;
+use crate::shared::{
+    animation::{Animation, AnimationSet, AnimationState},
+    atlas::SpriteAtlas,
+    storage,
+};
+use crate::stats::LifetimeStats;
+
+const SKIN_SELECTION_KEY: &str = "skin";
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub(crate) enum SkinId {
+    #[default]
+    Classic,
+    Goldfish,
+    Koi,
+    DeepSea,
+    Ghost,
+}
+
+/// Requirement on the player's lifetime stats that has to be met before a skin can be worn.
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum UnlockCondition {
+    Always,
+    ReachScore(u32),
+    EatWorms(u32),
+    PlayGames(u32),
+}
+
+impl UnlockCondition {
+    pub(crate) fn is_met(&self, stats: &LifetimeStats) -> bool {
+        match *self {
+            UnlockCondition::Always => true,
+            UnlockCondition::ReachScore(score) => stats.best_score >= score,
+            UnlockCondition::EatWorms(worms) => stats.worms_eaten >= worms,
+            UnlockCondition::PlayGames(games) => stats.games_played >= games,
+        }
+    }
+
+    pub(crate) fn description(&self, localization: &Localization) -> String {
+        match *self {
+            UnlockCondition::Always => localization.get("unlock-always").to_string(),
+            UnlockCondition::ReachScore(score) => {
+                localization.format("unlock-reach-score", &[("score", &score)])
+            }
+            UnlockCondition::EatWorms(worms) => {
+                localization.format("unlock-eat-worms", &[("worms", &worms)])
+            }
+            UnlockCondition::PlayGames(games) => {
+                localization.format("unlock-play-games", &[("games", &games)])
+            }
+        }
+    }
+}
+
+/// Describes how a skin looks and how it is unlocked.
+#[derive(Debug)]
+pub(crate) struct SkinDefinition {
+    pub id: SkinId,
+    /// Key of the name in the locale files.
+    pub name_key: &'static str,
+    /// Names of the two frames in the sprite atlas of the swimming animation that every state's
+    /// animation is built from.
+    pub swim_frames: [&'static str; 2],
+    /// Multiplied with the colors of the sprite.
+    pub tint: Color,
+    pub unlock: UnlockCondition,
+}
+
+pub(crate) const SKINS: &[SkinDefinition] = &[
+    SkinDefinition {
+        id: SkinId::Classic,
+        name_key: "skin-classic",
+        swim_frames: ["fish1", "fish2"],
+        tint: Color::WHITE,
+        unlock: UnlockCondition::Always,
+    },
+    SkinDefinition {
+        id: SkinId::Goldfish,
+        name_key: "skin-goldfish",
+        swim_frames: ["fish1", "fish2"],
+        tint: Color::rgb(1.0, 0.75, 0.2),
+        unlock: UnlockCondition::ReachScore(50),
+    },
+    SkinDefinition {
+        id: SkinId::Koi,
+        name_key: "skin-koi",
+        swim_frames: ["fish1", "fish2"],
+        tint: Color::rgb(1.0, 0.45, 0.4),
+        unlock: UnlockCondition::PlayGames(10),
+    },
+    SkinDefinition {
+        id: SkinId::DeepSea,
+        name_key: "skin-deep-sea",
+        swim_frames: ["fish1", "fish2"],
+        tint: Color::rgb(0.45, 0.6, 1.0),
+        unlock: UnlockCondition::EatWorms(50),
+    },
+    SkinDefinition {
+        id: SkinId::Ghost,
+        name_key: "skin-ghost",
+        swim_frames: ["fish1", "fish2"],
+        tint: Color::rgb(0.85, 1.0, 0.95),
+        unlock: UnlockCondition::ReachScore(150),
+    },
+];
+
+pub(crate) fn skin_definition(skin: SkinId) -> &'static SkinDefinition {
+    SKINS
+        .iter()
+        .find(|definition| definition.id == skin)
+        .expect("Every skin id should have a definition")
+}
+
+const PLAYER_ANIMATIONS_PATH: &str = "animations/player.animations.json";
+
+/// The frames and animations for every skin, keyed by the skin's id.
+#[derive(Resource)]
+pub(crate) struct PlayerSkins {
+    animation_set: Handle<AnimationSet>,
+    /// Atlas indices of the two swimming frames of each skin.
+    swim_frames: HashMap<SkinId, [usize; 2]>,
+    /// Empty until the player's animation file has loaded.
+    animations: HashMap<SkinId, PlayerStateAnimations>,
+}
+
+impl FromWorld for PlayerSkins {
+    fn from_world(world: &mut World) -> Self {
+        let sprite_atlas = world.get_resource::<SpriteAtlas>().unwrap();
+
+        debug!("Loading player skins...");
+        PlayerSkins {
+            // the skins only swim without an asset server, e.g. in tests
+            animation_set: world
+                .get_resource::<AssetServer>()
+                .map(|asset_server| asset_server.load(PLAYER_ANIMATIONS_PATH))
+                .unwrap_or_default(),
+            swim_frames: SKINS
+                .iter()
+                .map(|skin| {
+                    let [swim_1_name, swim_2_name] = skin.swim_frames;
+                    (
+                        skin.id,
+                        [
+                            sprite_atlas.index(swim_1_name),
+                            sprite_atlas.index(swim_2_name),
+                        ],
+                    )
+                })
+                .collect(),
+            animations: HashMap::new(),
+        }
+    }
+}
+
+impl PlayerSkins {
+    pub(super) fn animations(&self, skin: SkinId) -> Option<&PlayerStateAnimations> {
+        self.animations.get(&skin)
+    }
+
+    /// Index of the sprite used to show off the skin outside of the game, like in the locker.
+    pub(crate) fn preview_index(&self, skin: SkinId) -> usize {
+        self.swim_frames[&skin][0]
+    }
+
+    /// The animation to play for a state of the player while wearing the skin, if it has loaded.
+    pub(super) fn state_animation(&self, skin: SkinId, state: PlayerStates) -> Option<&Animation> {
+        self.animations(skin)
+            .and_then(|animations| animations.map.get(&state))
+    }
+}
+
+/// Builds the animations of every skin once the player's animation file loads, and again whenever
+/// the file changes. Players start playing the new animation for their current state.
+pub(super) fn load_player_animations_system(
+    mut commands: Commands,
+    mut asset_events: EventReader<AssetEvent<AnimationSet>>,
+    animation_sets: Res<Assets<AnimationSet>>,
+    sprite_atlas: Res<SpriteAtlas>,
+    skin_selection: Res<SkinSelection>,
+    mut player_skins: ResMut<PlayerSkins>,
+    mut player_query: Query<(&PlayerState, &mut TextureAtlas, Entity), With<Player>>,
+) {
+    let animation_set_id = player_skins.animation_set.id();
+    let was_loaded = asset_events
+        .read()
+        .any(|event| event.is_added(animation_set_id) || event.is_modified(animation_set_id));
+
+    if !was_loaded {
+        return;
+    }
+
+    let Some(animation_set) = animation_sets.get(animation_set_id) else {
+        return;
+    };
+
+    debug!("Building player animations...");
+    player_skins.animations = SKINS
+        .iter()
+        .map(|skin| {
+            (
+                skin.id,
+                PlayerStateAnimations::from_animation_set(
+                    animation_set,
+                    &sprite_atlas,
+                    player_skins.swim_frames[&skin.id],
+                ),
+            )
+        })
+        .collect();
+
+    for (player_state, mut texture_atlas, entity) in player_query.iter_mut() {
+        if let Some(animation) =
+            player_skins.state_animation(skin_selection.skin, player_state.current_state)
+        {
+            texture_atlas.index = animation.frames[0].index;
+            commands
+                .entity(entity)
+                .insert(AnimationState::from_animation(animation, 1.0));
+        }
+    }
+}
+
+/// The skin that the player has chosen to wear, saved between sessions.
+#[derive(Debug, Default, Deserialize, Serialize, Resource)]
+pub(crate) struct SkinSelection {
+    pub skin: SkinId,
+}
+
+impl SkinSelection {
+    pub(crate) fn load() -> Self {
+        storage::load_json(SKIN_SELECTION_KEY).unwrap_or_default()
+    }
+
+    pub(crate) fn save(&self) {
+        storage::save_json(SKIN_SELECTION_KEY, self);
+    }
+}
+
+/// Swaps the animations and tint of the existing player when a different skin is chosen.
+#[allow(clippy::type_complexity)]
+pub(super) fn apply_skin_selection_system(
+    player_skins: Res<PlayerSkins>,
+    skin_selection: Res<SkinSelection>,
+    mut query: Query<
+        (
+            &PlayerState,
+            Option<&mut AnimationState>,
+            &mut Sprite,
+            &mut TextureAtlas,
+        ),
+        With<Player>,
+    >,
+) {
+    if !skin_selection.is_changed() || skin_selection.is_added() {
+        return;
+    }
+
+    debug!("Changing player skin to {:?}", skin_selection.skin);
+
+    let tint = skin_definition(skin_selection.skin).tint;
+
+    for (player_state, animation_state, mut sprite, mut texture_atlas) in query.iter_mut() {
+        sprite.color = tint;
+        texture_atlas.index = player_skins.preview_index(skin_selection.skin);
+
+        let Some(next_animation) =
+            player_skins.state_animation(skin_selection.skin, player_state.current_state)
+        else {
+            continue;
+        };
+
+        if let Some(mut animation_state) = animation_state {
+            animation_state.play(next_animation);
+            texture_atlas.index = next_animation.frames[0].index;
+        }
+    }
+}
@@ -1,25 +1,72 @@
 use bevy::prelude::*;
 use std::collections::HashSet;
 
-use super::attributes::{BoostSupply, Player, PlayerStats};
-use super::events::PlayerBoosted;
+use super::attributes::{BoostSupply, BoostTuning, Player, PlayerStats};
+use super::events::{PlayerBoosted, PlayerStateChanged, PlayerStunned};
 use super::movement::move_player_from_input;
+use crate::modifiers::Modifiers;
+use crate::settings::{InputActions, Settings};
 use crate::shared::{
+    arena::Arena,
     game::GameState,
     movement::{SideScrollDirection, Velocity},
 };
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
-pub(super) enum PlayerStates {
+pub(crate) enum PlayerStates {
     Idle,
     Swim,
     Boost,
+    /// Jumping out of the water after boosting into the surface.
+    Leap,
+    /// A fast dash straight down.
+    Dive,
+    /// A short stagger after touching a boat.
+    Stunned,
 }
 
+impl PlayerStates {
+    /// Name of the state's animation in the player's animation file.
+    pub(super) fn animation_name(&self) -> &'static str {
+        match self {
+            PlayerStates::Idle => "idle",
+            PlayerStates::Swim => "swim",
+            PlayerStates::Boost => "boost",
+            PlayerStates::Leap => "leap",
+            PlayerStates::Dive => "dive",
+            PlayerStates::Stunned => "stunned",
+        }
+    }
+}
+
+/// Every transition that the player state machine allows, as `(from, to)` pairs.
+const TRANSITIONS: &[(PlayerStates, PlayerStates)] = &[
+    (PlayerStates::Idle, PlayerStates::Swim),
+    (PlayerStates::Idle, PlayerStates::Boost),
+    (PlayerStates::Idle, PlayerStates::Dive),
+    (PlayerStates::Idle, PlayerStates::Stunned),
+    (PlayerStates::Swim, PlayerStates::Idle),
+    (PlayerStates::Swim, PlayerStates::Boost),
+    (PlayerStates::Swim, PlayerStates::Dive),
+    (PlayerStates::Swim, PlayerStates::Stunned),
+    (PlayerStates::Boost, PlayerStates::Idle),
+    (PlayerStates::Boost, PlayerStates::Swim),
+    (PlayerStates::Boost, PlayerStates::Leap),
+    (PlayerStates::Boost, PlayerStates::Stunned),
+    (PlayerStates::Leap, PlayerStates::Swim),
+    (PlayerStates::Leap, PlayerStates::Stunned),
+    (PlayerStates::Dive, PlayerStates::Idle),
+    (PlayerStates::Dive, PlayerStates::Swim),
+    (PlayerStates::Dive, PlayerStates::Stunned),
+    (PlayerStates::Stunned, PlayerStates::Idle),
+];
+
 #[derive(Debug, Component)]
-pub(super) struct PlayerState {
-    pub(super) current_state: PlayerStates,
+pub(crate) struct PlayerState {
+    pub(crate) current_state: PlayerStates,
     pub(super) blocked_transitions: HashSet<PlayerStates>,
+    /// States entered since the last time the changes were announced.
+    pub(super) changes: Vec<PlayerStates>,
 }
 
 /// State transition methods and helpers
@@ -33,38 +80,43 @@ impl PlayerState {
             return false;
         }
 
-        match self.current_state {
-            PlayerStates::Idle => {
-                target_state == PlayerStates::Swim || target_state == PlayerStates::Boost
-            }
-            PlayerStates::Swim => {
-                target_state == PlayerStates::Idle || target_state == PlayerStates::Boost
-            }
-            PlayerStates::Boost => {
-                target_state == PlayerStates::Idle || target_state == PlayerStates::Swim
-            }
+        TRANSITIONS.contains(&(self.current_state, target_state))
+    }
+
+    /// Moves to the target state if the transition is allowed. Returns whether the state changed.
+    pub(super) fn transition_to(&mut self, target_state: PlayerStates) -> bool {
+        if !self.can_transition_to(target_state) {
+            return false;
         }
+
+        // debug!(
+        //     "Transitioning from state {:?} to {:?}.",
+        //     self.current_state, target_state
+        // );
+        self.changes.push(target_state);
+        self.current_state = target_state;
+        true
     }
 
     pub(super) fn start_swim(&mut self) {
-        if self.can_transition_to(PlayerStates::Swim) {
-            // debug!(
-            //     "Transitioning from state {:?} to {:?}.",
-            //     self.current_state,
-            //     PlayerStates::Swim
-            // );
-            self.current_state = PlayerStates::Swim;
-        }
+        self.transition_to(PlayerStates::Swim);
     }
 
     pub(super) fn start_idle(&mut self) {
-        if self.can_transition_to(PlayerStates::Idle) {
-            // debug!(
-            //     "Transitioning from state {:?} to {:?}.",
-            //     self.current_state,
-            //     PlayerStates::Idle
-            // );
-            self.current_state = PlayerStates::Idle;
+        self.transition_to(PlayerStates::Idle);
+    }
+
+    /// Starts a dive straight down that lasts for the player's dive duration.
+    pub(super) fn start_dive(
+        &mut self,
+        commands: &mut Commands,
+        entity: Entity,
+        player_stats: &PlayerStats,
+    ) {
+        if self.transition_to(PlayerStates::Dive) {
+            commands.entity(entity).insert(DiveData {
+                timer: Timer::from_seconds(player_stats.dive_duration, TimerMode::Once),
+            });
         }
     }
 
@@ -73,7 +125,7 @@ impl PlayerState {
     the direction they are facing. Changes the state and starts the boost cooldown.
     */
     // TODO: Reduce complexity
-    #[allow(clippy::clippy::too_many_arguments)]
+    #[allow(clippy::too_many_arguments)]
     pub(super) fn start_boost(
         &mut self,
         commands: &mut Commands,
@@ -85,11 +137,6 @@ impl PlayerState {
         player_boosted_events: &mut Events<PlayerBoosted>,
     ) {
         if self.can_transition_to(PlayerStates::Boost) {
-            // debug!(
-            //     "Transitioning from state {:?} to {:?}",
-            //     self.current_state,
-            //     PlayerStates::Boost
-            // );
             if boost_supply.use_boost() {
                 let boost_direction = if *target_speed == Vec3::ZERO {
                     if facing.is_right() {
@@ -103,16 +150,21 @@ impl PlayerState {
 
                 let prev_state = self.current_state;
 
-                self.current_state = PlayerStates::Boost;
+                self.transition_to(PlayerStates::Boost);
 
                 commands.entity(entity).insert((
                     BoostCooldown {
                         timer: Timer::from_seconds(player_stats.boost_cooldown, TimerMode::Once),
                         did_release: false,
+                        buffered_frames: 0,
                     },
                     BoostData {
                         velocity: boost_direction * player_stats.boost_speed,
                         timer: Timer::from_seconds(player_stats.boost_duration, TimerMode::Once),
+                        invulnerability: Timer::from_seconds(
+                            player_stats.boost_invulnerability,
+                            TimerMode::Once,
+                        ),
                         prev_state,
                     },
                 ));
@@ -125,6 +177,7 @@ impl PlayerState {
                 commands.entity(entity).insert(BoostCooldown {
                     timer: Timer::from_seconds(0.0, TimerMode::Once),
                     did_release: false,
+                    buffered_frames: 0,
                 });
             }
         }
@@ -136,9 +189,20 @@ impl PlayerState {
 pub(super) struct BoostData {
     velocity: Vec3,
     timer: Timer,
+    /// Started with the boost and may outlast it, so the data stays until both timers finish.
+    invulnerability: Timer,
     prev_state: PlayerStates,
 }
 
+impl BoostData {
+    pub(super) fn is_invulnerable(&self) -> bool {
+        !self.invulnerability.finished()
+    }
+}
+
+/// Number of frames that a boost pressed during the cooldown is remembered for.
+const BOOST_BUFFER_FRAMES: u8 = 6;
+
 /**
 Cooldown component that is applied after boosting that must expire before
 boosting is allowed again
@@ -147,14 +211,50 @@ boosting is allowed again
 pub(super) struct BoostCooldown {
     timer: Timer,
     did_release: bool,
+    buffered_frames: u8,
+}
+
+/// Marks a player whose boost was pressed during the cooldown and should boost as soon as possible.
+#[derive(Debug, Component)]
+pub(super) struct BufferedBoost;
+
+/// Data that is assigned to an entity while it is diving
+#[derive(Debug, Component)]
+pub(super) struct DiveData {
+    timer: Timer,
+}
+
+/**
+Data that is assigned to an entity after it gets stunned by a boat. The stun timer covers
+the stagger itself and the recovery timer covers the window afterwards where touching
+another boat is fatal.
+*/
+#[derive(Debug, Component)]
+pub(super) struct StunData {
+    timer: Timer,
+    recovery: Timer,
+}
+
+impl StunData {
+    pub(super) fn is_recovering(&self) -> bool {
+        !self.recovery.finished()
+    }
 }
 
+/// How close to the surface a boost has to get before it turns into a leap.
+const LEAP_SURFACE_TOLERANCE: f32 = 1.0;
+/// Speed that the player is knocked away from a boat at when stunned.
+const STUN_KNOCKBACK_SPEED: f32 = 250.0;
+
 /// Moves the player when they are not in the Boost state
+#[allow(clippy::type_complexity)]
 pub(super) fn swim_movement_system(
     mut commands: Commands,
     mut boost_events: ResMut<Events<PlayerBoosted>>,
     game_state: Res<GameState>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    modifiers: Res<Modifiers>,
     mut query: Query<(
         &Player,
         &mut Velocity,
@@ -162,22 +262,43 @@ pub(super) fn swim_movement_system(
         Entity,
         &mut PlayerState,
         &mut BoostSupply,
+        Option<&BufferedBoost>,
     )>,
 ) {
     if !game_state.is_running() {
         return;
     }
 
-    for (player, mut velocity, mut facing, entity, mut state, mut boost_supply) in query.iter_mut()
+    for (player, mut velocity, mut facing, entity, mut state, mut boost_supply, buffered_boost) in
+        query.iter_mut()
     {
         if state.current_state != PlayerStates::Idle && state.current_state != PlayerStates::Swim {
             continue;
         }
 
-        let target_speed =
-            move_player_from_input(&keyboard_input, player, &mut velocity, &mut facing);
+        let target_speed = move_player_from_input(
+            &keyboard_input,
+            &settings.controls,
+            &modifiers,
+            player,
+            &mut velocity,
+            &mut facing,
+        );
 
-        if keyboard_input.just_pressed(KeyCode::Space) {
+        if buffered_boost.is_some() {
+            commands.entity(entity).remove::<BufferedBoost>();
+        }
+
+        if settings
+            .controls
+            .just_pressed(&keyboard_input, InputActions::Dive)
+        {
+            state.start_dive(&mut commands, entity, &player.stats);
+        } else if settings
+            .controls
+            .just_pressed(&keyboard_input, InputActions::Boost)
+            || buffered_boost.is_some()
+        {
             state.start_boost(
                 &mut commands,
                 entity,
@@ -195,59 +316,98 @@ pub(super) fn swim_movement_system(
     }
 }
 
-/// Movement system for a boosting player
+/**
+Movement system for a boosting player. Boosting upwards into the surface of the water turns
+the boost into a leap.
+*/
 pub(super) fn boost_movement_system(
     mut commands: Commands,
     time: Res<Time>,
+    arena: Res<Arena>,
     game_state: Res<GameState>,
-    mut query: Query<(&mut BoostData, &mut PlayerState, &mut Velocity, Entity)>,
+    mut query: Query<(
+        &Player,
+        &mut BoostData,
+        &mut PlayerState,
+        &mut Velocity,
+        &Transform,
+        Entity,
+    )>,
 ) {
     if !game_state.is_running() {
         return;
     }
 
-    for (mut boost_data, mut player_state, mut velocity, entity) in query.iter_mut() {
-        if player_state.current_state != PlayerStates::Boost {
-            continue;
-        }
-
-        velocity.0 = boost_data.velocity;
+    let surface_y = (arena.height / 2.0) + arena.offset;
 
+    for (player, mut boost_data, mut player_state, mut velocity, transform, entity) in
+        query.iter_mut()
+    {
+        boost_data.invulnerability.tick(time.delta());
         boost_data.timer.tick(time.delta());
 
-        if boost_data.timer.finished() {
-            // debug!("Boost finished!");
-            commands.entity(entity).remove::<BoostData>();
-            match boost_data.prev_state {
-                PlayerStates::Idle => player_state.start_idle(),
-                PlayerStates::Swim => player_state.start_swim(),
-                _ => panic!("Player boosted from an invalid state"),
+        if player_state.current_state == PlayerStates::Boost {
+            velocity.0 = boost_data.velocity;
+
+            let at_surface = transform.translation.y >= surface_y - LEAP_SURFACE_TOLERANCE;
+
+            if at_surface
+                && boost_data.velocity.y > 0.0
+                && player_state.transition_to(PlayerStates::Leap)
+            {
+                velocity.0 = Vec3::new(
+                    velocity.0.x.clamp(-player.stats.speed, player.stats.speed),
+                    player.stats.leap_speed,
+                    0.0,
+                );
+            } else if boost_data.timer.finished() {
+                // debug!("Boost finished!");
+                match boost_data.prev_state {
+                    PlayerStates::Idle => player_state.start_idle(),
+                    PlayerStates::Swim => player_state.start_swim(),
+                    _ => panic!("Player boosted from an invalid state"),
+                }
             }
         }
+
+        if boost_data.timer.finished() && !boost_data.is_invulnerable() {
+            commands.entity(entity).remove::<BoostData>();
+        }
     }
 }
 
 /**
 Keeps track of the cooldown of the boost. The player must wait the duration
 of the boost cooldown and must release the boost button before being able
-to boost again.
+to boost again. A boost pressed during the cooldown is buffered for a few frames
+and triggered as soon as the cooldown finishes.
 */
 pub(super) fn boost_cooldown_system(
     mut commands: Commands,
     time: Res<Time>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
     game_state: Res<GameState>,
+    boost_tuning: Res<BoostTuning>,
     mut query: Query<(&mut BoostCooldown, &mut PlayerState, Entity)>,
 ) {
     if !game_state.is_running() {
         return;
     }
 
+    let controls = &settings.controls;
     for (mut boost_cooldown, mut player_state, entity) in query.iter_mut() {
         boost_cooldown.timer.tick(time.delta());
 
         boost_cooldown.did_release =
-            boost_cooldown.did_release || !keyboard_input.pressed(KeyCode::Space);
+            boost_cooldown.did_release || !controls.pressed(&keyboard_input, InputActions::Boost);
+
+        if boost_tuning.input_buffer && controls.just_pressed(&keyboard_input, InputActions::Boost)
+        {
+            boost_cooldown.buffered_frames = BOOST_BUFFER_FRAMES;
+        } else {
+            boost_cooldown.buffered_frames = boost_cooldown.buffered_frames.saturating_sub(1);
+        }
 
         if boost_cooldown.timer.finished() && boost_cooldown.did_release {
             // debug!("Boost cooldown finished. Boost can be used again.");
@@ -255,6 +415,10 @@ pub(super) fn boost_cooldown_system(
             player_state
                 .blocked_transitions
                 .remove(&PlayerStates::Boost);
+
+            if boost_cooldown.buffered_frames > 0 {
+                commands.entity(entity).insert(BufferedBoost);
+            }
         } else {
             // insert this every iteration of the timer so that if another system removes this first
             // this debuff will still be applied.
@@ -262,3 +426,266 @@ pub(super) fn boost_cooldown_system(
         }
     }
 }
+
+/**
+Moves a player that is leaping out of the water. The player keeps their momentum and falls back
+down because of their weight until they land back in the water.
+*/
+pub(super) fn leap_movement_system(
+    arena: Res<Arena>,
+    game_state: Res<GameState>,
+    mut query: Query<(&mut PlayerState, &Velocity, &Transform)>,
+) {
+    if !game_state.is_running() {
+        return;
+    }
+
+    let surface_y = (arena.height / 2.0) + arena.offset;
+
+    for (mut player_state, velocity, transform) in query.iter_mut() {
+        if player_state.current_state != PlayerStates::Leap {
+            continue;
+        }
+
+        if transform.translation.y < surface_y && velocity.0.y <= 0.0 {
+            player_state.start_swim();
+        }
+    }
+}
+
+/// Movement system for a diving player
+pub(super) fn dive_movement_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    game_state: Res<GameState>,
+    mut query: Query<(
+        &Player,
+        &mut DiveData,
+        &mut PlayerState,
+        &mut Velocity,
+        Entity,
+    )>,
+) {
+    if !game_state.is_running() {
+        return;
+    }
+
+    for (player, mut dive_data, mut player_state, mut velocity, entity) in query.iter_mut() {
+        dive_data.timer.tick(time.delta());
+
+        if player_state.current_state == PlayerStates::Dive {
+            velocity.0 = Vec3::new(velocity.0.x, -player.stats.dive_speed, 0.0);
+
+            if dive_data.timer.finished() {
+                player_state.start_swim();
+            }
+        }
+
+        if dive_data.timer.finished() || player_state.current_state != PlayerStates::Dive {
+            commands.entity(entity).remove::<DiveData>();
+        }
+    }
+}
+
+/// Staggers the player and knocks them away from the boat they touched.
+pub(super) fn player_stunned_handler(
+    mut commands: Commands,
+    mut player_stunned_reader: EventReader<PlayerStunned>,
+    boat_query: Query<&Transform, Without<Player>>,
+    mut player_query: Query<(&Player, &mut PlayerState, &mut Velocity, &Transform)>,
+) {
+    for stunned_event in player_stunned_reader.read() {
+        let Ok((player, mut player_state, mut velocity, player_transform)) =
+            player_query.get_mut(stunned_event.player_entity)
+        else {
+            continue;
+        };
+
+        if !player_state.transition_to(PlayerStates::Stunned) {
+            continue;
+        }
+
+        debug!("Player was stunned by boat {:?}", stunned_event.boat_entity);
+
+        // knock the player down and away from the side of the boat they hit
+        let away_from_boat = boat_query
+            .get(stunned_event.boat_entity)
+            .map(|boat_transform| {
+                (player_transform.translation.x - boat_transform.translation.x).signum()
+            })
+            .unwrap_or(0.0);
+
+        velocity.0 = Vec3::new(away_from_boat, -1.0, 0.0).normalize() * STUN_KNOCKBACK_SPEED;
+
+        commands
+            .entity(stunned_event.player_entity)
+            .insert(StunData {
+                timer: Timer::from_seconds(player.stats.stun_duration, TimerMode::Once),
+                recovery: Timer::from_seconds(
+                    player.stats.stun_duration + player.stats.stun_recovery,
+                    TimerMode::Once,
+                ),
+            });
+    }
+}
+
+/// Slows down a stunned player and returns them to idle when the stun wears off.
+pub(super) fn stunned_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    game_state: Res<GameState>,
+    mut query: Query<(
+        &Player,
+        &mut StunData,
+        &mut PlayerState,
+        &mut Velocity,
+        Entity,
+    )>,
+) {
+    if !game_state.is_running() {
+        return;
+    }
+
+    for (player, mut stun_data, mut player_state, mut velocity, entity) in query.iter_mut() {
+        stun_data.timer.tick(time.delta());
+        stun_data.recovery.tick(time.delta());
+
+        if player_state.current_state == PlayerStates::Stunned {
+            velocity.0 *= 1.0 - (player.stats.traction * time.delta_seconds());
+
+            if stun_data.timer.finished() {
+                player_state.start_idle();
+            }
+        }
+
+        if !stun_data.is_recovering() {
+            commands.entity(entity).remove::<StunData>();
+        }
+    }
+}
+
+/// Sends a `PlayerStateChanged` event for every transition the player made this frame.
+pub(super) fn emit_state_changes_system(
+    mut query: Query<(&mut PlayerState, Entity)>,
+    mut state_changed_events: EventWriter<PlayerStateChanged>,
+) {
+    for (mut player_state, entity) in query.iter_mut() {
+        if player_state.changes.is_empty() {
+            continue;
+        }
+
+        for new_state in player_state.changes.drain(..) {
+            state_changed_events.send(PlayerStateChanged {
+                player_entity: entity,
+                new_state,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(current_state: PlayerStates) -> PlayerState {
+        PlayerState {
+            current_state,
+            blocked_transitions: HashSet::new(),
+            changes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn cannot_transition_to_the_current_state() {
+        for &(from, _) in TRANSITIONS {
+            assert!(!state(from).can_transition_to(from));
+        }
+    }
+
+    #[test]
+    fn boost_is_allowed_from_idle_and_swim() {
+        assert!(state(PlayerStates::Idle).can_transition_to(PlayerStates::Boost));
+        assert!(state(PlayerStates::Swim).can_transition_to(PlayerStates::Boost));
+        assert!(!state(PlayerStates::Leap).can_transition_to(PlayerStates::Boost));
+        assert!(!state(PlayerStates::Dive).can_transition_to(PlayerStates::Boost));
+        assert!(!state(PlayerStates::Stunned).can_transition_to(PlayerStates::Boost));
+    }
+
+    #[test]
+    fn leap_is_only_reachable_from_boost() {
+        assert!(state(PlayerStates::Boost).can_transition_to(PlayerStates::Leap));
+        assert!(!state(PlayerStates::Idle).can_transition_to(PlayerStates::Leap));
+        assert!(!state(PlayerStates::Swim).can_transition_to(PlayerStates::Leap));
+        assert!(!state(PlayerStates::Dive).can_transition_to(PlayerStates::Leap));
+    }
+
+    #[test]
+    fn leap_lands_by_swimming() {
+        assert!(state(PlayerStates::Leap).can_transition_to(PlayerStates::Swim));
+        assert!(!state(PlayerStates::Leap).can_transition_to(PlayerStates::Idle));
+    }
+
+    #[test]
+    fn dive_is_reachable_from_idle_and_swim() {
+        assert!(state(PlayerStates::Idle).can_transition_to(PlayerStates::Dive));
+        assert!(state(PlayerStates::Swim).can_transition_to(PlayerStates::Dive));
+        assert!(!state(PlayerStates::Boost).can_transition_to(PlayerStates::Dive));
+    }
+
+    #[test]
+    fn stunned_is_reachable_from_every_moving_state() {
+        for from in [
+            PlayerStates::Idle,
+            PlayerStates::Swim,
+            PlayerStates::Boost,
+            PlayerStates::Leap,
+            PlayerStates::Dive,
+        ] {
+            assert!(state(from).can_transition_to(PlayerStates::Stunned));
+        }
+    }
+
+    #[test]
+    fn stunned_only_recovers_to_idle() {
+        let stunned = state(PlayerStates::Stunned);
+
+        assert!(stunned.can_transition_to(PlayerStates::Idle));
+        assert!(!stunned.can_transition_to(PlayerStates::Swim));
+        assert!(!stunned.can_transition_to(PlayerStates::Boost));
+        assert!(!stunned.can_transition_to(PlayerStates::Dive));
+    }
+
+    #[test]
+    fn blocked_transitions_are_not_allowed() {
+        let mut player_state = state(PlayerStates::Swim);
+        player_state.blocked_transitions.insert(PlayerStates::Boost);
+
+        assert!(!player_state.can_transition_to(PlayerStates::Boost));
+        assert!(!player_state.transition_to(PlayerStates::Boost));
+        assert_eq!(player_state.current_state, PlayerStates::Swim);
+    }
+
+    #[test]
+    fn transition_to_changes_the_current_state() {
+        let mut player_state = state(PlayerStates::Idle);
+
+        assert!(player_state.transition_to(PlayerStates::Swim));
+        assert_eq!(player_state.current_state, PlayerStates::Swim);
+        assert!(!player_state.transition_to(PlayerStates::Leap));
+        assert_eq!(player_state.current_state, PlayerStates::Swim);
+    }
+
+    #[test]
+    fn transitions_are_recorded_until_announced() {
+        let mut player_state = state(PlayerStates::Idle);
+
+        player_state.transition_to(PlayerStates::Swim);
+        player_state.transition_to(PlayerStates::Leap);
+        player_state.transition_to(PlayerStates::Boost);
+
+        assert_eq!(
+            player_state.changes,
+            vec![PlayerStates::Swim, PlayerStates::Boost]
+        );
+    }
+}
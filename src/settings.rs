@@ -0,0 +1,391 @@
+/**
+Every option the player can change, saved together as one versioned file. The settings are loaded
+before the app is built so that the window and the log can be set up with them, and graphics
+changes made while playing are applied to the window as they happen.
+*/
+use bevy::{
+    log::Level,
+    prelude::*,
+    window::{PresentMode, PrimaryWindow, WindowMode, WindowResolution},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::accessibility::AccessibilitySettings;
+use crate::audio::mixer::AudioSettings;
+use crate::localization::Languages;
+use crate::shared::{render::CameraScaling, stages, storage};
+
+const SETTINGS_KEY: &str = "settings";
+/// Bumped whenever the saved settings change in a way that older files need migrating for.
+const SETTINGS_VERSION: u32 = 1;
+
+/// The version of settings carried over from before they were saved as one file.
+const LEGACY_VERSION: u32 = 0;
+/// Settings used to be saved under a key per area.
+const LEGACY_AUDIO_KEY: &str = "audio_settings";
+const LEGACY_ACCESSIBILITY_KEY: &str = "accessibility";
+const LEGACY_CAMERA_SCALING_KEY: &str = "camera_scaling";
+
+/// The window size at a resolution scale of 1.
+const BASE_RESOLUTION: Vec2 = Vec2::new(1280.0, 720.0);
+pub const RESOLUTION_SCALES: [f32; 5] = [0.5, 0.75, 1.0, 1.5, 2.0];
+
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GraphicsSettings {
+    pub fullscreen: bool,
+    pub vsync: bool,
+    /// Scales the size of the window when it isn't fullscreen.
+    pub resolution_scale: f32,
+}
+
+impl Default for GraphicsSettings {
+    fn default() -> Self {
+        Self {
+            fullscreen: false,
+            vsync: false,
+            resolution_scale: 1.0,
+        }
+    }
+}
+
+impl GraphicsSettings {
+    pub fn window_mode(&self) -> WindowMode {
+        if self.fullscreen {
+            WindowMode::BorderlessFullscreen
+        } else {
+            WindowMode::Windowed
+        }
+    }
+
+    pub fn present_mode(&self) -> PresentMode {
+        if self.vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        }
+    }
+
+    pub fn resolution(&self) -> WindowResolution {
+        let size = BASE_RESOLUTION * self.resolution_scale;
+        WindowResolution::new(size.x, size.y)
+    }
+
+    /// Moves on to the next resolution scale, wrapping back around to the smallest one.
+    pub fn next_resolution_scale(&mut self) {
+        self.resolution_scale = RESOLUTION_SCALES
+            .iter()
+            .copied()
+            .find(|scale| *scale > self.resolution_scale)
+            .unwrap_or(RESOLUTION_SCALES[0]);
+    }
+}
+
+/// Things the player can do with the keyboard, each bound to keys in `ControlBindings`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum InputActions {
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    Boost,
+    Dive,
+}
+
+pub const INPUT_ACTIONS: [InputActions; 6] = [
+    InputActions::MoveLeft,
+    InputActions::MoveRight,
+    InputActions::MoveUp,
+    InputActions::MoveDown,
+    InputActions::Boost,
+    InputActions::Dive,
+];
+
+impl InputActions {
+    /// The key of the action's name in the locale files.
+    pub fn text_key(&self) -> &'static str {
+        match self {
+            InputActions::MoveLeft => "action-move-left",
+            InputActions::MoveRight => "action-move-right",
+            InputActions::MoveUp => "action-move-up",
+            InputActions::MoveDown => "action-move-down",
+            InputActions::Boost => "action-boost",
+            InputActions::Dive => "action-dive",
+        }
+    }
+}
+
+/// The keys an action is bound to. Only the primary key can be rebound.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct KeyBinding {
+    pub primary: KeyCode,
+    pub secondary: Option<KeyCode>,
+}
+
+impl KeyBinding {
+    const fn new(primary: KeyCode, secondary: Option<KeyCode>) -> Self {
+        Self { primary, secondary }
+    }
+
+    fn keys(&self) -> impl Iterator<Item = KeyCode> {
+        std::iter::once(self.primary).chain(self.secondary)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ControlBindings {
+    pub move_left: KeyBinding,
+    pub move_right: KeyBinding,
+    pub move_up: KeyBinding,
+    pub move_down: KeyBinding,
+    pub boost: KeyBinding,
+    pub dive: KeyBinding,
+}
+
+impl Default for ControlBindings {
+    fn default() -> Self {
+        Self {
+            move_left: KeyBinding::new(KeyCode::KeyA, Some(KeyCode::ArrowLeft)),
+            move_right: KeyBinding::new(KeyCode::KeyD, Some(KeyCode::ArrowRight)),
+            move_up: KeyBinding::new(KeyCode::KeyW, Some(KeyCode::ArrowUp)),
+            move_down: KeyBinding::new(KeyCode::KeyS, Some(KeyCode::ArrowDown)),
+            boost: KeyBinding::new(KeyCode::Space, None),
+            dive: KeyBinding::new(KeyCode::ShiftLeft, None),
+        }
+    }
+}
+
+impl ControlBindings {
+    pub fn binding(&self, action: InputActions) -> &KeyBinding {
+        match action {
+            InputActions::MoveLeft => &self.move_left,
+            InputActions::MoveRight => &self.move_right,
+            InputActions::MoveUp => &self.move_up,
+            InputActions::MoveDown => &self.move_down,
+            InputActions::Boost => &self.boost,
+            InputActions::Dive => &self.dive,
+        }
+    }
+
+    fn binding_mut(&mut self, action: InputActions) -> &mut KeyBinding {
+        match action {
+            InputActions::MoveLeft => &mut self.move_left,
+            InputActions::MoveRight => &mut self.move_right,
+            InputActions::MoveUp => &mut self.move_up,
+            InputActions::MoveDown => &mut self.move_down,
+            InputActions::Boost => &mut self.boost,
+            InputActions::Dive => &mut self.dive,
+        }
+    }
+
+    pub fn pressed(&self, keyboard_input: &ButtonInput<KeyCode>, action: InputActions) -> bool {
+        keyboard_input.any_pressed(self.binding(action).keys())
+    }
+
+    pub fn just_pressed(
+        &self,
+        keyboard_input: &ButtonInput<KeyCode>,
+        action: InputActions,
+    ) -> bool {
+        keyboard_input.any_just_pressed(self.binding(action).keys())
+    }
+
+    /**
+    Binds the primary key of the action to the key. An action that was already bound to the key
+    swaps keys with the rebound action, so every key keeps doing at most one thing.
+    */
+    pub fn rebind(&mut self, action: InputActions, key: KeyCode) {
+        let old_key = self.binding(action).primary;
+
+        for other_action in INPUT_ACTIONS {
+            let other_binding = self.binding_mut(other_action);
+
+            if other_binding.primary == key {
+                other_binding.primary = old_key;
+            }
+
+            if other_binding.secondary == Some(key) {
+                other_binding.secondary = None;
+            }
+        }
+
+        self.binding_mut(action).primary = key;
+    }
+}
+
+/// How much the game logs. Only set in the settings file, for tracking down problems.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub enum LogLevels {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Default for LogLevels {
+    fn default() -> Self {
+        if cfg!(debug_assertions) {
+            LogLevels::Debug
+        } else {
+            LogLevels::Error
+        }
+    }
+}
+
+impl LogLevels {
+    pub fn level(&self) -> Level {
+        match self {
+            LogLevels::Error => Level::ERROR,
+            LogLevels::Warn => Level::WARN,
+            LogLevels::Info => Level::INFO,
+            LogLevels::Debug => Level::DEBUG,
+            LogLevels::Trace => Level::TRACE,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Resource)]
+#[serde(default)]
+pub struct Settings {
+    pub version: u32,
+    pub graphics: GraphicsSettings,
+    pub audio: AudioSettings,
+    pub accessibility: AccessibilitySettings,
+    pub camera_scaling: CameraScaling,
+    pub controls: ControlBindings,
+    /// The language of the game's text, following the system's language when there is none.
+    pub language: Option<Languages>,
+    pub log_level: LogLevels,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            graphics: GraphicsSettings::default(),
+            audio: AudioSettings::default(),
+            accessibility: AccessibilitySettings::default(),
+            camera_scaling: CameraScaling::default(),
+            controls: ControlBindings::default(),
+            language: None,
+            log_level: LogLevels::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// Loads the saved settings. They are migrated by the `SettingsPlugin`, once there is a log to
+    /// tell about it.
+    pub fn load() -> Self {
+        if let Some(settings) = storage::load_json::<Settings>(SETTINGS_KEY) {
+            return settings;
+        }
+
+        Self {
+            version: LEGACY_VERSION,
+            audio: storage::load_json(LEGACY_AUDIO_KEY).unwrap_or_default(),
+            accessibility: storage::load_json(LEGACY_ACCESSIBILITY_KEY).unwrap_or_default(),
+            camera_scaling: storage::load_json(LEGACY_CAMERA_SCALING_KEY).unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+
+    pub fn save(&self) {
+        storage::save_json(SETTINGS_KEY, self);
+    }
+
+    /// Upgrades settings saved by an older version. Options that didn't exist yet already got
+    /// their defaults while loading.
+    fn migrate(&mut self) {
+        if self.version == LEGACY_VERSION {
+            debug!("No saved settings found, carried over the settings of older versions");
+        }
+
+        if self.version < SETTINGS_VERSION {
+            debug!(
+                "Migrating settings from version {} to {}",
+                self.version, SETTINGS_VERSION
+            );
+            self.version = SETTINGS_VERSION;
+        }
+    }
+}
+
+/// Applies changes to the settings while the game is running. The settings themselves are loaded
+/// in `main` so the window starts out with them, and migrated here once the log is set up.
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        debug!("Building settings plugin...");
+        if let Some(mut settings) = app.world.get_resource_mut::<Settings>() {
+            settings.migrate();
+        }
+
+        app.add_systems(
+            Update,
+            (apply_graphics_settings_system).in_set(stages::PrepareRenderSet),
+        );
+    }
+}
+
+/// Only touches the window when the graphics settings change, so resizing the window by hand
+/// sticks until the resolution scale is changed.
+fn apply_graphics_settings_system(
+    settings: Res<Settings>,
+    mut applied_graphics: Local<Option<GraphicsSettings>>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let graphics = settings.graphics;
+    let Some(applied) = applied_graphics.replace(graphics) else {
+        // the window was created with these settings
+        return;
+    };
+
+    if applied == graphics {
+        return;
+    }
+
+    let Ok(mut window) = window_query.get_single_mut() else {
+        return;
+    };
+
+    debug!("Applying graphics settings: {:?}", graphics);
+    window.mode = graphics.window_mode();
+    window.present_mode = graphics.present_mode();
+
+    if applied.resolution_scale != graphics.resolution_scale {
+        let size = graphics.resolution();
+        window.resolution.set(size.width(), size.height());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rebinding_a_used_key_swaps_it() {
+        let mut controls = ControlBindings::default();
+
+        controls.rebind(InputActions::Boost, KeyCode::KeyW);
+        assert_eq!(controls.boost.primary, KeyCode::KeyW);
+        assert_eq!(controls.move_up.primary, KeyCode::Space);
+
+        controls.rebind(InputActions::Dive, KeyCode::ArrowLeft);
+        assert_eq!(controls.dive.primary, KeyCode::ArrowLeft);
+        assert_eq!(controls.move_left.secondary, None);
+    }
+
+    #[test]
+    fn settings_without_new_options_still_load() {
+        let settings: Settings =
+            serde_json::from_str(r#"{"version": 0, "graphics": {"fullscreen": true}}"#).unwrap();
+
+        assert!(settings.graphics.fullscreen);
+        assert_eq!(settings.graphics.resolution_scale, 1.0);
+        assert_eq!(settings.controls, ControlBindings::default());
+    }
+}
@@ -1,21 +1,44 @@
+use bevy::asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext};
 use bevy::prelude::*;
-use bevy::utils::Duration;
+use bevy::utils::{BoxedFuture, Duration};
+use serde::Deserialize;
+use std::collections::HashMap;
 
+use super::collision::Collider;
 use super::game::GameState;
 
+/// How an animation continues once it reaches its last frame.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnimationModes {
+    /// Play once and stay on the last frame.
+    Once,
+    /// Start over from the first frame forever.
+    Loop,
+    /// Play forwards then backwards forever.
+    PingPong,
+    /// Play the given number of times and stay on the last frame.
+    Repeat(u32),
+}
+
 /**
 Represents one frame of animation.
 **/
 #[derive(Debug, Clone, PartialEq)]
 pub struct AnimationFrame {
-    pub material_handle: Handle<Image>,
+    /// Index of the frame's sprite in the sprite atlas.
+    pub index: usize,
     pub time: f32,
+    /// Size of the entity's collider while the frame is showing, instead of its usual size.
+    pub hitbox: Option<Vec2>,
+    /// Sound bank cue to play when the frame starts showing.
+    pub sound: Option<String>,
 }
 
 /// Represents an entire animation
 #[derive(Debug, Clone, PartialEq)]
 pub struct Animation {
-    pub should_loop: bool,
+    pub mode: AnimationModes,
     pub frames: Vec<AnimationFrame>,
 }
 
@@ -26,6 +49,15 @@ pub struct AnimationState {
     pub timer: Timer,
     pub frame_index: usize,
     pub speed_multiplier: f32,
+    /// Whether a ping-pong animation is going backwards.
+    reversing: bool,
+    /// How many times the animation has played all the way through.
+    plays: u32,
+    finished: bool,
+    /// Whether the first frame has been shown, which happens the next time animations update.
+    started: bool,
+    /// The collider size to go back to after a frame with a hitbox override.
+    default_hitbox: Option<Vec2>,
 }
 
 // TODO: Add a sprite material when this component is added
@@ -37,38 +69,131 @@ impl AnimationState {
             timer: Timer::from_seconds(animation.frames[0].time, TimerMode::Once),
             frame_index: 0,
             speed_multiplier,
+            reversing: false,
+            plays: 0,
+            finished: false,
+            started: false,
+            default_hitbox: None,
+        }
+    }
+
+    /// Switches to a different animation, starting from its first frame.
+    pub fn play(&mut self, animation: &Animation) {
+        self.animation = animation.clone();
+        self.frame_index = 0;
+        self.reversing = false;
+        self.plays = 0;
+        self.finished = false;
+        self.started = false;
+        self.timer
+            .set_duration(Duration::from_secs_f32(animation.frames[0].time));
+        self.timer.reset();
+    }
+
+    /// Whether the animation played to its end and stays on its last frame.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Moves to the frame that comes after the current one. Returns `None` once the animation is
+    /// over.
+    fn advance(&mut self) -> Option<usize> {
+        let last_frame = self.animation.frames.len() - 1;
+
+        if last_frame == 0 {
+            return match self.animation.mode {
+                AnimationModes::Loop | AnimationModes::PingPong => Some(0),
+                AnimationModes::Once | AnimationModes::Repeat(_) => None,
+            };
+        }
+
+        match self.animation.mode {
+            AnimationModes::Once if self.frame_index == last_frame => None,
+            AnimationModes::Once => Some(self.frame_index + 1),
+            AnimationModes::Loop => Some((self.frame_index + 1) % (last_frame + 1)),
+            AnimationModes::Repeat(times) if self.frame_index == last_frame => {
+                self.plays += 1;
+                if self.plays >= times {
+                    None
+                } else {
+                    Some(0)
+                }
+            }
+            AnimationModes::Repeat(_) => Some(self.frame_index + 1),
+            AnimationModes::PingPong => {
+                if self.frame_index == last_frame {
+                    self.reversing = true;
+                } else if self.frame_index == 0 {
+                    self.reversing = false;
+                }
+
+                if self.reversing {
+                    Some(self.frame_index - 1)
+                } else {
+                    Some(self.frame_index + 1)
+                }
+            }
         }
     }
 }
 
-/// Transitions the animation state if it is time for the next frame
+/// Sent when an animation that does not repeat forever reaches its end.
+#[derive(Debug, Event)]
+pub struct AnimationFinished {
+    pub entity: Entity,
+}
+
+/// Sent when an animation reaches a frame that plays a sound.
+#[derive(Debug, Event)]
+pub struct AnimationSound {
+    pub entity: Entity,
+    pub sound: String,
+}
+
+/// Shows the first frame of animations that just started, and transitions the animation state if
+/// it is time for the next frame. Showing a frame sets its sprite, hitbox and sound.
 pub(super) fn animation_system(
     time: Res<Time>,
     game_state: Res<GameState>,
-    mut query: Query<(&mut AnimationState, &mut Handle<Image>)>,
+    mut animation_finished_events: EventWriter<AnimationFinished>,
+    mut animation_sound_events: EventWriter<AnimationSound>,
+    mut query: Query<(
+        &mut AnimationState,
+        &mut TextureAtlas,
+        Option<&mut Collider>,
+        Entity,
+    )>,
 ) {
     if !game_state.is_running() {
         return;
     }
 
-    for (mut animation_state, mut material_handle) in query.iter_mut() {
-        let speed_multiplier = animation_state.speed_multiplier;
-        animation_state.timer.tick(Duration::from_secs_f32(
-            time.delta().as_secs_f32() * speed_multiplier,
-        ));
+    for (mut animation_state, mut texture_atlas, collider, entity) in query.iter_mut() {
+        let next_frame = if !animation_state.started {
+            // the timer of the first frame is already set up when the animation starts
+            animation_state.started = true;
+            animation_state.animation.frames[animation_state.frame_index].clone()
+        } else {
+            if animation_state.finished {
+                continue;
+            }
 
-        if animation_state.timer.finished() {
-            let cur_animation = &animation_state.animation;
-            let cur_frame = animation_state.frame_index;
-            let num_frames = cur_animation.frames.len();
+            let speed_multiplier = animation_state.speed_multiplier;
+            animation_state.timer.tick(Duration::from_secs_f32(
+                time.delta().as_secs_f32() * speed_multiplier,
+            ));
 
-            if (cur_frame + 1) == num_frames && !cur_animation.should_loop {
+            if !animation_state.timer.finished() {
                 continue;
             }
 
-            let next_frame_index =
-                (animation_state.frame_index + 1) % animation_state.animation.frames.len();
-            let next_frame = cur_animation.frames[next_frame_index].clone();
+            let Some(next_frame_index) = animation_state.advance() else {
+                animation_state.finished = true;
+                animation_finished_events.send(AnimationFinished { entity });
+                continue;
+            };
+
+            let next_frame = animation_state.animation.frames[next_frame_index].clone();
 
             animation_state.frame_index = next_frame_index;
 
@@ -77,7 +202,171 @@ pub(super) fn animation_system(
                 .set_duration(Duration::from_secs_f32(next_frame.time));
             animation_state.timer.reset();
 
-            *material_handle = next_frame.material_handle.clone();
+            next_frame
+        };
+
+        texture_atlas.index = next_frame.index;
+
+        if let Some(mut collider) = collider {
+            let default_hitbox = *animation_state
+                .default_hitbox
+                .get_or_insert(Vec2::new(collider.width, collider.height));
+            let hitbox = next_frame.hitbox.unwrap_or(default_hitbox);
+
+            collider.width = hitbox.x;
+            collider.height = hitbox.y;
+        }
+
+        if let Some(sound) = next_frame.sound {
+            animation_sound_events.send(AnimationSound { entity, sound });
+        }
+    }
+}
+
+/// How a frame is written in an animation file.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AnimationFrameDefinition {
+    /// Name of the frame's sprite in the sprite atlas.
+    pub sprite: String,
+    pub time: f32,
+    #[serde(default)]
+    pub hitbox: Option<[f32; 2]>,
+    #[serde(default)]
+    pub sound: Option<String>,
+}
+
+/// How an animation is written in an animation file.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AnimationDefinition {
+    pub mode: AnimationModes,
+    pub frames: Vec<AnimationFrameDefinition>,
+}
+
+impl AnimationDefinition {
+    /// Builds the animation, looking up the index of each frame's sprite by its name.
+    pub fn resolve(&self, sprite_index: impl Fn(&str) -> usize) -> Animation {
+        Animation {
+            mode: self.mode,
+            frames: self
+                .frames
+                .iter()
+                .map(|frame| AnimationFrame {
+                    index: sprite_index(&frame.sprite),
+                    time: frame.time,
+                    hitbox: frame.hitbox.map(Vec2::from),
+                    sound: frame.sound.clone(),
+                })
+                .collect(),
         }
     }
 }
+
+/// A file of animations, keyed by name.
+#[derive(Debug, Asset, TypePath)]
+pub struct AnimationSet {
+    pub animations: HashMap<String, AnimationDefinition>,
+}
+
+impl AnimationSet {
+    pub fn get(&self, name: &str) -> Option<&AnimationDefinition> {
+        self.animations.get(name)
+    }
+}
+
+#[derive(Default)]
+pub(super) struct AnimationSetLoader;
+
+impl AssetLoader for AnimationSetLoader {
+    type Asset = AnimationSet;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a Self::Settings,
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+
+            let animations = serde_json::from_slice(&bytes)?;
+
+            Ok(AnimationSet { animations })
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["animations.json"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn animation_state(mode: AnimationModes, num_frames: usize) -> AnimationState {
+        let frames = (0..num_frames)
+            .map(|index| AnimationFrame {
+                index,
+                time: 0.1,
+                hitbox: None,
+                sound: None,
+            })
+            .collect();
+
+        AnimationState::from_animation(&Animation { mode, frames }, 1.0)
+    }
+
+    /// Advances through the animation until it ends or `steps` frames have played.
+    fn play(animation_state: &mut AnimationState, steps: usize) -> Vec<usize> {
+        let mut frames = Vec::new();
+        for _ in 0..steps {
+            match animation_state.advance() {
+                Some(frame_index) => {
+                    animation_state.frame_index = frame_index;
+                    frames.push(frame_index);
+                }
+                None => break,
+            }
+        }
+        frames
+    }
+
+    #[test]
+    fn once_stops_on_the_last_frame() {
+        let mut animation_state = animation_state(AnimationModes::Once, 3);
+        assert_eq!(play(&mut animation_state, 10), vec![1, 2]);
+    }
+
+    #[test]
+    fn loop_starts_over() {
+        let mut animation_state = animation_state(AnimationModes::Loop, 3);
+        assert_eq!(play(&mut animation_state, 5), vec![1, 2, 0, 1, 2]);
+    }
+
+    #[test]
+    fn ping_pong_goes_back_and_forth() {
+        let mut animation_state = animation_state(AnimationModes::PingPong, 3);
+        assert_eq!(play(&mut animation_state, 6), vec![1, 2, 1, 0, 1, 2]);
+    }
+
+    #[test]
+    fn repeat_plays_the_given_number_of_times() {
+        let mut animation_state = animation_state(AnimationModes::Repeat(2), 2);
+        assert_eq!(play(&mut animation_state, 10), vec![1, 0, 1]);
+    }
+
+    #[test]
+    fn single_frame_animations_only_finish_when_they_do_not_repeat() {
+        assert_eq!(
+            play(&mut animation_state(AnimationModes::Once, 1), 3),
+            Vec::<usize>::new()
+        );
+        assert_eq!(
+            play(&mut animation_state(AnimationModes::Loop, 1), 3),
+            vec![0, 0, 0]
+        );
+    }
+}
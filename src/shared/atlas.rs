@@ -0,0 +1,223 @@
+/**
+All of the game's sprites are packed into a single texture atlas when the game starts, so every
+sprite is drawn from the same texture and sprites are referenced by their index in the atlas.
+
+Sprites can either be separate images or sprite sheets laid out in a grid. Every frame of a sprite
+sheet gets its own index, in order from left to right and top to bottom. The indices are known
+before the images finish loading, so entities can be spawned with their atlas index right away and
+show up once the atlas is packed.
+*/
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// Describes how the frames of a sprite sheet are laid out.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct SpriteSheetGrid {
+    pub tile_size: UVec2,
+    pub columns: u32,
+    pub rows: u32,
+}
+
+/// An image that is packed into the atlas.
+#[derive(Debug)]
+pub(crate) struct AtlasSource {
+    pub name: &'static str,
+    pub path: &'static str,
+    /// Set when the image is a sprite sheet instead of a single sprite.
+    pub grid: Option<SpriteSheetGrid>,
+}
+
+impl AtlasSource {
+    fn num_frames(&self) -> usize {
+        self.grid
+            .map(|grid| (grid.columns * grid.rows) as usize)
+            .unwrap_or(1)
+    }
+}
+
+pub(crate) const ATLAS_SOURCES: &[AtlasSource] = &[
+    AtlasSource {
+        name: "fish1",
+        path: "sprites/player/fish1.png",
+        grid: None,
+    },
+    AtlasSource {
+        name: "fish2",
+        path: "sprites/player/fish2.png",
+        grid: None,
+    },
+    AtlasSource {
+        name: "worm1",
+        path: "sprites/worm/worm1.png",
+        grid: None,
+    },
+    AtlasSource {
+        name: "worm2",
+        path: "sprites/worm/worm2.png",
+        grid: None,
+    },
+    AtlasSource {
+        name: "boat",
+        path: "sprites/boat/boat.png",
+        grid: None,
+    },
+    AtlasSource {
+        name: "hook",
+        path: "sprites/hook/hook.png",
+        grid: None,
+    },
+];
+
+/// The packed atlas with every sprite in the game.
+#[derive(Debug, Resource)]
+pub(crate) struct SpriteAtlas {
+    pub image: Handle<Image>,
+    pub layout: Handle<TextureAtlasLayout>,
+    /// Index of the first frame of each source, keyed by the source's name.
+    first_indices: HashMap<&'static str, usize>,
+}
+
+impl SpriteAtlas {
+    /// Index of a single sprite.
+    pub fn index(&self, name: &str) -> usize {
+        self.frame(name, 0)
+    }
+
+    /// Index of one frame of a sprite sheet.
+    pub fn frame(&self, name: &str, frame: usize) -> usize {
+        let first_index = self
+            .first_indices
+            .get(name)
+            .unwrap_or_else(|| panic!("No sprite named {} in the atlas", name));
+
+        first_index + frame
+    }
+
+    pub fn texture_atlas(&self, index: usize) -> TextureAtlas {
+        TextureAtlas {
+            layout: self.layout.clone(),
+            index,
+        }
+    }
+}
+
+/// The images that still have to be packed into the atlas. Removed once the atlas is packed.
+#[derive(Debug, Resource)]
+pub(super) struct AtlasSources {
+    images: Vec<Handle<Image>>,
+}
+
+impl FromWorld for SpriteAtlas {
+    fn from_world(world: &mut World) -> Self {
+        let mut first_indices = HashMap::new();
+        let mut next_index = 0;
+        for source in ATLAS_SOURCES {
+            first_indices.insert(source.name, next_index);
+            next_index += source.num_frames();
+        }
+
+        // Without an asset server, e.g. in tests, the atlas is never packed but sprites still know
+        // their indices.
+        let Some(asset_server) = world.get_resource::<AssetServer>() else {
+            return SpriteAtlas {
+                image: Handle::default(),
+                layout: Handle::default(),
+                first_indices,
+            };
+        };
+
+        debug!("Loading sprites for the atlas...");
+        let images = ATLAS_SOURCES
+            .iter()
+            .map(|source| asset_server.load(source.path))
+            .collect();
+
+        // The handles are filled in when the atlas is packed.
+        let image = world.resource::<Assets<Image>>().reserve_handle();
+        let layout = world
+            .resource::<Assets<TextureAtlasLayout>>()
+            .reserve_handle();
+
+        world.insert_resource(AtlasSources { images });
+
+        SpriteAtlas {
+            image,
+            layout,
+            first_indices,
+        }
+    }
+}
+
+/// Packs the sprites into the atlas once all of them have loaded.
+pub(super) fn pack_sprite_atlas_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    sprite_atlas: Res<SpriteAtlas>,
+    atlas_sources: Option<Res<AtlasSources>>,
+    mut images: ResMut<Assets<Image>>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    let Some(atlas_sources) = atlas_sources else {
+        return;
+    };
+
+    for (source, image) in ATLAS_SOURCES.iter().zip(atlas_sources.images.iter()) {
+        match asset_server.load_state(image) {
+            LoadState::Loaded => {}
+            LoadState::Failed => {
+                error!("Could not load {} for the sprite atlas", source.path);
+                commands.remove_resource::<AtlasSources>();
+                return;
+            }
+            _ => return,
+        }
+    }
+
+    let mut builder = TextureAtlasBuilder::default().padding(UVec2::ONE);
+    for image in atlas_sources.images.iter() {
+        builder.add_texture(Some(image.id()), images.get(image).unwrap());
+    }
+
+    let (packed_layout, packed_image) = match builder.finish() {
+        Ok(packed) => packed,
+        Err(error) => {
+            error!("Could not pack the sprite atlas: {:?}", error);
+            commands.remove_resource::<AtlasSources>();
+            return;
+        }
+    };
+
+    // The packer places sprites wherever they fit, so the layout is rebuilt in the order of the
+    // sources to match the indices handed out before packing.
+    let mut layout = TextureAtlasLayout::new_empty(packed_layout.size);
+    for (source, image) in ATLAS_SOURCES.iter().zip(atlas_sources.images.iter()) {
+        let packed_index = packed_layout.get_texture_index(image).unwrap();
+        let packed_rect = packed_layout.textures[packed_index];
+
+        let Some(grid) = source.grid else {
+            layout.add_texture(packed_rect);
+            continue;
+        };
+
+        let tile_size = grid.tile_size.as_vec2();
+        for row in 0..grid.rows {
+            for column in 0..grid.columns {
+                let min = packed_rect.min + Vec2::new(column as f32, row as f32) * tile_size;
+                layout.add_texture(Rect::from_corners(min, min + tile_size));
+            }
+        }
+    }
+
+    debug!(
+        "Packed {} sprites into a {:?} atlas",
+        layout.len(),
+        layout.size
+    );
+
+    images.insert(&sprite_atlas.image, packed_image);
+    layouts.insert(&sprite_atlas.layout, layout);
+
+    // the atlas has its own copy of every sprite now
+    commands.remove_resource::<AtlasSources>();
+}
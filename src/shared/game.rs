@@ -1,10 +1,14 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, window::WindowFocused};
 
+use crate::modes::CurrentGameMode;
+use crate::modifiers::Modifiers;
 use crate::objects::boat::Hook;
-use crate::player::events::{PlayerAte, PlayerBonked, PlayerHooked, PlayerStarved};
+use crate::player::events::{PlayerBonked, PlayerHooked, PlayerStarved};
 
 #[derive(Debug, Copy, Clone)]
 pub enum GameStates {
+    /// The title screen shown before the first run, the world is frozen behind it.
+    Menu,
     Running,
     Paused,
     GameOver,
@@ -49,6 +53,10 @@ pub struct GameOver {
     pub winning_boat: Option<Entity>,
 }
 
+/// Sent when the goal of the game mode is reached, which ends the run.
+#[derive(Event)]
+pub struct GoalReached;
+
 #[derive(Event)]
 pub struct GamePaused;
 
@@ -58,13 +66,29 @@ pub struct GameUnpaused;
 #[derive(Event)]
 pub struct GameRestarted;
 
-const MAX_DIFFICULTY: u8 = 4;
-const SCORE_PER_WORM: u8 = 5;
+/// Sent to abandon the current run and go back to the title menu.
+#[derive(Event)]
+pub struct QuitToMenu;
+
+pub const MAX_DIFFICULTY: u8 = 4;
+pub(crate) const SCORE_PER_WORM: u32 = 5;
 
 #[derive(Default, Resource)]
 pub struct Score {
     pub count: u32,
     pub timer: Timer,
+    /// Points that don't add up to a whole point yet because of the score multiplier.
+    pub(super) partial_points: f32,
+}
+
+impl Score {
+    pub(crate) fn add_points(&mut self, points: u32, multiplier: f32) {
+        self.partial_points += points as f32 * multiplier;
+
+        let whole_points = self.partial_points.floor();
+        self.count += whole_points as u32;
+        self.partial_points -= whole_points;
+    }
 }
 
 #[derive(Default, Resource)]
@@ -89,11 +113,13 @@ pub(super) fn difficulty_scaling_system(
     }
 }
 
+/// Scores the points for staying alive. Worms are scored by the combo system.
 pub(super) fn increment_score_system(
     time: Res<Time>,
     game_state: Res<GameState>,
+    current_mode: Res<CurrentGameMode>,
+    modifiers: Res<Modifiers>,
     mut score: ResMut<Score>,
-    mut player_ate_reader: EventReader<PlayerAte>,
 ) {
     if !game_state.is_running() {
         return;
@@ -102,12 +128,10 @@ pub(super) fn increment_score_system(
     score.timer.tick(time.delta());
 
     if score.timer.finished() {
-        score.count += 1;
-        debug!("Score: {:?}", score.count);
-    }
-
-    for _ in player_ate_reader.read() {
-        score.count += SCORE_PER_WORM as u32;
+        score.add_points(
+            current_mode.active().points_per_second(),
+            modifiers.score_multiplier(),
+        );
         debug!("Score: {:?}", score.count);
     }
 }
@@ -124,6 +148,7 @@ pub(super) fn end_game_system(
     mut player_hooked_reader: EventReader<PlayerHooked>,
     mut player_starved_reader: EventReader<PlayerStarved>,
     mut player_bonked_reader: EventReader<PlayerBonked>,
+    mut goal_reached_reader: EventReader<GoalReached>,
     mut game_over_events: EventWriter<GameOver>,
     mut game_state: ResMut<GameState>,
     hook_query: Query<(&Hook, &Parent)>,
@@ -150,6 +175,12 @@ pub(super) fn end_game_system(
         });
         game_state.transition(GameStates::GameOver);
     }
+
+    for _ in goal_reached_reader.read() {
+        debug!("Ending game because the goal of the game mode was reached.");
+        game_over_events.send(GameOver { winning_boat: None });
+        game_state.transition(GameStates::GameOver);
+    }
 }
 
 pub(super) fn reset_difficulty_on_restart(
@@ -170,6 +201,7 @@ pub(super) fn reset_score_on_restart(
     if restart_reader.read().next().is_some() {
         debug!("Resetting score after restart");
         score.count = 0;
+        score.partial_points = 0.0;
         score.timer = Timer::from_seconds(1.0, TimerMode::Repeating);
     }
 }
@@ -185,21 +217,51 @@ pub(super) fn reset_game_state_on_restart(
     }
 }
 
+/// Only a running game can be paused.
 pub(super) fn pause_game(
     mut game_state: ResMut<GameState>,
     mut pause_reader: EventReader<GamePaused>,
 ) {
-    if pause_reader.read().next().is_some() {
+    if pause_reader.read().next().is_some() && game_state.is_running() {
         game_state.transition(GameStates::Paused);
     }
 }
 
+/// Unpausing always resumes the run, so a late unpause can't bring back an earlier state such as
+/// the game over screen.
 pub(super) fn unpause_game(
     mut game_state: ResMut<GameState>,
     mut unpause_reader: EventReader<GameUnpaused>,
 ) {
     if unpause_reader.read().next().is_some() {
-        let prev_state = game_state.prev_state;
-        game_state.transition(prev_state);
+        if let GameStates::Paused = game_state.cur_state {
+            game_state.transition(GameStates::Running);
+        }
+    }
+}
+
+pub(super) fn quit_to_menu(
+    mut game_state: ResMut<GameState>,
+    mut quit_reader: EventReader<QuitToMenu>,
+) {
+    if quit_reader.read().next().is_some() {
+        debug!("Quitting the run to the menu");
+        game_state.transition(GameStates::Menu);
+    }
+}
+
+/// Pauses the game when the window loses focus, so the fish doesn't die while nobody is looking.
+pub(super) fn pause_on_focus_lost(
+    game_state: Res<GameState>,
+    mut window_focused_reader: EventReader<WindowFocused>,
+    mut game_paused_events: EventWriter<GamePaused>,
+) {
+    let focus_lost = window_focused_reader
+        .read()
+        .any(|focused_event| !focused_event.focused);
+
+    if focus_lost && game_state.is_running() {
+        debug!("Pausing because the window lost focus");
+        game_paused_events.send(GamePaused);
     }
 }
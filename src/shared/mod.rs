@@ -3,23 +3,27 @@ use bevy_prototype_lyon::prelude::*;
 
 pub mod animation;
 pub mod arena;
+pub mod atlas;
 pub mod collision;
 pub mod game;
 pub mod movement;
 pub mod render;
 pub mod rng;
 pub mod stages;
+pub mod storage;
 
+/// The rules of the game that everything else builds on: the game state, score, difficulty,
+/// movement and the seedable rng. Drawing the game is left to `SharedRenderPlugin`, so the game
+/// can also run without a window, e.g. in tests.
 pub struct SharedPlugin;
 
 impl Plugin for SharedPlugin {
     fn build(&self, app: &mut App) {
         info!("Building shared plugin...");
 
-        // Add plugin to render shapes with bevy_prototype_lyon.
-        app.add_plugins(ShapePlugin);
-
-        app.init_resource::<render::FontHandles>();
+        // fonts and sprites stay unloaded placeholders when there is no asset server
+        app.init_resource::<render::FontHandles>()
+            .init_resource::<atlas::SpriteAtlas>();
 
         // Configure ordering of custom system sets.
         app.configure_sets(
@@ -54,16 +58,16 @@ impl Plugin for SharedPlugin {
         );
 
         /* Startup systems
-        - Spawn the camera
         - Create the arena
         */
-        app.add_systems(Startup, (initialize_game, arena::initialize_arena));
+        app.add_systems(Startup, arena::initialize_arena);
 
         /* Resources
         - Seedable rng
         - Score counter
         - Difficulty manager
         - Overall state of game
+        - How the arena is fit to the window
         */
         app.init_resource::<rng::GameRng>()
             .insert_resource(game::Difficulty {
@@ -73,20 +77,26 @@ impl Plugin for SharedPlugin {
             .insert_resource(game::Score {
                 count: 0,
                 timer: Timer::from_seconds(1.0, TimerMode::Repeating),
+                partial_points: 0.0,
             })
             .insert_resource(game::GameState {
-                cur_state: game::GameStates::Running,
-                prev_state: game::GameStates::Running,
+                cur_state: game::GameStates::Menu,
+                prev_state: game::GameStates::Menu,
             });
         /* Events
         - Game state changes
         - Generic event to a transform reaching its destination
+        - Animations finishing or reaching a frame with a sound
         */
         app.add_event::<game::GameOver>()
+            .add_event::<game::GoalReached>()
             .add_event::<game::GamePaused>()
             .add_event::<game::GameUnpaused>()
             .add_event::<game::GameRestarted>()
-            .add_event::<movement::DestinationReached>();
+            .add_event::<game::QuitToMenu>()
+            .add_event::<movement::DestinationReached>()
+            .add_event::<animation::AnimationFinished>()
+            .add_event::<animation::AnimationSound>();
         // Timer systems.
         app.add_systems(
             Update,
@@ -116,16 +126,55 @@ impl Plugin for SharedPlugin {
             Update,
             (
                 movement::follow_system,
-                render::adjust_to_render_layer,
                 animation::animation_system,
                 game::finalize_score,
-                render::scale_camera_to_screen_size,
                 rng::reset_rng_on_restart,
                 game::reset_difficulty_on_restart,
                 game::reset_game_state_on_restart,
                 game::reset_score_on_restart,
                 game::pause_game,
                 game::unpause_game,
+                game::quit_to_menu,
+            )
+                .in_set(stages::PrepareRenderSet),
+        );
+    }
+}
+
+/// Everything that needs a window, the renderer or the asset server: the camera, shapes, packing
+/// the sprite atlas and loading animations. Added right after `SharedPlugin`, before the plugins
+/// that load animations.
+pub struct SharedRenderPlugin;
+
+impl Plugin for SharedRenderPlugin {
+    fn build(&self, app: &mut App) {
+        debug!("Building shared render plugin...");
+
+        // Add plugin to render shapes with bevy_prototype_lyon.
+        app.add_plugins(ShapePlugin);
+
+        app.init_asset::<animation::AnimationSet>()
+            .init_asset_loader::<animation::AnimationSetLoader>();
+
+        /* Startup systems
+        - Spawn the camera
+        - Cover everything outside the arena
+        */
+        app.add_systems(Startup, initialize_game).add_systems(
+            Startup,
+            render::spawn_letterbox_bars.after(arena::initialize_arena),
+        );
+
+        app.add_systems(
+            Update,
+            (game::pause_on_focus_lost).in_set(stages::HandleEventsSet),
+        )
+        .add_systems(
+            Update,
+            (
+                render::adjust_to_render_layer,
+                atlas::pack_sprite_atlas_system,
+                render::fit_camera_to_window,
                 movement::flip_sprite_system,
             )
                 .in_set(stages::PrepareRenderSet),
@@ -137,5 +186,5 @@ impl Plugin for SharedPlugin {
 pub struct MainCamera;
 
 fn initialize_game(mut commands: Commands) {
-    commands.spawn((Camera2dBundle::default(), MainCamera));
+    commands.spawn((Camera2dBundle::default(), IsDefaultUiCamera, MainCamera));
 }
@@ -42,7 +42,7 @@ pub fn movement_system(
     game_state: Res<GameState>,
     mut query: Query<(&Velocity, &mut Transform)>,
 ) {
-    if let GameStates::Paused = game_state.cur_state {
+    if let GameStates::Paused | GameStates::Menu = game_state.cur_state {
         return;
     }
 
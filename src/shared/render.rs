@@ -1,57 +1,149 @@
-use bevy::{prelude::*, window::WindowResized};
+use bevy::{prelude::*, render::camera::ScalingMode, window::PrimaryWindow};
+
+use serde::{Deserialize, Serialize};
 
 use super::arena::Arena;
 use super::MainCamera;
+use crate::settings::Settings;
 
 #[derive(Debug, Clone, Resource)]
 pub(crate) struct FontHandles {
     pub main_font: Handle<Font>,
+    /// Draws the characters the main font doesn't have, see `localization`.
+    pub fallback_font: Handle<Font>,
 }
 
 impl FromWorld for FontHandles {
     fn from_world(world: &mut World) -> Self {
-        let asset_server = world.get_resource::<AssetServer>().unwrap();
+        // without an asset server, e.g. in tests, text is left without a font
+        let Some(asset_server) = world.get_resource::<AssetServer>() else {
+            return Self {
+                main_font: Handle::default(),
+                fallback_font: Handle::default(),
+            };
+        };
         debug!("Loading fonts...");
         Self {
             main_font: asset_server.load("fonts/Chonkly.ttf"),
+            fallback_font: asset_server.load("fonts/DejaVuSans-Bold.ttf"),
         }
     }
 }
 
-pub(super) fn scale_camera_to_screen_size(
+/// How the arena is fit into the window. The arena always keeps its aspect ratio and the rest of
+/// the window is covered by letterbox bars.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub enum CameraScalingModes {
+    /// Scale the arena to fill as much of the window as possible.
+    #[default]
+    Letterbox,
+    /// Only scale the arena by whole numbers so every pixel of the nearest-filtered sprites stays
+    /// the same size.
+    PixelPerfect,
+}
+
+/// The scaling mode picked by the player.
+#[derive(Debug, Default, Copy, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CameraScaling {
+    pub mode: CameraScalingModes,
+}
+
+/// Far enough past the edges of the arena to cover any window.
+const LETTERBOX_BAR_SIZE: f32 = 10000.0;
+/// In front of everything in the arena, but still in front of the camera.
+const LETTERBOX_BAR_Z: f32 = 900.0;
+
+/// Covers everything outside of the arena. The bars stay at the edges of the arena in the world,
+/// so they keep covering it while the camera shakes or zooms.
+pub(super) fn spawn_letterbox_bars(mut commands: Commands, arena: Res<Arena>) {
+    let bar_distance = Vec2::new(
+        (arena.width + LETTERBOX_BAR_SIZE) / 2.0,
+        (arena.height + LETTERBOX_BAR_SIZE) / 2.0,
+    );
+
+    for bar_position in [
+        Vec2::new(0.0, bar_distance.y),
+        Vec2::new(0.0, -bar_distance.y),
+        Vec2::new(bar_distance.x, 0.0),
+        Vec2::new(-bar_distance.x, 0.0),
+    ] {
+        commands.spawn(SpriteBundle {
+            sprite: Sprite {
+                color: Color::BLACK,
+                custom_size: Some(Vec2::splat(LETTERBOX_BAR_SIZE)),
+                ..Default::default()
+            },
+            transform: Transform::from_translation(bar_position.extend(LETTERBOX_BAR_Z)),
+            ..Default::default()
+        });
+    }
+}
+
+/// Fits the arena into the window whenever the window or the scaling mode changes, including when
+/// the window is first created.
+pub(super) fn fit_camera_to_window(
     arena: Res<Arena>,
-    mut resize_event_reader: EventReader<WindowResized>,
-    mut query: Query<(&Camera, &MainCamera, &mut Transform)>,
+    settings: Res<Settings>,
+    window_query: Query<Ref<Window>, With<PrimaryWindow>>,
+    mut camera_query: Query<&mut OrthographicProjection, With<MainCamera>>,
 ) {
-    if let Some(resize_event) = resize_event_reader.read().next() {
-        let scale = Vec3::new(
-            arena.width / resize_event.width,
-            arena.height / resize_event.height,
-            1.0,
-        );
-
-        let (_, _, mut camera_transform) = query
-            .get_single_mut()
-            .expect("Could not find camera to resize.");
-
-        camera_transform.scale = scale;
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+
+    if !window.is_changed() && !settings.is_changed() && !arena.is_changed() {
+        return;
+    }
+
+    let scaling_mode = match settings.camera_scaling.mode {
+        CameraScalingModes::Letterbox => ScalingMode::AutoMin {
+            min_width: arena.width,
+            min_height: arena.height,
+        },
+        CameraScalingModes::PixelPerfect => {
+            let pixels_per_unit = (window.physical_width() as f32 / arena.width)
+                .min(window.physical_height() as f32 / arena.height)
+                .floor()
+                .max(1.0);
+
+            // the projection works in logical pixels
+            ScalingMode::WindowSize(pixels_per_unit / window.scale_factor())
+        }
+    };
+
+    debug!("Fitting camera to window with {:?}", scaling_mode);
+
+    for mut projection in camera_query.iter_mut() {
+        projection.scaling_mode = scaling_mode;
     }
 }
 
 #[derive(Debug, Component)]
 pub enum RenderLayer {
+    Effects,
     Player,
     Objects,
     Background,
 }
 
+/// Orders entities that share a render layer, higher orders are drawn on top.
+#[derive(Debug, Component)]
+pub struct LayerOrder(pub u8);
+
 /// TODO: Use Vec2's everywhere so this can just be set once when added
-pub(super) fn adjust_to_render_layer(mut query: Query<(&RenderLayer, &mut Transform)>) {
-    for (render_layer, mut transform) in query.iter_mut() {
-        transform.translation.z = match render_layer {
+pub(super) fn adjust_to_render_layer(
+    mut query: Query<(&RenderLayer, Option<&LayerOrder>, &mut Transform)>,
+) {
+    for (render_layer, layer_order, mut transform) in query.iter_mut() {
+        let layer_z = match render_layer {
+            RenderLayer::Effects => 4.0,
             RenderLayer::Player => 3.0,
             RenderLayer::Objects => 2.0,
             RenderLayer::Background => 1.0,
-        }
+        };
+
+        // stay below the next layer up
+        transform.translation.z = layer_z + layer_order.map_or(0.0, |order| order.0 as f32 / 100.0);
     }
 }
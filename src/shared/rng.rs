@@ -1,28 +1,73 @@
+/**
+The seedable rng that decides everything random about a run, like which boats spawn and where
+their hooks hang. Two runs with the same seed get the same boats.
+
+Seeds are shared as short codes. A run can be started from a code with `--seed <code>` on native
+builds or `?seed=<code>` in the page's url on wasm builds. `--daily` or `?daily` starts in daily
+seed mode, where everyone playing on the same day gets the same seed.
+*/
 use bevy::prelude::*;
+use bevy::utils::SystemTime;
 
 use rand::prelude::*;
 use rand_chacha::ChaCha8Rng;
 
 use super::game::GameRestarted;
 
+/// Where the seed of each run comes from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SeedModes {
+    /// A new seed every run.
+    Random,
+    /// The same seed every run.
+    Fixed(u32),
+    /// A seed that changes every day, the same for everyone.
+    Daily,
+}
+
+impl SeedModes {
+    fn next_seed(&self) -> u32 {
+        match *self {
+            SeedModes::Random => thread_rng().gen(),
+            SeedModes::Fixed(seed) => seed,
+            SeedModes::Daily => daily_seed(current_day()),
+        }
+    }
+}
+
 #[derive(Resource)]
 pub struct GameRng {
     pub rng: ChaCha8Rng,
-    pub seed: <ChaCha8Rng as SeedableRng>::Seed,
+    pub seed: u32,
+    pub mode: SeedModes,
 }
 
-impl Default for GameRng {
-    fn default() -> Self {
-        let mut seed: <ChaCha8Rng as SeedableRng>::Seed = Default::default();
-        rand::thread_rng().fill(&mut seed);
-
-        debug!("Random seed used: {:?}", seed);
+impl GameRng {
+    pub(crate) fn from_mode(mode: SeedModes) -> Self {
+        let seed = mode.next_seed();
 
         GameRng {
-            rng: ChaCha8Rng::from_seed(seed),
+            rng: ChaCha8Rng::seed_from_u64(seed as u64),
             seed,
+            mode,
         }
     }
+
+    /// Switches to a different seed mode. The new mode is used from the next run on.
+    pub fn set_mode(&mut self, mode: SeedModes) {
+        debug!("Changing seed mode to {:?}", mode);
+        self.mode = mode;
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        let rng = GameRng::from_mode(launch_seed_mode());
+
+        debug!("Seed used: {} ({:?})", seed_code(rng.seed), rng.mode);
+
+        rng
+    }
 }
 
 pub(super) fn reset_rng_on_restart(
@@ -30,12 +75,160 @@ pub(super) fn reset_rng_on_restart(
     mut restart_reader: EventReader<GameRestarted>,
 ) {
     if restart_reader.read().next().is_some() {
-        let mut seed: <ChaCha8Rng as SeedableRng>::Seed = Default::default();
-        thread_rng().fill(&mut seed);
+        *rng = GameRng::from_mode(rng.mode);
+
+        debug!("Seed used after restart: {}", seed_code(rng.seed));
+    }
+}
+
+/// Crockford's base 32, which leaves out letters that are easy to mix up.
+const SEED_CODE_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const SEED_CODE_LENGTH: usize = 7;
+
+/// Turns a seed into a short code that is easy to read out and type.
+pub fn seed_code(seed: u32) -> String {
+    (0..SEED_CODE_LENGTH)
+        .rev()
+        .map(|digit| SEED_CODE_ALPHABET[((seed >> (digit * 5)) & 0x1f) as usize] as char)
+        .collect()
+}
 
-        debug!("Generated random seed after restart: {:?}", seed);
+/// Reads a seed back from its short code. Letters can be upper or lower case, dashes and spaces
+/// are ignored, and letters that look like digits are read as those digits.
+pub fn parse_seed_code(code: &str) -> Option<u32> {
+    let mut seed: u64 = 0;
+    let mut num_digits = 0;
+
+    for character in code.chars().filter(|c| *c != '-' && !c.is_whitespace()) {
+        let character = match character.to_ascii_uppercase() {
+            'O' => '0',
+            'I' | 'L' => '1',
+            other => other,
+        };
+
+        let value = SEED_CODE_ALPHABET
+            .iter()
+            .position(|&c| c as char == character)?;
+
+        seed = (seed << 5) | value as u64;
+        num_digits += 1;
+    }
+
+    if num_digits == 0 || num_digits > SEED_CODE_LENGTH {
+        return None;
+    }
+
+    u32::try_from(seed).ok()
+}
+
+/// Days since the unix epoch in UTC, so every player's day changes at the same time.
+pub fn current_day() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|since_epoch| since_epoch.as_secs() / (60 * 60 * 24))
+        .unwrap_or(0)
+}
+
+pub fn daily_seed(day: u64) -> u32 {
+    // scramble the day so that seeds of following days don't look alike
+    ChaCha8Rng::seed_from_u64(day).gen()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn launch_seed_mode() -> SeedModes {
+    let mut args = std::env::args().skip(1);
+    let mut mode = SeedModes::Random;
+
+    while let Some(arg) = args.next() {
+        if arg == "--daily" {
+            mode = SeedModes::Daily;
+        } else if let Some(code) = arg
+            .strip_prefix("--seed=")
+            .map(str::to_string)
+            .or_else(|| (arg == "--seed").then(|| args.next()).flatten())
+        {
+            match parse_seed_code(&code) {
+                Some(seed) => mode = SeedModes::Fixed(seed),
+                None => error!("{:?} is not a valid seed code", code),
+            }
+        }
+    }
+
+    mode
+}
+
+#[cfg(target_arch = "wasm32")]
+fn launch_seed_mode() -> SeedModes {
+    let Some(params) = web_sys::window()
+        .and_then(|window| window.location().search().ok())
+        .and_then(|search| web_sys::UrlSearchParams::new_with_str(&search).ok())
+    else {
+        return SeedModes::Random;
+    };
+
+    if let Some(code) = params.get("seed") {
+        match parse_seed_code(&code) {
+            Some(seed) => return SeedModes::Fixed(seed),
+            None => error!("{:?} is not a valid seed code", code),
+        }
+    }
+
+    if params.has("daily") {
+        SeedModes::Daily
+    } else {
+        SeedModes::Random
+    }
+}
+
+/// Puts the seed in the page's url so that the run can be shared by copying the url.
+#[cfg(target_arch = "wasm32")]
+pub fn share_seed(seed: u32) {
+    let url = format!("?seed={}", seed_code(seed));
+
+    if let Some(Ok(history)) = web_sys::window().map(|window| window.history()) {
+        if history
+            .replace_state_with_url(&wasm_bindgen::JsValue::NULL, "", Some(&url))
+            .is_err()
+        {
+            debug!("Could not put the seed in the url");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seed_codes_round_trip() {
+        for seed in [0, 1, 31, 32, 123_456_789, u32::MAX] {
+            let code = seed_code(seed);
+
+            assert_eq!(code.len(), SEED_CODE_LENGTH);
+            assert_eq!(parse_seed_code(&code), Some(seed));
+        }
+    }
+
+    #[test]
+    fn seed_codes_are_forgiving_to_type() {
+        let seed = parse_seed_code("3KF9ZQ2").unwrap();
+
+        assert_eq!(parse_seed_code("3kf9-zq2"), Some(seed));
+        assert_eq!(parse_seed_code("10"), parse_seed_code("lO"));
+    }
+
+    #[test]
+    fn invalid_seed_codes_are_rejected() {
+        assert_eq!(parse_seed_code(""), None);
+        assert_eq!(parse_seed_code("3KF9ZQ2X"), None);
+        assert_eq!(parse_seed_code("U"), None);
+        // larger than a u32
+        assert_eq!(parse_seed_code("ZZZZZZZ"), None);
+    }
 
-        rng.rng = ChaCha8Rng::from_seed(seed);
-        rng.seed = seed;
+    #[test]
+    fn daily_seeds_only_depend_on_the_day() {
+        assert_eq!(daily_seed(19_000), daily_seed(19_000));
+        assert_ne!(daily_seed(19_000), daily_seed(19_001));
     }
 }
@@ -0,0 +1,89 @@
+/**
+Persists game data between sessions. Native builds store each key as a json file in the
+working directory and wasm builds store each key in the browser's local storage. Tests keep
+everything in memory, so they start from nothing and never touch the player's files. Each test
+thread has its own storage, which is why the test harness runs every system on the thread of its
+test.
+*/
+use bevy::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+
+#[cfg(all(not(target_arch = "wasm32"), not(test)))]
+pub fn load(key: &str) -> Option<String> {
+    let filename = key.to_owned() + ".json";
+    debug!("Loading {:?} from file {:?}", key, filename);
+
+    std::fs::read_to_string(filename).ok()
+}
+
+#[cfg(all(not(target_arch = "wasm32"), not(test)))]
+pub fn save(key: &str, contents: &str) {
+    let filename = key.to_owned() + ".json";
+    debug!("Writing {:?} to file {:?}", contents, filename);
+
+    std::fs::write(filename, contents).unwrap();
+}
+
+#[cfg(test)]
+thread_local! {
+    static TEST_STORAGE: std::cell::RefCell<std::collections::HashMap<String, String>> =
+        Default::default();
+}
+
+#[cfg(test)]
+pub fn load(key: &str) -> Option<String> {
+    TEST_STORAGE.with(|storage| storage.borrow().get(key).cloned())
+}
+
+#[cfg(test)]
+pub fn save(key: &str, contents: &str) {
+    TEST_STORAGE.with(|storage| {
+        storage
+            .borrow_mut()
+            .insert(key.to_owned(), contents.to_owned())
+    });
+}
+
+#[cfg(all(target_arch = "wasm32", not(test)))]
+fn local_storage() -> web_sys::Storage {
+    let window = web_sys::window().unwrap();
+
+    if let Ok(Some(local_storage)) = window.local_storage() {
+        local_storage
+    } else {
+        panic!("Could not get local storage")
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", not(test)))]
+pub fn load(key: &str) -> Option<String> {
+    debug!("Loading {:?} from local storage", key);
+    local_storage().get_item(key).ok().flatten()
+}
+
+#[cfg(all(target_arch = "wasm32", not(test)))]
+pub fn save(key: &str, contents: &str) {
+    if local_storage().set_item(key, contents).is_ok() {
+        debug!("Updated {:?} in local storage to {:?}", key, contents);
+    } else {
+        panic!("Could not save in local storage")
+    }
+}
+
+/// Loads and deserializes the value stored under the key, if there is a valid one.
+pub fn load_json<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let contents = load(key)?;
+
+    match serde_json::from_str::<T>(&contents) {
+        Ok(value) => Some(value),
+        Err(err) => {
+            debug!("Could not parse stored value for {:?}: {:?}", key, err);
+            None
+        }
+    }
+}
+
+pub fn save_json<T: Serialize>(key: &str, value: &T) {
+    let serialized = serde_json::to_string_pretty(value).unwrap();
+    save(key, &serialized);
+}
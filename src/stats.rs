@@ -0,0 +1,67 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::player::events::PlayerAte;
+use crate::shared::{
+    game::{GameOver, Score},
+    stages, storage,
+};
+
+const STATS_KEY: &str = "stats";
+
+/// Totals that are tracked across every run and saved between sessions.
+#[derive(Debug, Default, Deserialize, Serialize, Resource)]
+pub struct LifetimeStats {
+    pub games_played: u32,
+    pub best_score: u32,
+    pub worms_eaten: u32,
+}
+
+impl LifetimeStats {
+    pub fn load() -> Self {
+        storage::load_json(STATS_KEY).unwrap_or_else(|| {
+            debug!("No saved stats found, starting new ones");
+            Self::default()
+        })
+    }
+
+    fn save(&self) {
+        storage::save_json(STATS_KEY, self);
+    }
+}
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        debug!("Building stats plugin...");
+        app.insert_resource(LifetimeStats::load()).add_systems(
+            Update,
+            (count_worms_eaten_system, record_game_over_system).in_set(stages::HandleEventsSet),
+        );
+    }
+}
+
+fn count_worms_eaten_system(
+    mut player_ate_reader: EventReader<PlayerAte>,
+    mut stats: ResMut<LifetimeStats>,
+) {
+    for _ in player_ate_reader.read() {
+        stats.worms_eaten += 1;
+    }
+}
+
+/// Saves the stats once per run so that eating worms doesn't write to storage every time.
+fn record_game_over_system(
+    score: Res<Score>,
+    mut game_over_reader: EventReader<GameOver>,
+    mut stats: ResMut<LifetimeStats>,
+) {
+    if game_over_reader.read().next().is_some() {
+        stats.games_played += 1;
+        stats.best_score = stats.best_score.max(score.count);
+
+        debug!("Saving lifetime stats: {:?}", *stats);
+        stats.save();
+    }
+}
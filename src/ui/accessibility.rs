@@ -0,0 +1,208 @@
+use bevy::prelude::*;
+
+use super::settings::{on_off, SettingsScreen};
+use super::theme::{TextRoles, UiTheme};
+use super::widgets;
+use crate::localization::Localization;
+use crate::settings::Settings;
+use crate::shared::{
+    render::{CameraScalingModes, FontHandles},
+    stages,
+};
+
+/// Plugin for the accessibility toggles. The toggles are shown on the settings screen.
+pub struct AccessibilityPanelPlugin;
+
+impl Plugin for AccessibilityPanelPlugin {
+    fn build(&self, app: &mut App) {
+        debug!("Building AccessibilityPanelPlugin...");
+        app.add_systems(Startup, spawn_accessibility_panel)
+            .add_systems(
+                Update,
+                (accessibility_toggle_system).in_set(stages::HandleEventsSet),
+            )
+            .add_systems(
+                Update,
+                (show_accessibility_panel, update_toggle_text).in_set(stages::PrepareRenderSet),
+            );
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+enum AccessibilityOptions {
+    ScreenShake,
+    HitStop,
+    CameraZoom,
+    PixelPerfect,
+    ColorPalette,
+    GameSpeed,
+}
+
+impl AccessibilityOptions {
+    fn text_key(&self) -> &'static str {
+        match self {
+            AccessibilityOptions::ScreenShake => "accessibility-screen-shake",
+            AccessibilityOptions::HitStop => "accessibility-hit-stop",
+            AccessibilityOptions::CameraZoom => "accessibility-camera-zoom",
+            AccessibilityOptions::PixelPerfect => "accessibility-pixel-perfect",
+            AccessibilityOptions::ColorPalette => "accessibility-colors",
+            AccessibilityOptions::GameSpeed => "accessibility-game-speed",
+        }
+    }
+
+    /// Flips the option, or moves on to its next value.
+    fn toggle(&self, settings: &mut Settings) {
+        let accessibility = &mut settings.accessibility;
+        match self {
+            AccessibilityOptions::ScreenShake => {
+                accessibility.screen_shake = !accessibility.screen_shake
+            }
+            AccessibilityOptions::HitStop => accessibility.hit_stop = !accessibility.hit_stop,
+            AccessibilityOptions::CameraZoom => {
+                accessibility.camera_zoom = !accessibility.camera_zoom
+            }
+            AccessibilityOptions::PixelPerfect => {
+                let scaling = &mut settings.camera_scaling;
+                scaling.mode = match scaling.mode {
+                    CameraScalingModes::Letterbox => CameraScalingModes::PixelPerfect,
+                    CameraScalingModes::PixelPerfect => CameraScalingModes::Letterbox,
+                }
+            }
+            AccessibilityOptions::ColorPalette => {
+                accessibility.palette = accessibility.palette.next()
+            }
+            AccessibilityOptions::GameSpeed => {
+                accessibility.game_speed = accessibility.game_speed.next()
+            }
+        }
+    }
+
+    fn value(&self, settings: &Settings, localization: &Localization) -> String {
+        let accessibility = &settings.accessibility;
+        let value = match self {
+            AccessibilityOptions::ScreenShake => on_off(accessibility.screen_shake, localization),
+            AccessibilityOptions::HitStop => on_off(accessibility.hit_stop, localization),
+            AccessibilityOptions::CameraZoom => on_off(accessibility.camera_zoom, localization),
+            AccessibilityOptions::PixelPerfect => on_off(
+                settings.camera_scaling.mode == CameraScalingModes::PixelPerfect,
+                localization,
+            ),
+            AccessibilityOptions::ColorPalette => {
+                localization.get(accessibility.palette.text_key())
+            }
+            AccessibilityOptions::GameSpeed => {
+                let game_speed = accessibility.game_speed;
+                if game_speed.is_assisted() {
+                    return localization.format(
+                        "accessibility-game-speed-assisted",
+                        &[("speed", &localization.get(game_speed.text_key()))],
+                    );
+                }
+                localization.get(game_speed.text_key())
+            }
+        };
+        value.to_string()
+    }
+}
+
+#[derive(Component)]
+struct AccessibilityPanelRootNode;
+
+#[derive(Component)]
+struct AccessibilityToggle {
+    option: AccessibilityOptions,
+}
+
+fn spawn_accessibility_panel(mut commands: Commands, fonts: Res<FontHandles>, theme: Res<UiTheme>) {
+    let mut panel = widgets::panel(
+        Style {
+            position_type: PositionType::Absolute,
+            right: Val::Px(20.0),
+            bottom: Val::Px(20.0),
+            ..Default::default()
+        },
+        &theme,
+    );
+    panel.0.visibility = Visibility::Hidden;
+
+    commands
+        .spawn((panel, AccessibilityPanelRootNode))
+        .with_children(|builder| {
+            for option in [
+                AccessibilityOptions::ScreenShake,
+                AccessibilityOptions::HitStop,
+                AccessibilityOptions::CameraZoom,
+                AccessibilityOptions::PixelPerfect,
+                AccessibilityOptions::ColorPalette,
+                AccessibilityOptions::GameSpeed,
+            ] {
+                builder
+                    .spawn((
+                        widgets::button(Style::default()),
+                        AccessibilityToggle { option },
+                    ))
+                    .with_children(|builder| {
+                        builder.spawn(widgets::label("", TextRoles::Body, &fonts, &theme));
+                    });
+            }
+        });
+}
+
+fn show_accessibility_panel(
+    settings_screen: Res<SettingsScreen>,
+    mut root_query: Query<&mut Visibility, With<AccessibilityPanelRootNode>>,
+) {
+    if !settings_screen.is_changed() {
+        return;
+    }
+
+    for mut visibility in root_query.iter_mut() {
+        *visibility = if settings_screen.is_open {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+fn accessibility_toggle_system(
+    mut settings: ResMut<Settings>,
+    interaction_query: Query<(&Interaction, &AccessibilityToggle), Changed<Interaction>>,
+) {
+    for (interaction, toggle) in interaction_query.iter() {
+        if let Interaction::Pressed = *interaction {
+            toggle.option.toggle(&mut settings);
+
+            debug!(
+                "Saving accessibility settings: {:?}",
+                settings.accessibility
+            );
+            settings.save();
+        }
+    }
+}
+
+fn update_toggle_text(
+    settings: Res<Settings>,
+    localization: Res<Localization>,
+    toggle_query: Query<(&AccessibilityToggle, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    if !settings.is_changed() && !localization.is_changed() {
+        return;
+    }
+
+    for (toggle, children) in toggle_query.iter() {
+        for &child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                text.sections[0].value = localization.format(
+                    "accessibility-option",
+                    &[
+                        ("option", &localization.get(toggle.option.text_key())),
+                        ("value", &toggle.option.value(&settings, &localization)),
+                    ],
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use bevy::prelude::*;
+
+use super::theme::{TextRoles, UiTheme};
+use super::widgets;
+use crate::daily::{format_day, DailyHistory};
+use crate::localization::Localization;
+use crate::modifiers::modifier_definition;
+use crate::shared::{
+    game::{GameRestarted, GameState, GameStates},
+    render::FontHandles,
+    stages,
+};
+
+/// How many of the most recent days are listed in the history.
+const DAYS_SHOWN: usize = 10;
+
+/// Plugin for the panel with the results of past daily challenges. The panel can be opened with [H]
+/// between runs.
+pub struct DailyHistoryPanelPlugin;
+
+impl Plugin for DailyHistoryPanelPlugin {
+    fn build(&self, app: &mut App) {
+        debug!("Building DailyHistoryPanelPlugin...");
+        app.add_systems(Startup, spawn_daily_history_panel)
+            .add_systems(
+                Update,
+                (toggle_daily_history_system).in_set(stages::HandleEventsSet),
+            )
+            .add_systems(
+                Update,
+                (update_daily_history_display).in_set(stages::PrepareRenderSet),
+            );
+    }
+}
+
+#[derive(Component)]
+struct DailyHistoryRootNode {
+    is_open: bool,
+}
+
+#[derive(Component)]
+struct DailyHistoryText;
+
+fn spawn_daily_history_panel(
+    mut commands: Commands,
+    fonts: Res<FontHandles>,
+    theme: Res<UiTheme>,
+    localization: Res<Localization>,
+) {
+    let mut panel = widgets::panel(
+        Style {
+            position_type: PositionType::Absolute,
+            right: Val::Px(20.0),
+            top: Val::Px(20.0),
+            ..Default::default()
+        },
+        &theme,
+    );
+    panel.0.visibility = Visibility::Hidden;
+
+    commands
+        .spawn((panel, DailyHistoryRootNode { is_open: false }))
+        .with_children(|builder| {
+            builder.spawn(widgets::localized_label(
+                "daily-title",
+                TextRoles::Item,
+                &fonts,
+                &theme,
+                &localization,
+            ));
+            builder.spawn((
+                widgets::label("", TextRoles::Body, &fonts, &theme),
+                DailyHistoryText,
+            ));
+            builder.spawn(widgets::localized_label(
+                "daily-close",
+                TextRoles::Body,
+                &fonts,
+                &theme,
+                &localization,
+            ));
+        });
+}
+
+/// Opens and closes the history. The history can only be opened between runs.
+fn toggle_daily_history_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    game_state: Res<GameState>,
+    mut restart_reader: EventReader<GameRestarted>,
+    mut root_query: Query<&mut DailyHistoryRootNode>,
+) {
+    if restart_reader.read().next().is_some() {
+        for mut root_node in root_query.iter_mut() {
+            root_node.is_open = false;
+        }
+        return;
+    }
+
+    let (GameStates::GameOver | GameStates::Menu) = game_state.cur_state else {
+        return;
+    };
+
+    if keyboard_input.just_pressed(KeyCode::KeyH) {
+        for mut root_node in root_query.iter_mut() {
+            root_node.is_open = !root_node.is_open;
+        }
+    }
+}
+
+fn update_daily_history_display(
+    history: Res<DailyHistory>,
+    localization: Res<Localization>,
+    mut root_query: Query<(&mut Visibility, Ref<DailyHistoryRootNode>)>,
+    mut text_query: Query<&mut Text, With<DailyHistoryText>>,
+) {
+    for (mut visibility, root_node) in root_query.iter_mut() {
+        if !root_node.is_changed() && !localization.is_changed() {
+            continue;
+        }
+
+        *visibility = if root_node.is_open {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+
+        if !root_node.is_open {
+            continue;
+        }
+
+        let lines: Vec<String> = history
+            .results
+            .iter()
+            .rev()
+            .take(DAYS_SHOWN)
+            .map(|result| {
+                let modifier = result
+                    .modifier
+                    .map(|modifier| {
+                        format!(
+                            "  {}",
+                            localization.get(modifier_definition(modifier).name_key)
+                        )
+                    })
+                    .unwrap_or_default();
+
+                format!("{}  {}{}", format_day(result.day), result.score, modifier)
+            })
+            .collect();
+
+        for mut text in text_query.iter_mut() {
+            text.sections[0].value = if lines.is_empty() {
+                localization.get("daily-none").to_string()
+            } else {
+                lines.join("\n")
+            };
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use bevy::prelude::*;
+
+use crate::accessibility::Palette;
+use crate::combo::{Combo, PointsScored};
+use crate::localization::Localization;
+use crate::shared::{
+    game::{GameRestarted, GameState, GameStates},
+    movement::Velocity,
+    render::{FontHandles, RenderLayer},
+};
+use crate::ui::{
+    theme::{TextRoles, UiTheme},
+    widgets,
+};
+
+const POPUP_LIFETIME: f32 = 1.0;
+const POPUP_RISE_SPEED: f32 = 40.0;
+
+#[derive(Component)]
+pub(super) struct ComboText;
+
+/// Floating "+N" text that rises from where points were scored and fades away.
+#[derive(Component)]
+pub(super) struct PointsPopup {
+    timer: Timer,
+}
+
+/// Startup system that is responsible for adding the combo multiplier display to UI.
+pub fn setup_combo_display(
+    mut commands: Commands,
+    fonts: Res<FontHandles>,
+    theme: Res<UiTheme>,
+    palette: Res<Palette>,
+) {
+    commands.spawn((
+        widgets::colored_label("", TextRoles::Heading, palette.highlight, &fonts, &theme)
+            .with_style(Style {
+                margin: UiRect {
+                    left: Val::Percent(5.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+        ComboText,
+    ));
+}
+
+pub(super) fn update_combo_text(
+    combo: Res<Combo>,
+    palette: Res<Palette>,
+    localization: Res<Localization>,
+    mut query: Query<&mut Text, With<ComboText>>,
+) {
+    if !combo.is_changed() && !palette.is_changed() && !localization.is_changed() {
+        return;
+    }
+
+    let combo_text = if combo.multiplier() > 1 {
+        localization.format("hud-combo", &[("multiplier", &combo.multiplier())])
+    } else {
+        "".to_string()
+    };
+
+    for mut text in query.iter_mut() {
+        text.sections[0].value = combo_text.clone();
+        text.sections[0].style.color = palette.highlight;
+    }
+}
+
+pub(super) fn spawn_points_popups(
+    mut commands: Commands,
+    fonts: Res<FontHandles>,
+    theme: Res<UiTheme>,
+    palette: Res<Palette>,
+    mut points_scored_reader: EventReader<PointsScored>,
+) {
+    for points_scored_event in points_scored_reader.read() {
+        if points_scored_event.points == 0 {
+            continue;
+        }
+
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    format!("+{}", points_scored_event.points),
+                    TextStyle {
+                        font: fonts.main_font.clone(),
+                        font_size: theme.font_size(TextRoles::Item),
+                        color: palette.highlight,
+                    },
+                ),
+                transform: Transform::from_translation(points_scored_event.position.extend(0.0)),
+                ..Default::default()
+            },
+            Velocity(Vec3::new(0.0, POPUP_RISE_SPEED, 0.0)),
+            PointsPopup {
+                timer: Timer::from_seconds(POPUP_LIFETIME, TimerMode::Once),
+            },
+            RenderLayer::Effects,
+        ));
+    }
+}
+
+/// Fades popups out over their lifetime. Popups freeze along with the rest of the game.
+pub(super) fn update_points_popups(
+    mut commands: Commands,
+    time: Res<Time>,
+    game_state: Res<GameState>,
+    mut query: Query<(Entity, &mut PointsPopup, &mut Text)>,
+) {
+    if let GameStates::Paused | GameStates::Menu = game_state.cur_state {
+        return;
+    }
+
+    for (entity, mut popup, mut text) in query.iter_mut() {
+        popup.timer.tick(time.delta());
+
+        if popup.timer.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        text.sections[0]
+            .style
+            .color
+            .set_a(1.0 - popup.timer.fraction());
+    }
+}
+
+pub(super) fn clear_points_popups_on_restart(
+    mut commands: Commands,
+    mut restart_reader: EventReader<GameRestarted>,
+    query: Query<Entity, With<PointsPopup>>,
+) {
+    if restart_reader.read().next().is_some() {
+        for entity in query.iter() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -1,8 +1,14 @@
 use bevy::prelude::*;
 
+mod combo;
+mod mode;
+mod mute;
 mod pause;
 mod score;
 
+use combo::ComboText;
+use mode::GameModeText;
+use mute::MuteButton;
 use pause::PauseButton;
 use score::ScoreText;
 
@@ -18,19 +24,31 @@ impl Plugin for GameHudPlugin {
             .add_systems(Startup, (compose_game_hud,))
             .add_systems(
                 Startup,
-                (score::setup_score_display, pause::setup_pause_button).before(compose_game_hud),
+                (
+                    score::setup_score_display,
+                    mode::setup_game_mode_display,
+                    combo::setup_combo_display,
+                    mute::setup_mute_button,
+                    pause::setup_pause_button,
+                )
+                    .before(compose_game_hud),
             )
             .add_systems(
                 Update,
-                (pause::pause_button_system,).in_set(stages::HandleEventsSet),
+                (pause::pause_button_system, mute::mute_button_system)
+                    .in_set(stages::HandleEventsSet),
             )
             .add_systems(
                 Update,
                 (
                     score::update_score_text,
-                    score::change_color_on_game_over,
-                    score::revert_color_on_restart,
-                    pause::reset_pause_button_on_restart,
+                    mode::update_game_mode_text,
+                    combo::update_combo_text,
+                    combo::spawn_points_popups,
+                    combo::update_points_popups,
+                    combo::clear_points_popups_on_restart,
+                    pause::update_pause_button_icon,
+                    mute::update_mute_button_text,
                 ),
             );
     }
@@ -43,16 +61,63 @@ struct GameHudRoot;
 fn compose_game_hud(
     mut commands: Commands,
     score_root_query: Query<Entity, With<ScoreText>>,
+    mode_root_query: Query<Entity, With<GameModeText>>,
+    combo_root_query: Query<Entity, With<ComboText>>,
+    mute_root_query: Query<Entity, With<MuteButton>>,
     pause_root_query: Query<Entity, With<PauseButton>>,
 ) {
     let score_text_root_node = score_root_query
         .get_single()
         .expect("Could not find score text root node to compose into game HUD");
 
+    let mode_text_root_node = mode_root_query
+        .get_single()
+        .expect("Could not find game mode text root node to compose into game HUD");
+
+    let combo_text_root_node = combo_root_query
+        .get_single()
+        .expect("Could not find combo text root node to compose into game HUD");
+
     let pause_root_node = pause_root_query
         .get_single()
         .expect("Could not find game over message root node to compose into gameover HUD");
 
+    let mute_root_node = mute_root_query
+        .get_single()
+        .expect("Could not find mute button root node to compose into game HUD");
+
+    // Keep the mode and combo texts right below the score
+    let texts_root_node = commands
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .push_children(&[
+            score_text_root_node,
+            mode_text_root_node,
+            combo_text_root_node,
+        ])
+        .id();
+
+    // Keep the buttons grouped together on the right side of the HUD
+    let buttons_root_node = commands
+        .spawn(NodeBundle {
+            style: Style {
+                align_items: AlignItems::FlexStart,
+                margin: UiRect {
+                    right: Val::Percent(5.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .push_children(&[mute_root_node, pause_root_node])
+        .id();
+
     commands
         .spawn((
             NodeBundle {
@@ -66,5 +131,5 @@ fn compose_game_hud(
             },
             GameHudRoot,
         ))
-        .push_children(&[score_text_root_node, pause_root_node]);
+        .push_children(&[texts_root_node, buttons_root_node]);
 }
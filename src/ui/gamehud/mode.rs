@@ -0,0 +1,51 @@
+use bevy::prelude::*;
+
+use crate::localization::Localization;
+use crate::modes::{CurrentGameMode, RunProgress};
+use crate::shared::render::FontHandles;
+use crate::ui::{
+    theme::{TextRoles, UiTheme},
+    widgets,
+};
+
+/// Extra HUD text of the game mode being played, e.g. the time left in survival mode.
+#[derive(Component)]
+pub(super) struct GameModeText;
+
+/// Startup system that is responsible for adding the game mode text to UI.
+pub fn setup_game_mode_display(
+    mut commands: Commands,
+    fonts: Res<FontHandles>,
+    theme: Res<UiTheme>,
+) {
+    commands.spawn((
+        widgets::label("", TextRoles::Item, &fonts, &theme).with_style(Style {
+            margin: UiRect {
+                left: Val::Percent(5.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        }),
+        GameModeText,
+    ));
+}
+
+pub(super) fn update_game_mode_text(
+    current_mode: Res<CurrentGameMode>,
+    progress: Res<RunProgress>,
+    localization: Res<Localization>,
+    mut query: Query<&mut Text, With<GameModeText>>,
+) {
+    if !current_mode.is_changed() && !progress.is_changed() && !localization.is_changed() {
+        return;
+    }
+
+    let hud_text = current_mode
+        .active()
+        .hud_text(&progress, &localization)
+        .unwrap_or_default();
+
+    for mut text in query.iter_mut() {
+        text.sections[0].value = hud_text.clone();
+    }
+}
@@ -0,0 +1,72 @@
+use bevy::prelude::*;
+
+use crate::localization::LocalizedText;
+use crate::settings::Settings;
+use crate::shared::render::FontHandles;
+use crate::ui::{
+    theme::{TextRoles, UiTheme},
+    widgets,
+};
+
+#[derive(Debug, Component)]
+pub(super) struct MuteButton;
+
+#[derive(Debug, Component)]
+pub(super) struct MuteButtonText;
+
+pub(super) fn setup_mute_button(
+    mut commands: Commands,
+    fonts: Res<FontHandles>,
+    theme: Res<UiTheme>,
+) {
+    commands
+        .spawn((
+            widgets::button(Style {
+                height: Val::Px(64.0),
+                padding: UiRect::horizontal(Val::Px(10.0)),
+                margin: UiRect::right(Val::Px(10.0)),
+                align_items: AlignItems::Center,
+                ..Default::default()
+            }),
+            MuteButton,
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                widgets::label("", TextRoles::Item, &fonts, &theme),
+                LocalizedText("hud-mute"),
+                MuteButtonText,
+            ));
+        });
+}
+
+pub(super) fn mute_button_system(
+    mut settings: ResMut<Settings>,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<MuteButton>)>,
+) {
+    for interaction in interaction_query.iter() {
+        if let Interaction::Pressed = *interaction {
+            settings.audio.muted = !settings.audio.muted;
+            debug!("Audio muted: {:?}", settings.audio.muted);
+            settings.save();
+        }
+    }
+}
+
+pub(super) fn update_mute_button_text(
+    settings: Res<Settings>,
+    mut text_query: Query<&mut LocalizedText, With<MuteButtonText>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    let key = if settings.audio.muted {
+        "hud-unmute"
+    } else {
+        "hud-mute"
+    };
+
+    for mut localized_text in text_query.iter_mut() {
+        localized_text.set_if_neq(LocalizedText(key));
+    }
+}
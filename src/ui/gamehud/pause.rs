@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use crate::shared::game::{GamePaused, GameRestarted, GameState, GameStates, GameUnpaused};
+use crate::shared::game::{GamePaused, GameState, GameStates, GameUnpaused};
 
 #[derive(Debug, Clone, Resource)]
 pub(super) struct PauseButtonMaterials {
@@ -9,9 +9,7 @@ pub(super) struct PauseButtonMaterials {
 }
 
 #[derive(Debug, Component)]
-pub(super) struct PauseButton {
-    is_paused: bool,
-}
+pub(super) struct PauseButton;
 
 impl FromWorld for PauseButtonMaterials {
     fn from_world(world: &mut World) -> Self {
@@ -34,56 +32,51 @@ pub(super) fn setup_pause_button(
             style: Style {
                 width: Val::Px(64.0),
                 height: Val::Px(64.0),
-                margin: UiRect {
-                    right: Val::Percent(5.0),
-                    ..Default::default()
-                },
                 ..Default::default()
             },
             image: UiImage::new(pause_button_materials.pause.clone()),
             ..Default::default()
         },
-        PauseButton { is_paused: false },
+        PauseButton,
     ));
 }
 
 pub(super) fn pause_button_system(
     game_state: Res<GameState>,
-    pause_button_materials: Res<PauseButtonMaterials>,
-    mut game_paused_events: ResMut<Events<GamePaused>>,
-    mut game_unpaused_events: ResMut<Events<GameUnpaused>>,
-    mut interaction_query: Query<
-        (&Interaction, &mut UiImage, &mut PauseButton),
-        Changed<Interaction>,
-    >,
+    mut game_paused_events: EventWriter<GamePaused>,
+    mut game_unpaused_events: EventWriter<GameUnpaused>,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<PauseButton>)>,
 ) {
-    if let GameStates::GameOver = game_state.cur_state {
-        return;
-    }
-
-    for (interaction, mut ui_image, mut pause_button) in interaction_query.iter_mut() {
+    for interaction in interaction_query.iter() {
         if let Interaction::Pressed = *interaction {
-            if pause_button.is_paused {
-                ui_image.texture = pause_button_materials.pause.clone();
-                game_unpaused_events.send(GameUnpaused);
-            } else {
-                ui_image.texture = pause_button_materials.play.clone();
-                game_paused_events.send(GamePaused);
+            match game_state.cur_state {
+                GameStates::Running => {
+                    game_paused_events.send(GamePaused);
+                }
+                GameStates::Paused => {
+                    game_unpaused_events.send(GameUnpaused);
+                }
+                GameStates::Menu | GameStates::GameOver => {}
             }
-
-            pause_button.is_paused = !pause_button.is_paused;
         }
     }
 }
-pub(super) fn reset_pause_button_on_restart(
-    mut restart_reader: EventReader<GameRestarted>,
+
+/// Shows the play icon while paused, however the game got paused.
+pub(super) fn update_pause_button_icon(
+    game_state: Res<GameState>,
     pause_button_materials: Res<PauseButtonMaterials>,
-    mut pause_button_query: Query<(&mut UiImage, &mut PauseButton)>,
+    mut pause_button_query: Query<&mut UiImage, With<PauseButton>>,
 ) {
-    if restart_reader.read().next().is_some() {
-        for (mut ui_image, mut pause_button) in pause_button_query.iter_mut() {
-            ui_image.texture = pause_button_materials.pause.clone();
-            pause_button.is_paused = false;
-        }
+    if !game_state.is_changed() {
+        return;
+    }
+
+    for mut ui_image in pause_button_query.iter_mut() {
+        ui_image.texture = if let GameStates::Paused = game_state.cur_state {
+            pause_button_materials.play.clone()
+        } else {
+            pause_button_materials.pause.clone()
+        };
     }
 }
@@ -1,76 +1,74 @@
 use bevy::prelude::*;
 
-use crate::leaderboard::LocalScores;
-use crate::shared::game::{GameOver, GameRestarted, Score};
+use crate::accessibility::Palette;
+use crate::daily::DailyChallenge;
+use crate::leaderboard::{LeaderboardId, Leaderboards};
+use crate::localization::Localization;
+use crate::modes::CurrentGameMode;
+use crate::shared::game::{GameState, GameStates, Score};
 use crate::shared::render::FontHandles;
+use crate::ui::{
+    theme::{TextRoles, UiTheme},
+    widgets,
+};
 
 #[derive(Component)]
 pub(super) struct ScoreText;
 
 /// Startup system that is responsible for adding the score display to UI.
-pub fn setup_score_display(mut commands: Commands, fonts: Res<FontHandles>) {
+pub fn setup_score_display(
+    mut commands: Commands,
+    fonts: Res<FontHandles>,
+    theme: Res<UiTheme>,
+    palette: Res<Palette>,
+) {
     commands.spawn((
-        TextBundle {
-            text: Text::from_section(
-                "Score:".to_string(),
-                TextStyle {
-                    font: fonts.main_font.clone(),
-                    font_size: 60.0,
-                    color: Color::GREEN,
-                    ..Default::default()
-                },
-            ),
-            style: Style {
+        widgets::colored_label("", TextRoles::Hud, palette.score, &fonts, &theme).with_style(
+            Style {
                 margin: UiRect {
                     left: Val::Percent(5.0),
                     ..Default::default()
                 },
                 ..Default::default()
             },
-            ..Default::default()
-        },
+        ),
         ScoreText,
     ));
 }
 
+/// The score turns to the high score colour and says so while the run beats the best score on its
+/// leaderboard, and to the danger colour once the game is over.
+#[allow(clippy::too_many_arguments)]
 pub(super) fn update_score_text(
     score: Res<Score>,
-    local_scores: Res<LocalScores>,
+    leaderboards: Res<Leaderboards>,
+    daily_challenge: Res<DailyChallenge>,
+    current_mode: Res<CurrentGameMode>,
+    game_state: Res<GameState>,
+    palette: Res<Palette>,
+    localization: Res<Localization>,
     mut query: Query<&mut Text, With<ScoreText>>,
 ) {
-    for mut text in query.iter_mut() {
-        text.sections[0].value = format!("Score: {:?}", score.count);
-
-        if let Some(high_score) = local_scores.high_score() {
-            if score.count > high_score {
-                text.sections[0].style.color = Color::GOLD;
+    // practice runs can't set a high score
+    let is_new_best =
+        LeaderboardId::current(&daily_challenge, &current_mode).is_some_and(|leaderboard| {
+            match leaderboards.get(leaderboard).high_score() {
+                Some(high_score) => score.count > high_score,
+                None => true,
             }
-        } else {
-            text.sections[0].style.color = Color::GOLD;
-        }
-    }
-}
+        });
 
-// TODO: Change this to only trigger when game over state is entered.
-pub(super) fn change_color_on_game_over(
-    mut game_over_reader: EventReader<GameOver>,
-    mut score_text_query: Query<&mut Text, With<ScoreText>>,
-) {
-    if game_over_reader.read().next().is_some() {
-        for mut score_text in score_text_query.iter_mut() {
-            score_text.sections[0].style.color = Color::RED;
-        }
-    }
-}
+    let (key, color) = if let GameStates::GameOver = game_state.cur_state {
+        ("hud-score", palette.danger)
+    } else if is_new_best {
+        ("hud-score-best", palette.high_score)
+    } else {
+        ("hud-score", palette.score)
+    };
+    let value = localization.format(key, &[("score", &score.count)]);
 
-// TODO: Change this to only trigger when ingame state is entered.
-pub(super) fn revert_color_on_restart(
-    mut restart_reader: EventReader<GameRestarted>,
-    mut score_text_query: Query<&mut Text, With<ScoreText>>,
-) {
-    if restart_reader.read().next().is_some() {
-        for mut score_text in score_text_query.iter_mut() {
-            score_text.sections[0].style.color = Color::GREEN;
-        }
+    for mut text in query.iter_mut() {
+        text.sections[0].value = value.clone();
+        text.sections[0].style.color = color;
     }
 }
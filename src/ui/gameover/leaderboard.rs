@@ -1,8 +1,14 @@
 use bevy::prelude::*;
 
-use crate::leaderboard::{LocalScores, ScoreSaved};
+use crate::leaderboard::{Leaderboards, ScoreSaved};
+use crate::localization::Localization;
+use crate::modifiers::modifier_definition;
 use crate::shared::game::GameRestarted;
 use crate::shared::render::FontHandles;
+use crate::ui::{
+    theme::{TextRoles, UiTheme},
+    widgets,
+};
 
 #[derive(Resource)]
 pub(super) struct HighScoreDisplayConfig {
@@ -11,7 +17,15 @@ pub(super) struct HighScoreDisplayConfig {
 
 #[derive(Component)]
 pub(super) struct HighScoreDisplayRootNode {
-    score_nodes: Vec<Entity>,
+    title_node: Entity,
+    entry_nodes: Vec<EntryNodes>,
+}
+
+/// The texts of one place on the leaderboard.
+struct EntryNodes {
+    score: Entity,
+    /// The modifiers and game speed the score was set with.
+    tags: Entity,
 }
 
 /// Spawns a node that contains the leaderboard to display.
@@ -19,59 +33,44 @@ pub(super) fn spawn_leaderboard_display(
     mut commands: Commands,
     config: Res<HighScoreDisplayConfig>,
     fonts: Res<FontHandles>,
+    theme: Res<UiTheme>,
 ) {
-    let mut score_nodes = Vec::new();
+    let mut title_node = Entity::PLACEHOLDER;
+    let mut entry_nodes = Vec::new();
 
-    let leaderboard_root_node = commands
-        .spawn((NodeBundle {
-            style: Style {
-                flex_direction: FlexDirection::Column,
-                align_items: AlignItems::FlexStart,
-                justify_content: JustifyContent::SpaceEvenly,
-                flex_grow: 1.,
-                flex_shrink: 1.,
-                flex_basis: Val::Px(0.),
-                /*
-                margin: UiRect {
-                    top: Val::Percent(5.0),
-                    bottom: Val::Percent(20.0),
-                    left: Val::Percent(5.0),
-                    ..Default::default()
-                },*/
-                /*size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),*/
-                //flex_grow: 1.0,
-                ..Default::default()
-            },
-            visibility: Visibility::Hidden,
+    let mut leaderboard = widgets::list(
+        Style {
+            align_items: AlignItems::FlexStart,
+            justify_content: JustifyContent::SpaceEvenly,
+            flex_grow: 1.,
+            flex_shrink: 1.,
+            flex_basis: Val::Px(0.),
             ..Default::default()
-        },))
+        },
+        &theme,
+    );
+    leaderboard.0.visibility = Visibility::Hidden;
+
+    let leaderboard_root_node = commands
+        .spawn(leaderboard)
         .with_children(|builder| {
-            // Spawn leaderboard title.
-            builder.spawn(TextBundle {
-                style: Style {
-                    margin: UiRect {
-                        top: Val::Percent(10.0),
-                        ..Default::default()
-                    },
-                    ..Default::default()
-                },
-                text: Text::from_section(
-                    "High scores:",
-                    TextStyle {
-                        font_size: 35.0,
-                        font: fonts.main_font.clone(),
-                        color: Color::BLACK,
+            title_node = builder
+                .spawn(
+                    widgets::label("", TextRoles::Heading, &fonts, &theme).with_style(Style {
+                        margin: UiRect {
+                            top: Val::Percent(10.0),
+                            ..Default::default()
+                        },
                         ..Default::default()
-                    },
-                ),
-                visibility: Visibility::Inherited,
-                ..Default::default()
-            });
-
-            for i in 0..config.scores_to_show {
-                let score_node = builder
-                    .spawn((TextBundle {
+                    }),
+                )
+                .id();
+
+            for _ in 0..config.scores_to_show {
+                builder
+                    .spawn(NodeBundle {
                         style: Style {
+                            align_items: AlignItems::Baseline,
                             margin: UiRect {
                                 top: Val::Px(10.0),
                                 bottom: Val::Px(10.0),
@@ -79,19 +78,24 @@ pub(super) fn spawn_leaderboard_display(
                             },
                             ..Default::default()
                         },
-                        text: Text::from_section(
-                            format!("{}. test", i + 1),
-                            TextStyle {
-                                font: fonts.main_font.clone(),
-                                font_size: 25.0,
-                                color: Color::BLACK,
-                                ..Default::default()
-                            },
-                        ),
                         ..Default::default()
-                    },))
-                    .id();
-                score_nodes.push(score_node);
+                    })
+                    .with_children(|builder| {
+                        // places are coloured when the scores are shown
+                        let score = builder
+                            .spawn(widgets::colored_label(
+                                "",
+                                TextRoles::Entry,
+                                theme.text,
+                                &fonts,
+                                &theme,
+                            ))
+                            .id();
+                        let tags = builder
+                            .spawn(widgets::label("", TextRoles::Small, &fonts, &theme))
+                            .id();
+                        entry_nodes.push(EntryNodes { score, tags });
+                    });
             }
         })
         .id();
@@ -99,7 +103,10 @@ pub(super) fn spawn_leaderboard_display(
     // Add marker component that keeps track of the score ui nodes in order.
     commands
         .entity(leaderboard_root_node)
-        .insert(HighScoreDisplayRootNode { score_nodes });
+        .insert(HighScoreDisplayRootNode {
+            title_node,
+            entry_nodes,
+        });
 }
 
 fn change_visibility_of_scoreboard(
@@ -119,8 +126,10 @@ fn change_visibility_of_scoreboard(
 
 pub(super) fn show_high_scores_on_score_saved(
     mut score_saved_reader: EventReader<ScoreSaved>,
-    local_scores: Res<LocalScores>,
+    leaderboards: Res<Leaderboards>,
     mut high_score_visibility_query: Query<(&mut Visibility, &HighScoreDisplayRootNode)>,
+    theme: Res<UiTheme>,
+    localization: Res<Localization>,
     mut high_score_text_query: Query<&mut Text>,
 ) {
     if let Some(score_saved_event) = score_saved_reader.read().next() {
@@ -131,29 +140,58 @@ pub(super) fn show_high_scores_on_score_saved(
 
         change_visibility_of_scoreboard(true, &mut high_score_visibility_query);
 
-        let score_nodes = &high_score_visibility_query
+        let root_node = high_score_visibility_query
             .get_single()
             .expect("Could not find leaderboard root node to display its score nodes.")
-            .1
-            .score_nodes;
-
-        for (i, score_entity) in score_nodes.iter().enumerate() {
-            let mut high_score_text = high_score_text_query.get_mut(*score_entity).unwrap();
-
-            if local_scores.scores.len() > i {
-                high_score_text.sections[0].value =
-                    format!("{}. {}", i + 1, local_scores.scores[i]);
-                if i == 0 {
-                    high_score_text.sections[0].style.color = Color::GOLD;
-                } else if i == 1 {
-                    high_score_text.sections[0].style.color = Color::SILVER;
-                } else if i == 2 {
-                    // bronze color
-                    high_score_text.sections[0].style.color = Color::hex("cd7f32").unwrap();
+            .1;
+
+        let scores = leaderboards.get(score_saved_event.leaderboard);
+        high_score_text_query
+            .get_mut(root_node.title_node)
+            .unwrap()
+            .sections[0]
+            .value = score_saved_event.leaderboard.title(&localization);
+
+        for (i, entry_nodes) in root_node.entry_nodes.iter().enumerate() {
+            let (score_text, tags_text) = match scores.scores.get(i) {
+                Some(entry) => {
+                    let mut tags: Vec<String> = entry
+                        .modifiers
+                        .iter()
+                        .map(|&id| {
+                            localization
+                                .get(modifier_definition(id).name_key)
+                                .to_string()
+                        })
+                        .collect();
+                    if entry.game_speed.is_assisted() {
+                        tags.push(localization.format(
+                            "leaderboard-game-speed",
+                            &[("speed", &localization.get(entry.game_speed.text_key()))],
+                        ));
+                    }
+
+                    (
+                        format!("{}. {}", i + 1, entry.score),
+                        if tags.is_empty() {
+                            "".to_string()
+                        } else {
+                            format!("  {}", tags.join(", "))
+                        },
+                    )
                 }
-            } else {
-                high_score_text.sections[0].value = "".to_string();
-            }
+                None => ("".to_string(), "".to_string()),
+            };
+
+            let mut text = high_score_text_query.get_mut(entry_nodes.score).unwrap();
+            text.sections[0].value = score_text;
+            text.sections[0].style.color = theme.place_color(i);
+
+            high_score_text_query
+                .get_mut(entry_nodes.tags)
+                .unwrap()
+                .sections[0]
+                .value = tags_text;
         }
     }
 }
@@ -1,8 +1,19 @@
 use bevy::prelude::*;
 
+use crate::accessibility::Palette;
+use crate::daily::DailyChallenge;
+use crate::localization::{Localization, LocalizedText};
+use crate::modes::CurrentGameMode;
 use crate::player::events::{PlayerBonked, PlayerHooked, PlayerStarved};
-use crate::shared::game::GameRestarted;
+use crate::shared::game::{GameRestarted, GoalReached};
 use crate::shared::render::FontHandles;
+#[cfg(target_arch = "wasm32")]
+use crate::shared::rng::share_seed;
+use crate::shared::rng::{seed_code, GameRng};
+use crate::ui::{
+    theme::{TextRoles, UiTheme},
+    widgets,
+};
 
 #[derive(Component)]
 pub(super) struct GameOverMessageRootNode;
@@ -13,7 +24,24 @@ pub(super) struct GameOverText;
 #[derive(Component)]
 struct RestartText;
 
-pub(super) fn spawn_gameover_message_display(mut commands: Commands, fonts: Res<FontHandles>) {
+#[derive(Component)]
+struct LockerHintText;
+
+#[derive(Component)]
+pub(super) struct SeedText;
+
+pub(super) fn spawn_gameover_message_display(
+    mut commands: Commands,
+    fonts: Res<FontHandles>,
+    theme: Res<UiTheme>,
+    palette: Res<Palette>,
+    localization: Res<Localization>,
+) {
+    let message_label = |value: &str, role| {
+        widgets::colored_label(value, role, palette.danger, &fonts, &theme)
+            .with_justify(JustifyText::Center)
+    };
+
     commands
         .spawn((
             NodeBundle {
@@ -32,58 +60,53 @@ pub(super) fn spawn_gameover_message_display(mut commands: Commands, fonts: Res<
         ))
         .with_children(|builder| {
             // TODO: Can we use 1 text section here?
+            builder.spawn((message_label("", TextRoles::Title), GameOverText));
             builder.spawn((
-                TextBundle {
-                    text: Text::from_section(
-                        "HOOKED!".to_string(),
-                        TextStyle {
-                            font_size: 100.0,
-                            font: fonts.main_font.clone(),
-                            color: Color::RED,
-                        },
-                    )
-                    .with_justify(JustifyText::Center),
-                    ..Default::default()
-                },
-                GameOverText,
+                message_label(localization.get("game-over-restart"), TextRoles::Subtitle),
+                LocalizedText("game-over-restart"),
+                RestartText,
             ));
             builder.spawn((
-                TextBundle {
-                    text: Text::from_section(
-                        "Press [R] to restart".to_string(),
-                        TextStyle {
-                            font_size: 50.0,
-                            font: fonts.main_font.clone(),
-                            color: Color::RED,
-                        },
-                    )
-                    .with_justify(JustifyText::Center),
-                    ..Default::default()
-                },
-                RestartText,
+                message_label(localization.get("game-over-locker"), TextRoles::Item),
+                LocalizedText("game-over-locker"),
+                LockerHintText,
             ));
+            builder.spawn((message_label("", TextRoles::Item), SeedText));
         });
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(super) fn show_game_over_text(
     mut player_hooked_reader: EventReader<PlayerHooked>,
     mut player_starved_reader: EventReader<PlayerStarved>,
     mut player_bonked_reader: EventReader<PlayerBonked>,
-    mut game_over_text_query: Query<&mut Text, With<GameOverText>>,
+    mut goal_reached_reader: EventReader<GoalReached>,
+    current_mode: Res<CurrentGameMode>,
+    rng: Res<GameRng>,
+    daily_challenge: Res<DailyChallenge>,
+    localization: Res<Localization>,
+    mut game_over_text_query: Query<&mut Text, (With<GameOverText>, Without<SeedText>)>,
+    mut seed_text_query: Query<&mut Text, (With<SeedText>, Without<GameOverText>)>,
     mut game_over_root_node_query: Query<&mut Visibility, With<GameOverMessageRootNode>>,
 ) {
     // TODO: Refactor gameover event to be an enum and use match here instead.
     let mut game_over_message = "".to_string();
     if player_hooked_reader.read().next().is_some() {
-        game_over_message = "HOOKED!".to_string();
+        game_over_message = localization.get("game-over-hooked").to_string();
     }
 
     if player_bonked_reader.read().next().is_some() {
-        game_over_message = "BONKED!".to_string();
+        game_over_message = localization.get("game-over-bonked").to_string();
     }
 
     if player_starved_reader.read().next().is_some() {
-        game_over_message = "STARVED!".to_string();
+        game_over_message = localization.get("game-over-starved").to_string();
+    }
+
+    if goal_reached_reader.read().next().is_some() {
+        game_over_message = localization
+            .get(current_mode.active().goal_message_key())
+            .to_string();
     }
 
     if game_over_message != *"" {
@@ -92,6 +115,29 @@ pub(super) fn show_game_over_text(
             .expect("Could not find game over text node to set.");
         game_over_text.sections[0].value = game_over_message.clone();
 
+        let mut seed_text = seed_text_query
+            .get_single_mut()
+            .expect("Could not find seed text node to set.");
+        let seed_key = match daily_challenge.run {
+            Some(run) if run.ranked => "game-over-seed-daily",
+            Some(_) => "game-over-seed-practice",
+            None => "game-over-seed",
+        };
+        let code = seed_code(rng.seed);
+        let seed_message = localization.format(seed_key, &[("seed", &code)]);
+
+        // the web version shares the seed in the url, on desktop the game has to be started with it
+        #[cfg(target_arch = "wasm32")]
+        share_seed(rng.seed);
+        #[cfg(not(target_arch = "wasm32"))]
+        let seed_message = format!(
+            "{}\n{}",
+            seed_message,
+            localization.format("game-over-replay", &[("seed", &code)])
+        );
+
+        seed_text.sections[0].value = seed_message;
+
         let mut game_over_root_node_vis = game_over_root_node_query
             .get_single_mut()
             .expect("Could not find game over message root node to change visibility.");
@@ -100,6 +146,26 @@ pub(super) fn show_game_over_text(
     }
 }
 
+pub(super) fn update_game_over_message_colors(
+    palette: Res<Palette>,
+    root_query: Query<&Children, With<GameOverMessageRootNode>>,
+    mut text_query: Query<&mut Text>,
+) {
+    if !palette.is_changed() {
+        return;
+    }
+
+    for children in root_query.iter() {
+        for &child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                for section in text.sections.iter_mut() {
+                    section.style.color = palette.danger;
+                }
+            }
+        }
+    }
+}
+
 // TODO: Refactor to use types to reduce complexity
 pub(super) fn clear_game_over_message_on_restart(
     mut restart_reader: EventReader<GameRestarted>,
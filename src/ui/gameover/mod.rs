@@ -30,6 +30,7 @@ impl Plugin for GameOverHudPlugin {
                 (
                     message::show_game_over_text,
                     message::clear_game_over_message_on_restart,
+                    message::update_game_over_message_colors,
                     leaderboard::show_high_scores_on_score_saved,
                     leaderboard::hide_high_scores_on_restart,
                 )
@@ -0,0 +1,237 @@
+use bevy::prelude::*;
+
+use super::theme::{TextRoles, UiTheme};
+use super::widgets;
+use crate::localization::Localization;
+use crate::player::skins::{PlayerSkins, SkinSelection, SKINS};
+use crate::shared::{
+    atlas::SpriteAtlas,
+    game::{GameRestarted, GameState, GameStates},
+    render::FontHandles,
+    stages,
+};
+use crate::stats::LifetimeStats;
+
+/// Plugin for the locker screen, where the player picks which skin their fish wears. The locker
+/// can be opened from the menu and the game over screen.
+pub struct LockerPlugin;
+
+impl Plugin for LockerPlugin {
+    fn build(&self, app: &mut App) {
+        debug!("Building LockerPlugin...");
+        app.init_resource::<LockerState>()
+            .add_systems(Startup, spawn_locker_display)
+            .add_systems(
+                Update,
+                (toggle_locker_system, browse_locker_system).in_set(stages::HandleEventsSet),
+            )
+            .add_systems(
+                Update,
+                (update_locker_display).in_set(stages::PrepareRenderSet),
+            );
+    }
+}
+
+#[derive(Debug, Default, Resource)]
+pub(super) struct LockerState {
+    pub is_open: bool,
+    /// Index into the list of skins of the skin being looked at.
+    cursor: usize,
+}
+
+#[derive(Component)]
+struct LockerRootNode;
+
+#[derive(Component)]
+struct LockerSkinPreview;
+
+#[derive(Component)]
+struct LockerSkinName;
+
+#[derive(Component)]
+struct LockerSkinStatus;
+
+fn spawn_locker_display(
+    mut commands: Commands,
+    fonts: Res<FontHandles>,
+    theme: Res<UiTheme>,
+    localization: Res<Localization>,
+    sprite_atlas: Res<SpriteAtlas>,
+) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..Default::default()
+                },
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Global(1),
+                ..Default::default()
+            },
+            LockerRootNode,
+        ))
+        .with_children(|builder| {
+            builder
+                .spawn(widgets::panel(
+                    Style {
+                        align_items: AlignItems::Center,
+                        ..Default::default()
+                    },
+                    &theme,
+                ))
+                .with_children(|builder| {
+                    builder.spawn(widgets::localized_label(
+                        "locker-title",
+                        TextRoles::Subtitle,
+                        &fonts,
+                        &theme,
+                        &localization,
+                    ));
+                    builder.spawn((
+                        AtlasImageBundle {
+                            style: Style {
+                                width: Val::Px(96.0),
+                                height: Val::Px(96.0),
+                                ..Default::default()
+                            },
+                            image: UiImage::new(sprite_atlas.image.clone()),
+                            texture_atlas: sprite_atlas.texture_atlas(0),
+                            ..Default::default()
+                        },
+                        LockerSkinPreview,
+                    ));
+                    builder.spawn((
+                        widgets::label("", TextRoles::Heading, &fonts, &theme),
+                        LockerSkinName,
+                    ));
+                    builder.spawn((
+                        widgets::label("", TextRoles::Entry, &fonts, &theme),
+                        LockerSkinStatus,
+                    ));
+                    builder.spawn(widgets::localized_label(
+                        "locker-controls",
+                        TextRoles::Body,
+                        &fonts,
+                        &theme,
+                        &localization,
+                    ));
+                });
+        });
+}
+
+/// Opens and closes the locker. The locker can only be opened between runs.
+fn toggle_locker_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    game_state: Res<GameState>,
+    skin_selection: Res<SkinSelection>,
+    mut restart_reader: EventReader<GameRestarted>,
+    mut locker_state: ResMut<LockerState>,
+) {
+    if restart_reader.read().next().is_some() {
+        locker_state.is_open = false;
+        return;
+    }
+
+    let (GameStates::GameOver | GameStates::Menu) = game_state.cur_state else {
+        return;
+    };
+
+    if keyboard_input.just_pressed(KeyCode::KeyL) {
+        locker_state.is_open = !locker_state.is_open;
+        locker_state.cursor = SKINS
+            .iter()
+            .position(|skin| skin.id == skin_selection.skin)
+            .unwrap_or(0);
+    }
+}
+
+fn browse_locker_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    stats: Res<LifetimeStats>,
+    mut locker_state: ResMut<LockerState>,
+    mut skin_selection: ResMut<SkinSelection>,
+) {
+    if !locker_state.is_open {
+        return;
+    }
+
+    if keyboard_input.any_just_pressed([KeyCode::ArrowLeft, KeyCode::KeyA]) {
+        locker_state.cursor = (locker_state.cursor + SKINS.len() - 1) % SKINS.len();
+    }
+
+    if keyboard_input.any_just_pressed([KeyCode::ArrowRight, KeyCode::KeyD]) {
+        locker_state.cursor = (locker_state.cursor + 1) % SKINS.len();
+    }
+
+    if keyboard_input.any_just_pressed([KeyCode::Enter, KeyCode::Space]) {
+        let skin = &SKINS[locker_state.cursor];
+
+        if skin.id != skin_selection.skin && skin.unlock.is_met(&stats) {
+            debug!("Wearing skin {:?}", skin.id);
+            skin_selection.skin = skin.id;
+            skin_selection.save();
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn update_locker_display(
+    locker_state: Res<LockerState>,
+    skin_selection: Res<SkinSelection>,
+    stats: Res<LifetimeStats>,
+    player_skins: Res<PlayerSkins>,
+    localization: Res<Localization>,
+    mut root_query: Query<&mut Visibility, With<LockerRootNode>>,
+    mut preview_query: Query<(&mut TextureAtlas, &mut BackgroundColor), With<LockerSkinPreview>>,
+    mut name_query: Query<&mut Text, (With<LockerSkinName>, Without<LockerSkinStatus>)>,
+    mut status_query: Query<&mut Text, (With<LockerSkinStatus>, Without<LockerSkinName>)>,
+) {
+    if !locker_state.is_changed() && !skin_selection.is_changed() && !localization.is_changed() {
+        return;
+    }
+
+    let mut root_visibility = root_query
+        .get_single_mut()
+        .expect("Could not find locker root node.");
+
+    *root_visibility = if locker_state.is_open {
+        Visibility::Visible
+    } else {
+        Visibility::Hidden
+    };
+
+    let skin = &SKINS[locker_state.cursor];
+    let is_unlocked = skin.unlock.is_met(&stats);
+
+    for (mut preview_atlas, mut preview_tint) in preview_query.iter_mut() {
+        preview_atlas.index = player_skins.preview_index(skin.id);
+        *preview_tint = if is_unlocked {
+            skin.tint.into()
+        } else {
+            // show locked skins as a silhouette
+            Color::BLACK.into()
+        };
+    }
+
+    for mut name_text in name_query.iter_mut() {
+        name_text.sections[0].value = localization.get(skin.name_key).to_string();
+    }
+
+    for mut status_text in status_query.iter_mut() {
+        status_text.sections[0].value = if skin.id == skin_selection.skin {
+            localization.get("locker-wearing").to_string()
+        } else if is_unlocked {
+            localization.get("locker-unlocked").to_string()
+        } else {
+            localization.format(
+                "locker-locked",
+                &[("requirement", &skin.unlock.description(&localization))],
+            )
+        };
+    }
+}
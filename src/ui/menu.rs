@@ -0,0 +1,260 @@
+use bevy::prelude::*;
+
+use super::locker::LockerState;
+use super::settings::SettingsScreen;
+use super::theme::{TextRoles, UiTheme};
+use super::widgets;
+use crate::daily::{DailyChallenge, DailyHistory};
+use crate::localization::Localization;
+use crate::modes::CurrentGameMode;
+use crate::modifiers::modifier_definition;
+use crate::shared::{
+    game::{GameRestarted, GameState, GameStates},
+    render::FontHandles,
+    rng::{seed_code, GameRng, SeedModes},
+    stages,
+};
+
+/// Plugin for the title menu that is shown when the game is first opened.
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        debug!("Building MenuPlugin...");
+        app.add_systems(Startup, spawn_menu_display)
+            .add_systems(
+                Update,
+                (
+                    start_game_system,
+                    toggle_daily_seed_system,
+                    select_game_mode_system,
+                )
+                    .in_set(stages::HandleEventsSet),
+            )
+            .add_systems(
+                Update,
+                (
+                    show_menu_in_menu_state,
+                    update_seed_mode_text,
+                    update_game_mode_text,
+                )
+                    .in_set(stages::PrepareRenderSet),
+            );
+    }
+}
+
+#[derive(Component)]
+struct MenuRootNode;
+
+#[derive(Component)]
+struct SeedModeText;
+
+#[derive(Component)]
+struct GameModeText;
+
+fn spawn_menu_display(
+    mut commands: Commands,
+    fonts: Res<FontHandles>,
+    theme: Res<UiTheme>,
+    localization: Res<Localization>,
+) {
+    let mut menu = widgets::screen();
+    menu.visibility = Visibility::Inherited;
+
+    commands
+        .spawn((menu, MenuRootNode))
+        .with_children(|builder| {
+            builder.spawn(widgets::localized_label(
+                "menu-title",
+                TextRoles::Title,
+                &fonts,
+                &theme,
+                &localization,
+            ));
+            builder.spawn(widgets::localized_label(
+                "menu-start",
+                TextRoles::Subtitle,
+                &fonts,
+                &theme,
+                &localization,
+            ));
+            for hint in [
+                "menu-locker",
+                "menu-modifiers",
+                "menu-settings",
+                "menu-daily-history",
+            ] {
+                builder.spawn(widgets::localized_label(
+                    hint,
+                    TextRoles::Item,
+                    &fonts,
+                    &theme,
+                    &localization,
+                ));
+            }
+            builder.spawn((
+                widgets::label("", TextRoles::Item, &fonts, &theme),
+                SeedModeText,
+            ));
+            builder.spawn((
+                widgets::label("", TextRoles::Item, &fonts, &theme),
+                GameModeText,
+            ));
+        });
+}
+
+/// Starts the first run by restarting the frozen world behind the menu.
+fn start_game_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    game_state: Res<GameState>,
+    locker_state: Res<LockerState>,
+    settings_screen: Res<SettingsScreen>,
+    mut restart_events: EventWriter<GameRestarted>,
+) {
+    let GameStates::Menu = game_state.cur_state else {
+        return;
+    };
+
+    // Enter is used to pick a skin while the locker is open, and can be bound in the settings
+    if keyboard_input.just_pressed(KeyCode::Enter)
+        && !locker_state.is_open
+        && !settings_screen.is_open
+    {
+        debug!("Starting game from the menu");
+        restart_events.send(GameRestarted);
+    }
+}
+
+/// Switches between the daily challenge and a random seed for the next run.
+fn toggle_daily_seed_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    game_state: Res<GameState>,
+    locker_state: Res<LockerState>,
+    settings_screen: Res<SettingsScreen>,
+    mut rng: ResMut<GameRng>,
+) {
+    let GameStates::Menu = game_state.cur_state else {
+        return;
+    };
+
+    if keyboard_input.just_pressed(KeyCode::KeyD)
+        && !locker_state.is_open
+        && !settings_screen.is_open
+    {
+        let mode = if let SeedModes::Daily = rng.mode {
+            SeedModes::Random
+        } else {
+            SeedModes::Daily
+        };
+        rng.set_mode(mode);
+    }
+}
+
+/// Cycles through the game modes for the next run.
+fn select_game_mode_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    game_state: Res<GameState>,
+    locker_state: Res<LockerState>,
+    settings_screen: Res<SettingsScreen>,
+    mut current_mode: ResMut<CurrentGameMode>,
+) {
+    let GameStates::Menu = game_state.cur_state else {
+        return;
+    };
+
+    if keyboard_input.just_pressed(KeyCode::KeyG)
+        && !locker_state.is_open
+        && !settings_screen.is_open
+    {
+        current_mode.select_next();
+    }
+}
+
+fn update_game_mode_text(
+    rng: Res<GameRng>,
+    current_mode: Res<CurrentGameMode>,
+    localization: Res<Localization>,
+    mut text_query: Query<&mut Text, With<GameModeText>>,
+) {
+    if !rng.is_changed() && !current_mode.is_changed() && !localization.is_changed() {
+        return;
+    }
+
+    let mode_text = if let SeedModes::Daily = rng.mode {
+        localization.get("menu-mode-daily").to_string()
+    } else {
+        let mode = current_mode.selected();
+        localization.format(
+            "menu-mode",
+            &[
+                ("mode", &localization.get(mode.name_key())),
+                ("description", &localization.get(mode.description_key())),
+            ],
+        )
+    };
+
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = mode_text.clone();
+    }
+}
+
+fn update_seed_mode_text(
+    rng: Res<GameRng>,
+    history: Res<DailyHistory>,
+    localization: Res<Localization>,
+    mut text_query: Query<&mut Text, With<SeedModeText>>,
+) {
+    if !rng.is_changed() && !history.is_changed() && !localization.is_changed() {
+        return;
+    }
+
+    let seed_mode = match rng.mode {
+        SeedModes::Random => localization.get("menu-seed-random").to_string(),
+        SeedModes::Fixed(seed) => {
+            localization.format("menu-seed-fixed", &[("seed", &seed_code(seed))])
+        }
+        SeedModes::Daily => {
+            let today = DailyChallenge::today(&history);
+            let modifier = today
+                .modifier
+                .map(|modifier| {
+                    format!(
+                        " ({})",
+                        localization.get(modifier_definition(modifier).name_key)
+                    )
+                })
+                .unwrap_or_default();
+            let key = if today.ranked {
+                "menu-seed-daily-ranked"
+            } else {
+                "menu-seed-daily-practice"
+            };
+
+            localization.format(key, &[("modifier", &modifier)])
+        }
+    };
+
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = seed_mode.clone();
+    }
+}
+
+/// The menu makes way for the settings screen while it is open.
+fn show_menu_in_menu_state(
+    game_state: Res<GameState>,
+    settings_screen: Res<SettingsScreen>,
+    mut root_query: Query<&mut Visibility, With<MenuRootNode>>,
+) {
+    if !game_state.is_changed() && !settings_screen.is_changed() {
+        return;
+    }
+
+    for mut visibility in root_query.iter_mut() {
+        *visibility =
+            if let (GameStates::Menu, false) = (game_state.cur_state, settings_screen.is_open) {
+                Visibility::Visible
+            } else {
+                Visibility::Hidden
+            };
+    }
+}
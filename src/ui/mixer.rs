@@ -0,0 +1,170 @@
+use bevy::prelude::*;
+use bevy::ui::RelativeCursorPosition;
+
+use super::settings::SettingsScreen;
+use super::theme::{TextRoles, UiTheme};
+use super::widgets;
+use crate::accessibility::Palette;
+use crate::audio::mixer::AudioBuses;
+use crate::localization::Localization;
+use crate::settings::Settings;
+use crate::shared::{render::FontHandles, stages};
+
+const SLIDER_WIDTH: f32 = 200.0;
+const SLIDER_HEIGHT: f32 = 20.0;
+
+/// Plugin for the volume sliders of the audio mixer. The sliders are shown on the settings screen.
+pub struct MixerPanelPlugin;
+
+impl Plugin for MixerPanelPlugin {
+    fn build(&self, app: &mut App) {
+        debug!("Building MixerPanelPlugin...");
+        app.add_systems(Startup, spawn_mixer_panel)
+            .add_systems(
+                Update,
+                (volume_slider_system).in_set(stages::HandleEventsSet),
+            )
+            .add_systems(
+                Update,
+                (show_mixer_panel, update_volume_slider_fills).in_set(stages::PrepareRenderSet),
+            );
+    }
+}
+
+#[derive(Component)]
+struct MixerPanelRootNode;
+
+#[derive(Component)]
+struct VolumeSlider {
+    bus: AudioBuses,
+}
+
+#[derive(Component)]
+struct VolumeSliderFill {
+    bus: AudioBuses,
+}
+
+fn spawn_mixer_panel(
+    mut commands: Commands,
+    fonts: Res<FontHandles>,
+    theme: Res<UiTheme>,
+    palette: Res<Palette>,
+    localization: Res<Localization>,
+) {
+    let mut panel = widgets::panel(
+        Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(20.0),
+            bottom: Val::Px(20.0),
+            ..Default::default()
+        },
+        &theme,
+    );
+    panel.0.visibility = Visibility::Hidden;
+
+    commands
+        .spawn((panel, MixerPanelRootNode))
+        .with_children(|builder| {
+            for (bus, key) in [
+                (AudioBuses::Master, "mixer-master"),
+                (AudioBuses::Music, "mixer-music"),
+                (AudioBuses::Sfx, "mixer-sfx"),
+            ] {
+                builder.spawn(widgets::localized_label(
+                    key,
+                    TextRoles::Body,
+                    &fonts,
+                    &theme,
+                    &localization,
+                ));
+
+                builder
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(SLIDER_WIDTH),
+                                height: Val::Px(SLIDER_HEIGHT),
+                                ..Default::default()
+                            },
+                            background_color: theme.slider_background.into(),
+                            ..Default::default()
+                        },
+                        RelativeCursorPosition::default(),
+                        VolumeSlider { bus },
+                    ))
+                    .with_children(|builder| {
+                        builder.spawn((
+                            NodeBundle {
+                                style: Style {
+                                    height: Val::Percent(100.0),
+                                    ..Default::default()
+                                },
+                                background_color: palette.slider_fill.into(),
+                                ..Default::default()
+                            },
+                            VolumeSliderFill { bus },
+                        ));
+                    });
+            }
+        });
+}
+
+fn show_mixer_panel(
+    settings_screen: Res<SettingsScreen>,
+    mut root_query: Query<&mut Visibility, With<MixerPanelRootNode>>,
+) {
+    if !settings_screen.is_changed() {
+        return;
+    }
+
+    for mut visibility in root_query.iter_mut() {
+        *visibility = if settings_screen.is_open {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+/// Sets the volume of a bus to wherever the slider is being dragged, and saves it once the slider
+/// is released.
+fn volume_slider_system(
+    mut settings: ResMut<Settings>,
+    slider_query: Query<(&Interaction, &RelativeCursorPosition, &VolumeSlider)>,
+    released_query: Query<&Interaction, (Changed<Interaction>, With<VolumeSlider>)>,
+) {
+    for (interaction, cursor_position, slider) in slider_query.iter() {
+        let Interaction::Pressed = interaction else {
+            continue;
+        };
+
+        if let Some(normalized) = cursor_position.normalized {
+            if settings.audio.bus_volume(slider.bus) != normalized.x.clamp(0.0, 1.0) {
+                settings.audio.set_bus_volume(slider.bus, normalized.x);
+            }
+        }
+    }
+
+    if released_query
+        .iter()
+        .any(|interaction| *interaction != Interaction::Pressed)
+    {
+        debug!("Saving audio settings: {:?}", settings.audio);
+        settings.save();
+    }
+}
+
+fn update_volume_slider_fills(
+    settings: Res<Settings>,
+    palette: Res<Palette>,
+    mut fill_query: Query<(&mut Style, &mut BackgroundColor, &VolumeSliderFill)>,
+) {
+    if !settings.is_changed() && !palette.is_changed() {
+        return;
+    }
+
+    for (mut style, mut background_color, fill) in fill_query.iter_mut() {
+        style.width = Val::Percent(settings.audio.bus_volume(fill.bus) * 100.0);
+        *background_color = palette.slider_fill.into();
+    }
+}
@@ -1,7 +1,18 @@
 use bevy::prelude::*;
 
+mod accessibility;
+mod daily;
 mod gamehud;
 mod gameover;
+mod locker;
+mod menu;
+mod mixer;
+mod modifiers;
+mod pause;
+mod settings;
+mod theme;
+mod toast;
+mod widgets;
 
 /// Plugin that encompasses the entire UI for the game.
 /// The UI includes everything that is overlaid on top of the game in its various scenes such as
@@ -12,6 +23,19 @@ impl Plugin for UIPlugin {
     /// Builds the UI and adds the relevant entities, components and systems to the bevy app.
     fn build(&self, app: &mut App) {
         debug!("Building UI plugin...");
-        app.add_plugins((gamehud::GameHudPlugin, gameover::GameOverHudPlugin));
+        app.add_plugins((
+            theme::UiThemePlugin,
+            accessibility::AccessibilityPanelPlugin,
+            daily::DailyHistoryPanelPlugin,
+            gamehud::GameHudPlugin,
+            gameover::GameOverHudPlugin,
+            locker::LockerPlugin,
+            menu::MenuPlugin,
+            mixer::MixerPanelPlugin,
+            modifiers::ModifiersPanelPlugin,
+            pause::PauseMenuPlugin,
+            settings::SettingsScreenPlugin,
+            toast::ToastPlugin,
+        ));
     }
 }
@@ -0,0 +1,266 @@
+use bevy::prelude::*;
+
+use super::theme::{TextRoles, UiTheme};
+use super::widgets;
+use crate::accessibility::Palette;
+use crate::localization::{Localization, LocalizedText};
+use crate::modifiers::{modifier_definition, score_multiplier, ModifierId, Modifiers, MODIFIERS};
+use crate::shared::{
+    game::{GameRestarted, GameState, GameStates},
+    render::FontHandles,
+    rng::{GameRng, SeedModes},
+    stages,
+};
+
+/// Keys that toggle the modifiers, in the order of `MODIFIERS`.
+const MODIFIER_KEYS: [KeyCode; 8] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+];
+
+const UNSELECTED_COLOR: Color = Color::BLACK;
+
+/// Plugin for the panel where modifiers are chosen before a run. The panel can be opened with [Tab]
+/// between runs.
+pub struct ModifiersPanelPlugin;
+
+impl Plugin for ModifiersPanelPlugin {
+    fn build(&self, app: &mut App) {
+        debug!("Building ModifiersPanelPlugin...");
+        app.add_systems(Startup, spawn_modifiers_panel)
+            .add_systems(
+                Update,
+                (toggle_modifiers_panel_system, toggle_modifiers_system)
+                    .in_set(stages::HandleEventsSet),
+            )
+            .add_systems(
+                Update,
+                (update_modifiers_display).in_set(stages::PrepareRenderSet),
+            );
+    }
+}
+
+#[derive(Component)]
+struct ModifiersPanelRootNode {
+    is_open: bool,
+}
+
+#[derive(Component)]
+struct ModifierText {
+    id: ModifierId,
+    /// The name is written by `update_modifiers_display`, the description is a `LocalizedText`.
+    is_name: bool,
+}
+
+#[derive(Component)]
+struct ScoreMultiplierText;
+
+fn spawn_modifiers_panel(
+    mut commands: Commands,
+    fonts: Res<FontHandles>,
+    theme: Res<UiTheme>,
+    localization: Res<Localization>,
+) {
+    let mut panel = widgets::panel(
+        Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(20.0),
+            top: Val::Px(20.0),
+            ..Default::default()
+        },
+        &theme,
+    );
+    panel.0.visibility = Visibility::Hidden;
+
+    commands
+        .spawn((panel, ModifiersPanelRootNode { is_open: false }))
+        .with_children(|builder| {
+            builder.spawn(widgets::localized_label(
+                "modifiers-title",
+                TextRoles::Item,
+                &fonts,
+                &theme,
+                &localization,
+            ));
+
+            for modifier in MODIFIERS {
+                builder
+                    .spawn(NodeBundle {
+                        style: Style {
+                            align_items: AlignItems::Baseline,
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    })
+                    .with_children(|builder| {
+                        builder.spawn((
+                            widgets::colored_label(
+                                "",
+                                TextRoles::Body,
+                                UNSELECTED_COLOR,
+                                &fonts,
+                                &theme,
+                            )
+                            .with_style(Style {
+                                margin: UiRect::right(Val::Px(10.0)),
+                                ..Default::default()
+                            }),
+                            ModifierText {
+                                id: modifier.id,
+                                is_name: true,
+                            },
+                        ));
+                        builder.spawn((
+                            widgets::colored_label(
+                                localization.get(modifier.description_key),
+                                TextRoles::Small,
+                                UNSELECTED_COLOR,
+                                &fonts,
+                                &theme,
+                            ),
+                            ModifierText {
+                                id: modifier.id,
+                                is_name: false,
+                            },
+                            LocalizedText(modifier.description_key),
+                        ));
+                    });
+            }
+
+            builder.spawn((
+                widgets::label("", TextRoles::Body, &fonts, &theme),
+                ScoreMultiplierText,
+            ));
+            builder.spawn(widgets::localized_label(
+                "modifiers-close",
+                TextRoles::Body,
+                &fonts,
+                &theme,
+                &localization,
+            ));
+        });
+}
+
+/// Opens and closes the panel. Modifiers can only be chosen between runs.
+fn toggle_modifiers_panel_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    game_state: Res<GameState>,
+    mut restart_reader: EventReader<GameRestarted>,
+    mut root_query: Query<&mut ModifiersPanelRootNode>,
+) {
+    if restart_reader.read().next().is_some() {
+        for mut root_node in root_query.iter_mut() {
+            root_node.is_open = false;
+        }
+        return;
+    }
+
+    let (GameStates::GameOver | GameStates::Menu) = game_state.cur_state else {
+        return;
+    };
+
+    if keyboard_input.just_pressed(KeyCode::Tab) {
+        for mut root_node in root_query.iter_mut() {
+            root_node.is_open = !root_node.is_open;
+        }
+    }
+}
+
+fn toggle_modifiers_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    root_query: Query<&ModifiersPanelRootNode>,
+    mut modifiers: ResMut<Modifiers>,
+) {
+    if !root_query.iter().any(|root_node| root_node.is_open) {
+        return;
+    }
+
+    for (key, modifier) in MODIFIER_KEYS.iter().zip(MODIFIERS.iter()) {
+        if keyboard_input.just_pressed(*key) {
+            modifiers.toggle(modifier.id);
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn update_modifiers_display(
+    modifiers: Res<Modifiers>,
+    rng: Res<GameRng>,
+    palette: Res<Palette>,
+    localization: Res<Localization>,
+    mut root_query: Query<(Ref<ModifiersPanelRootNode>, &mut Visibility)>,
+    mut modifier_text_query: Query<(&mut Text, &ModifierText), Without<ScoreMultiplierText>>,
+    mut multiplier_text_query: Query<&mut Text, (With<ScoreMultiplierText>, Without<ModifierText>)>,
+) {
+    for (root_node, mut visibility) in root_query.iter_mut() {
+        if root_node.is_changed() {
+            *visibility = if root_node.is_open {
+                Visibility::Visible
+            } else {
+                Visibility::Hidden
+            };
+        }
+    }
+
+    if !modifiers.is_changed()
+        && !rng.is_changed()
+        && !palette.is_changed()
+        && !localization.is_changed()
+    {
+        return;
+    }
+
+    for (mut text, modifier_text) in modifier_text_query.iter_mut() {
+        let color = if modifiers.is_selected(modifier_text.id) {
+            palette.selected
+        } else {
+            UNSELECTED_COLOR
+        };
+
+        for section in text.sections.iter_mut() {
+            section.style.color = color;
+        }
+
+        if modifier_text.is_name {
+            text.sections[0].value = modifier_name_text(modifier_text.id, &localization);
+        }
+    }
+
+    let multiplier_text = if let SeedModes::Daily = rng.mode {
+        localization.get("modifiers-daily").to_string()
+    } else {
+        localization.format(
+            "modifiers-score-multiplier",
+            &[(
+                "multiplier",
+                &format!("{:.2}", score_multiplier(modifiers.selected())),
+            )],
+        )
+    };
+
+    for mut text in multiplier_text_query.iter_mut() {
+        text.sections[0].value = multiplier_text.clone();
+    }
+}
+
+/// The name of a modifier with the key that toggles it and its score multiplier.
+fn modifier_name_text(id: ModifierId, localization: &Localization) -> String {
+    let number = MODIFIERS
+        .iter()
+        .position(|modifier| modifier.id == id)
+        .map_or(0, |index| index + 1);
+    let modifier = modifier_definition(id);
+
+    format!(
+        "[{}] {}  x{}",
+        number,
+        localization.get(modifier.name_key),
+        modifier.score_multiplier
+    )
+}
@@ -0,0 +1,286 @@
+use bevy::prelude::*;
+
+use super::settings::SettingsScreen;
+use super::theme::{TextRoles, UiTheme};
+use super::widgets;
+use crate::localization::Localization;
+use crate::shared::{
+    game::{GamePaused, GameRestarted, GameState, GameStates, GameUnpaused, QuitToMenu},
+    render::FontHandles,
+    stages,
+};
+
+/// Plugin for the menu shown over the game while it is paused. The menu is opened with [Esc], [P],
+/// the start button of a gamepad or the pause button of the HUD.
+pub struct PauseMenuPlugin;
+
+impl Plugin for PauseMenuPlugin {
+    fn build(&self, app: &mut App) {
+        debug!("Building PauseMenuPlugin...");
+        app.init_resource::<PauseMenuState>()
+            .add_systems(Startup, spawn_pause_menu)
+            .add_systems(
+                Update,
+                (toggle_pause_system, navigate_pause_menu_system).in_set(stages::HandleEventsSet),
+            )
+            .add_systems(
+                Update,
+                (show_pause_menu_when_paused, update_pause_menu_selection)
+                    .in_set(stages::PrepareRenderSet),
+            );
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum PauseMenuActions {
+    Resume,
+    Restart,
+    Settings,
+    Quit,
+}
+
+const PAUSE_MENU_ACTIONS: [PauseMenuActions; 4] = [
+    PauseMenuActions::Resume,
+    PauseMenuActions::Restart,
+    PauseMenuActions::Settings,
+    PauseMenuActions::Quit,
+];
+
+impl PauseMenuActions {
+    fn text_key(&self) -> &'static str {
+        match self {
+            PauseMenuActions::Resume => "pause-resume",
+            PauseMenuActions::Restart => "pause-restart",
+            PauseMenuActions::Settings => "pause-settings",
+            PauseMenuActions::Quit => "pause-quit",
+        }
+    }
+}
+
+#[derive(Debug, Default, Resource)]
+struct PauseMenuState {
+    selected: usize,
+}
+
+#[derive(Component)]
+struct PauseMenuRootNode;
+
+#[derive(Component)]
+struct PauseMenuButton {
+    action: PauseMenuActions,
+}
+
+fn spawn_pause_menu(
+    mut commands: Commands,
+    fonts: Res<FontHandles>,
+    theme: Res<UiTheme>,
+    localization: Res<Localization>,
+) {
+    commands
+        .spawn((widgets::screen(), PauseMenuRootNode))
+        .with_children(|builder| {
+            builder
+                .spawn(widgets::panel(
+                    Style {
+                        align_items: AlignItems::Center,
+                        ..Default::default()
+                    },
+                    &theme,
+                ))
+                .with_children(|builder| {
+                    builder.spawn(widgets::localized_label(
+                        "pause-title",
+                        TextRoles::Hud,
+                        &fonts,
+                        &theme,
+                        &localization,
+                    ));
+
+                    for action in PAUSE_MENU_ACTIONS {
+                        builder
+                            .spawn((
+                                widgets::button(Style {
+                                    width: Val::Px(250.0),
+                                    justify_content: JustifyContent::Center,
+                                    padding: UiRect::all(Val::Px(5.0)),
+                                    ..Default::default()
+                                }),
+                                PauseMenuButton { action },
+                            ))
+                            .with_children(|builder| {
+                                builder.spawn(widgets::localized_label(
+                                    action.text_key(),
+                                    TextRoles::Item,
+                                    &fonts,
+                                    &theme,
+                                    &localization,
+                                ));
+                            });
+                    }
+                });
+        });
+}
+
+fn pause_toggle_pressed(
+    keyboard_input: &ButtonInput<KeyCode>,
+    gamepads: &Gamepads,
+    gamepad_buttons: &ButtonInput<GamepadButton>,
+) -> bool {
+    keyboard_input.any_just_pressed([KeyCode::Escape, KeyCode::KeyP])
+        || gamepads.iter().any(|gamepad| {
+            gamepad_buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::Start))
+        })
+}
+
+/// Pauses a running game, or resumes a paused one. [Esc] closes the settings screen first when it
+/// is open over the pause menu.
+pub(super) fn toggle_pause_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    game_state: Res<GameState>,
+    settings_screen: Res<SettingsScreen>,
+    mut game_paused_events: EventWriter<GamePaused>,
+    mut game_unpaused_events: EventWriter<GameUnpaused>,
+) {
+    if settings_screen.is_open
+        || !pause_toggle_pressed(&keyboard_input, &gamepads, &gamepad_buttons)
+    {
+        return;
+    }
+
+    match game_state.cur_state {
+        GameStates::Running => {
+            game_paused_events.send(GamePaused);
+        }
+        GameStates::Paused => {
+            game_unpaused_events.send(GameUnpaused);
+        }
+        GameStates::Menu | GameStates::GameOver => {}
+    }
+}
+
+/// Moves through the menu with the keyboard or a gamepad, and picks actions with those or the mouse.
+#[allow(clippy::too_many_arguments)]
+fn navigate_pause_menu_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    game_state: Res<GameState>,
+    mut menu_state: ResMut<PauseMenuState>,
+    mut settings_screen: ResMut<SettingsScreen>,
+    mut game_unpaused_events: EventWriter<GameUnpaused>,
+    mut restart_events: EventWriter<GameRestarted>,
+    mut quit_events: EventWriter<QuitToMenu>,
+    interaction_query: Query<(&Interaction, &PauseMenuButton), Changed<Interaction>>,
+) {
+    let GameStates::Paused = game_state.cur_state else {
+        return;
+    };
+
+    if settings_screen.is_open {
+        return;
+    }
+
+    let gamepad_pressed = |button_type| {
+        gamepads
+            .iter()
+            .any(|gamepad| gamepad_buttons.just_pressed(GamepadButton::new(gamepad, button_type)))
+    };
+
+    let action_count = PAUSE_MENU_ACTIONS.len();
+    if keyboard_input.any_just_pressed([KeyCode::ArrowUp, KeyCode::KeyW])
+        || gamepad_pressed(GamepadButtonType::DPadUp)
+    {
+        menu_state.selected = (menu_state.selected + action_count - 1) % action_count;
+    }
+
+    if keyboard_input.any_just_pressed([KeyCode::ArrowDown, KeyCode::KeyS])
+        || gamepad_pressed(GamepadButtonType::DPadDown)
+    {
+        menu_state.selected = (menu_state.selected + 1) % action_count;
+    }
+
+    let mut chosen_action = None;
+    if keyboard_input.any_just_pressed([KeyCode::Enter, KeyCode::Space])
+        || gamepad_pressed(GamepadButtonType::South)
+    {
+        chosen_action = Some(PAUSE_MENU_ACTIONS[menu_state.selected]);
+    }
+
+    for (interaction, button) in interaction_query.iter() {
+        match *interaction {
+            Interaction::Pressed => chosen_action = Some(button.action),
+            Interaction::Hovered => {
+                menu_state.selected = PAUSE_MENU_ACTIONS
+                    .iter()
+                    .position(|action| *action == button.action)
+                    .unwrap_or(0);
+            }
+            Interaction::None => {}
+        }
+    }
+
+    let Some(action) = chosen_action else {
+        return;
+    };
+
+    debug!("Picked {:?} from the pause menu", action);
+    match action {
+        PauseMenuActions::Resume => {
+            game_unpaused_events.send(GameUnpaused);
+        }
+        PauseMenuActions::Restart => {
+            restart_events.send(GameRestarted);
+        }
+        PauseMenuActions::Settings => settings_screen.open(),
+        PauseMenuActions::Quit => {
+            quit_events.send(QuitToMenu);
+        }
+    }
+}
+
+/// The menu makes way for the settings screen while it is open.
+fn show_pause_menu_when_paused(
+    game_state: Res<GameState>,
+    settings_screen: Res<SettingsScreen>,
+    mut menu_state: ResMut<PauseMenuState>,
+    mut root_query: Query<&mut Visibility, With<PauseMenuRootNode>>,
+) {
+    if !game_state.is_changed() && !settings_screen.is_changed() {
+        return;
+    }
+
+    let is_paused = matches!(game_state.cur_state, GameStates::Paused);
+    if !is_paused && menu_state.selected != 0 {
+        *menu_state = PauseMenuState::default();
+    }
+
+    for mut visibility in root_query.iter_mut() {
+        *visibility = if is_paused && !settings_screen.is_open {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+fn update_pause_menu_selection(
+    menu_state: Res<PauseMenuState>,
+    theme: Res<UiTheme>,
+    mut button_query: Query<(&PauseMenuButton, &mut BackgroundColor)>,
+) {
+    if !menu_state.is_changed() && !theme.is_changed() {
+        return;
+    }
+
+    for (button, mut background_color) in button_query.iter_mut() {
+        let is_selected = PAUSE_MENU_ACTIONS[menu_state.selected] == button.action;
+        *background_color = if is_selected {
+            theme.button_selected
+        } else {
+            Color::NONE
+        }
+        .into();
+    }
+}
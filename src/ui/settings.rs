@@ -0,0 +1,417 @@
+use bevy::prelude::*;
+
+use super::pause;
+use super::theme::{TextRoles, UiTheme};
+use super::widgets;
+use crate::localization::{Languages, Localization, LANGUAGES};
+use crate::settings::{InputActions, Settings, INPUT_ACTIONS};
+use crate::shared::{
+    game::{GameRestarted, GameState, GameStates},
+    render::FontHandles,
+    stages,
+};
+
+/// Keys that can't be bound to actions because the game already uses them everywhere.
+const RESERVED_KEYS: [KeyCode; 5] = [
+    KeyCode::Escape,
+    KeyCode::KeyP,
+    KeyCode::KeyR,
+    KeyCode::KeyM,
+    KeyCode::KeyO,
+];
+
+/// Plugin for the settings screen. The screen is opened with [O] on the menu or from the pause
+/// menu, and shows the audio mixer and accessibility panels next to its own options.
+pub struct SettingsScreenPlugin;
+
+impl Plugin for SettingsScreenPlugin {
+    fn build(&self, app: &mut App) {
+        debug!("Building SettingsScreenPlugin...");
+        app.init_resource::<SettingsScreen>()
+            .add_systems(Startup, spawn_settings_screen)
+            .add_systems(
+                Update,
+                (
+                    toggle_settings_screen_system
+                        .after(pause::toggle_pause_system)
+                        .before(rebind_system),
+                    graphics_option_system,
+                    language_option_system,
+                    rebind_system,
+                )
+                    .in_set(stages::HandleEventsSet),
+            )
+            .add_systems(
+                Update,
+                (show_settings_screen, update_settings_text).in_set(stages::PrepareRenderSet),
+            );
+    }
+}
+
+#[derive(Debug, Default, Resource)]
+pub(super) struct SettingsScreen {
+    pub is_open: bool,
+    /// The action waiting for a key to be bound to it.
+    rebinding: Option<InputActions>,
+}
+
+impl SettingsScreen {
+    pub fn open(&mut self) {
+        self.is_open = true;
+    }
+
+    fn close(&mut self) {
+        self.is_open = false;
+        self.rebinding = None;
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+enum GraphicsOptions {
+    Fullscreen,
+    Vsync,
+    ResolutionScale,
+}
+
+impl GraphicsOptions {
+    fn text(&self, settings: &Settings, localization: &Localization) -> String {
+        let graphics = &settings.graphics;
+        match self {
+            GraphicsOptions::Fullscreen => localization.format(
+                "settings-fullscreen",
+                &[("value", &on_off(graphics.fullscreen, localization))],
+            ),
+            GraphicsOptions::Vsync => localization.format(
+                "settings-vsync",
+                &[("value", &on_off(graphics.vsync, localization))],
+            ),
+            GraphicsOptions::ResolutionScale => localization.format(
+                "settings-resolution-scale",
+                &[("scale", &format!("{:.2}", graphics.resolution_scale))],
+            ),
+        }
+    }
+
+    fn change(&self, settings: &mut Settings) {
+        let graphics = &mut settings.graphics;
+        match self {
+            GraphicsOptions::Fullscreen => graphics.fullscreen = !graphics.fullscreen,
+            GraphicsOptions::Vsync => graphics.vsync = !graphics.vsync,
+            GraphicsOptions::ResolutionScale => graphics.next_resolution_scale(),
+        }
+    }
+}
+
+pub(super) fn on_off(value: bool, localization: &Localization) -> &str {
+    localization.get(if value { "settings-on" } else { "settings-off" })
+}
+
+/// The language after this one. Automatic comes before the first language and after the last.
+fn next_language(language: Option<Languages>) -> Option<Languages> {
+    match language {
+        None => Some(LANGUAGES[0]),
+        Some(language) => LANGUAGES
+            .iter()
+            .position(|other| *other == language)
+            .and_then(|index| LANGUAGES.get(index + 1))
+            .copied(),
+    }
+}
+
+fn language_text(settings: &Settings, localization: &Localization) -> String {
+    let language = match settings.language {
+        Some(language) => language.native_name().to_string(),
+        None => localization.format(
+            "settings-language-automatic",
+            &[("language", &localization.language().native_name())],
+        ),
+    };
+    localization.format("settings-language", &[("language", &language)])
+}
+
+/// A readable name for a key, e.g. "A" instead of "KeyA".
+fn key_name(key: KeyCode) -> String {
+    let name = format!("{:?}", key);
+
+    ["Key", "Digit", "Arrow"]
+        .iter()
+        .find_map(|prefix| name.strip_prefix(prefix))
+        .filter(|stripped| !stripped.is_empty())
+        .map_or(name.clone(), str::to_string)
+}
+
+#[derive(Component)]
+struct SettingsScreenRootNode;
+
+#[derive(Component)]
+struct GraphicsOptionButton {
+    option: GraphicsOptions,
+}
+
+#[derive(Component)]
+struct LanguageButton;
+
+#[derive(Component)]
+struct BindingButton {
+    action: InputActions,
+}
+
+fn spawn_settings_screen(
+    mut commands: Commands,
+    fonts: Res<FontHandles>,
+    theme: Res<UiTheme>,
+    localization: Res<Localization>,
+) {
+    let spawn_button = |builder: &mut ChildBuilder, component: ButtonComponent| {
+        let mut button = builder.spawn(widgets::button(Style::default()));
+
+        match component {
+            ButtonComponent::Graphics(option) => button.insert(GraphicsOptionButton { option }),
+            ButtonComponent::Language => button.insert(LanguageButton),
+            ButtonComponent::Binding(action) => button.insert(BindingButton { action }),
+        };
+
+        button.with_children(|builder| {
+            builder.spawn(widgets::label("", TextRoles::Body, &fonts, &theme));
+        });
+    };
+
+    commands
+        .spawn((widgets::screen(), SettingsScreenRootNode))
+        .with_children(|builder| {
+            builder
+                .spawn(widgets::panel(Style::default(), &theme))
+                .with_children(|builder| {
+                    let heading = |key| {
+                        widgets::localized_label(
+                            key,
+                            TextRoles::Item,
+                            &fonts,
+                            &theme,
+                            &localization,
+                        )
+                    };
+
+                    spawn_button(builder, ButtonComponent::Language);
+
+                    builder.spawn(heading("settings-graphics"));
+                    for option in [
+                        GraphicsOptions::Fullscreen,
+                        GraphicsOptions::Vsync,
+                        GraphicsOptions::ResolutionScale,
+                    ] {
+                        spawn_button(builder, ButtonComponent::Graphics(option));
+                    }
+
+                    builder.spawn(heading("settings-controls"));
+                    for action in INPUT_ACTIONS {
+                        spawn_button(builder, ButtonComponent::Binding(action));
+                    }
+
+                    builder.spawn(widgets::localized_label(
+                        "settings-rebind-hint",
+                        TextRoles::Small,
+                        &fonts,
+                        &theme,
+                        &localization,
+                    ));
+                    builder.spawn(widgets::localized_label(
+                        "settings-back",
+                        TextRoles::Body,
+                        &fonts,
+                        &theme,
+                        &localization,
+                    ));
+                });
+        });
+}
+
+enum ButtonComponent {
+    Graphics(GraphicsOptions),
+    Language,
+    Binding(InputActions),
+}
+
+/// Opens the screen from the menu and closes it again. Runs after the pause toggle so that closing
+/// the screen with [Esc] doesn't also unpause the game.
+fn toggle_settings_screen_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    game_state: Res<GameState>,
+    mut restart_reader: EventReader<GameRestarted>,
+    mut settings_screen: ResMut<SettingsScreen>,
+) {
+    if restart_reader.read().next().is_some() {
+        settings_screen.close();
+        return;
+    }
+
+    if settings_screen.rebinding.is_some() {
+        return;
+    }
+
+    if settings_screen.is_open {
+        if keyboard_input.any_just_pressed([KeyCode::Escape, KeyCode::KeyO]) {
+            settings_screen.close();
+        }
+    } else if let GameStates::Menu = game_state.cur_state {
+        if keyboard_input.just_pressed(KeyCode::KeyO) {
+            settings_screen.open();
+        }
+    }
+}
+
+fn graphics_option_system(
+    settings_screen: Res<SettingsScreen>,
+    mut settings: ResMut<Settings>,
+    interaction_query: Query<(&Interaction, &GraphicsOptionButton), Changed<Interaction>>,
+) {
+    if !settings_screen.is_open {
+        return;
+    }
+
+    for (interaction, button) in interaction_query.iter() {
+        if let Interaction::Pressed = *interaction {
+            button.option.change(&mut settings);
+
+            debug!("Saving graphics settings: {:?}", settings.graphics);
+            settings.save();
+        }
+    }
+}
+
+fn language_option_system(
+    settings_screen: Res<SettingsScreen>,
+    mut settings: ResMut<Settings>,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<LanguageButton>)>,
+) {
+    if !settings_screen.is_open {
+        return;
+    }
+
+    for interaction in interaction_query.iter() {
+        if let Interaction::Pressed = *interaction {
+            settings.language = next_language(settings.language);
+
+            debug!("Saving language: {:?}", settings.language);
+            settings.save();
+        }
+    }
+}
+
+/// Clicking a control waits for the next key press to bind to it. [Esc] cancels the rebinding.
+fn rebind_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut settings_screen: ResMut<SettingsScreen>,
+    mut settings: ResMut<Settings>,
+    interaction_query: Query<(&Interaction, &BindingButton), Changed<Interaction>>,
+) {
+    if !settings_screen.is_open {
+        return;
+    }
+
+    if let Some(action) = settings_screen.rebinding {
+        if keyboard_input.just_pressed(KeyCode::Escape) {
+            settings_screen.rebinding = None;
+            return;
+        }
+
+        let Some(key) = keyboard_input
+            .get_just_pressed()
+            .find(|key| !RESERVED_KEYS.contains(key))
+        else {
+            return;
+        };
+
+        debug!("Binding {:?} to {:?}", action, key);
+        settings.controls.rebind(action, *key);
+        settings.save();
+        settings_screen.rebinding = None;
+        return;
+    }
+
+    for (interaction, button) in interaction_query.iter() {
+        if let Interaction::Pressed = *interaction {
+            settings_screen.rebinding = Some(button.action);
+        }
+    }
+}
+
+fn show_settings_screen(
+    game_state: Res<GameState>,
+    mut settings_screen: ResMut<SettingsScreen>,
+    mut root_query: Query<&mut Visibility, With<SettingsScreenRootNode>>,
+) {
+    // the screen only stays open between runs and while paused
+    if game_state.is_changed() && game_state.is_running() && settings_screen.is_open {
+        settings_screen.close();
+    }
+
+    if !settings_screen.is_changed() {
+        return;
+    }
+
+    for mut visibility in root_query.iter_mut() {
+        *visibility = if settings_screen.is_open {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn update_settings_text(
+    settings: Res<Settings>,
+    settings_screen: Res<SettingsScreen>,
+    localization: Res<Localization>,
+    graphics_button_query: Query<(&GraphicsOptionButton, &Children), Without<BindingButton>>,
+    language_button_query: Query<&Children, With<LanguageButton>>,
+    binding_button_query: Query<(&BindingButton, &Children), Without<GraphicsOptionButton>>,
+    mut text_query: Query<&mut Text>,
+) {
+    if !settings.is_changed() && !settings_screen.is_changed() && !localization.is_changed() {
+        return;
+    }
+
+    for (button, children) in graphics_button_query.iter() {
+        for &child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                text.sections[0].value = button.option.text(&settings, &localization);
+            }
+        }
+    }
+
+    for children in language_button_query.iter() {
+        for &child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                text.sections[0].value = language_text(&settings, &localization);
+            }
+        }
+    }
+
+    for (button, children) in binding_button_query.iter() {
+        let keys = if settings_screen.rebinding == Some(button.action) {
+            localization.get("settings-press-a-key").to_string()
+        } else {
+            let binding = settings.controls.binding(button.action);
+            match binding.secondary {
+                Some(secondary) => {
+                    format!("{} / {}", key_name(binding.primary), key_name(secondary))
+                }
+                None => key_name(binding.primary),
+            }
+        };
+
+        for &child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                text.sections[0].value = localization.format(
+                    "settings-binding",
+                    &[
+                        ("action", &localization.get(button.action.text_key())),
+                        ("keys", &keys),
+                    ],
+                );
+            }
+        }
+    }
+}
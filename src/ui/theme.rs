@@ -0,0 +1,257 @@
+/**
+The look of the UI: text colours and sizes, panel backgrounds and spacing. The theme is read from a
+theme file and reloaded whenever the file changes, and the built-in defaults are used until the file
+has loaded. Widgets spawned with the helpers in `widgets` are restyled when the theme changes.
+*/
+use bevy::asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext};
+use bevy::prelude::*;
+use bevy::utils::BoxedFuture;
+use serde::{Deserialize, Deserializer};
+
+use super::widgets::{ThemedList, ThemedPanel, ThemedText};
+use crate::shared::stages;
+
+const UI_THEME_PATH: &str = "ui/default.theme.json";
+
+/// What a text is used for, which decides its size.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum TextRoles {
+    /// The game's title and the game over message.
+    Title,
+    /// The score and screen headers.
+    Hud,
+    /// The most important instruction on a screen.
+    Subtitle,
+    /// Titles of lists like the leaderboard.
+    Heading,
+    /// Menu items and hints.
+    Item,
+    /// Entries of lists like the leaderboard.
+    Entry,
+    /// Options on the settings screen.
+    Body,
+    Small,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub(crate) struct FontSizes {
+    pub title: f32,
+    pub hud: f32,
+    pub subtitle: f32,
+    pub heading: f32,
+    pub item: f32,
+    pub entry: f32,
+    pub body: f32,
+    pub small: f32,
+}
+
+impl Default for FontSizes {
+    fn default() -> Self {
+        Self {
+            title: 100.0,
+            hud: 60.0,
+            subtitle: 50.0,
+            heading: 35.0,
+            item: 30.0,
+            entry: 25.0,
+            body: 20.0,
+            small: 15.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Asset, TypePath, Resource)]
+#[serde(default)]
+pub(crate) struct UiTheme {
+    #[serde(deserialize_with = "hex_color")]
+    pub text: Color,
+    #[serde(deserialize_with = "hex_color")]
+    pub panel_background: Color,
+    /// Background of the button that is picked with the keyboard or hovered.
+    #[serde(deserialize_with = "hex_color")]
+    pub button_selected: Color,
+    #[serde(deserialize_with = "hex_color")]
+    pub slider_background: Color,
+    /// Colours of the first, second and third place on the leaderboard.
+    #[serde(deserialize_with = "hex_colors")]
+    pub medals: [Color; 3],
+    pub font_sizes: FontSizes,
+    pub panel_padding: f32,
+    /// Space between the widgets of panels and lists.
+    pub row_gap: f32,
+}
+
+impl Default for UiTheme {
+    fn default() -> Self {
+        Self {
+            text: Color::BLACK,
+            panel_background: Color::rgba(1.0, 1.0, 1.0, 0.85),
+            button_selected: Color::rgba(0.0, 0.0, 0.0, 0.15),
+            slider_background: Color::GRAY,
+            medals: [Color::GOLD, Color::SILVER, Color::rgb_u8(205, 127, 50)],
+            font_sizes: FontSizes::default(),
+            panel_padding: 10.0,
+            row_gap: 5.0,
+        }
+    }
+}
+
+impl UiTheme {
+    pub fn font_size(&self, role: TextRoles) -> f32 {
+        let sizes = &self.font_sizes;
+        match role {
+            TextRoles::Title => sizes.title,
+            TextRoles::Hud => sizes.hud,
+            TextRoles::Subtitle => sizes.subtitle,
+            TextRoles::Heading => sizes.heading,
+            TextRoles::Item => sizes.item,
+            TextRoles::Entry => sizes.entry,
+            TextRoles::Body => sizes.body,
+            TextRoles::Small => sizes.small,
+        }
+    }
+
+    /// The colour of a place on the leaderboard, counting from 0.
+    pub fn place_color(&self, place: usize) -> Color {
+        self.medals.get(place).copied().unwrap_or(self.text)
+    }
+}
+
+/// Colours are written as hex codes in theme files, e.g. "ffffffd9".
+fn hex_color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+    let hex = String::deserialize(deserializer)?;
+    Color::hex(&hex).map_err(serde::de::Error::custom)
+}
+
+fn hex_colors<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[Color; 3], D::Error> {
+    let hexes = <[String; 3]>::deserialize(deserializer)?;
+    let mut colors = [Color::NONE; 3];
+    for (color, hex) in colors.iter_mut().zip(hexes.iter()) {
+        *color = Color::hex(hex).map_err(serde::de::Error::custom)?;
+    }
+    Ok(colors)
+}
+
+#[derive(Default)]
+struct UiThemeLoader;
+
+impl AssetLoader for UiThemeLoader {
+    type Asset = UiTheme;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a Self::Settings,
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+
+            Ok(serde_json::from_slice(&bytes)?)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["theme.json"]
+    }
+}
+
+#[derive(Debug, Resource)]
+struct UiThemeHandle(Handle<UiTheme>);
+
+impl FromWorld for UiThemeHandle {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.get_resource::<AssetServer>().unwrap();
+        debug!("Loading UI theme...");
+        Self(asset_server.load(UI_THEME_PATH))
+    }
+}
+
+/// Plugin for the UI theme. Added before the rest of the UI so the theme is there when the UI is
+/// spawned.
+pub struct UiThemePlugin;
+
+impl Plugin for UiThemePlugin {
+    fn build(&self, app: &mut App) {
+        debug!("Building UiThemePlugin...");
+        app.init_asset::<UiTheme>()
+            .init_asset_loader::<UiThemeLoader>()
+            .init_resource::<UiTheme>()
+            .init_resource::<UiThemeHandle>()
+            .add_systems(
+                Update,
+                (load_ui_theme_system, apply_ui_theme_system)
+                    .chain()
+                    .in_set(stages::PrepareRenderSet),
+            );
+    }
+}
+
+/// Swaps in the theme from the theme file once it has loaded, and again whenever it is edited.
+fn load_ui_theme_system(
+    mut asset_events: EventReader<AssetEvent<UiTheme>>,
+    themes: Res<Assets<UiTheme>>,
+    theme_handle: Res<UiThemeHandle>,
+    mut theme: ResMut<UiTheme>,
+) {
+    let theme_id = theme_handle.0.id();
+    let was_loaded = asset_events
+        .read()
+        .any(|event| event.is_added(theme_id) || event.is_modified(theme_id));
+
+    if !was_loaded {
+        return;
+    }
+
+    if let Some(loaded_theme) = themes.get(theme_id) {
+        debug!("Applying UI theme from {:?}", UI_THEME_PATH);
+        theme.set_if_neq(loaded_theme.clone());
+    }
+}
+
+/// Restyles every themed widget when the theme changes.
+fn apply_ui_theme_system(
+    theme: Res<UiTheme>,
+    mut text_query: Query<(&mut Text, &ThemedText)>,
+    mut panel_query: Query<(&mut Style, &mut BackgroundColor), With<ThemedPanel>>,
+    mut list_query: Query<&mut Style, (With<ThemedList>, Without<ThemedPanel>)>,
+) {
+    if !theme.is_changed() || theme.is_added() {
+        return;
+    }
+
+    for (mut text, themed_text) in text_query.iter_mut() {
+        themed_text.apply(&theme, &mut text);
+    }
+
+    for (mut style, mut background_color) in panel_query.iter_mut() {
+        style.padding = UiRect::all(Val::Px(theme.panel_padding));
+        style.row_gap = Val::Px(theme.row_gap);
+        *background_color = theme.panel_background.into();
+    }
+
+    for mut style in list_query.iter_mut() {
+        style.row_gap = Val::Px(theme.row_gap);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn theme_file_matches_the_built_in_theme() {
+        let theme: UiTheme =
+            serde_json::from_str(include_str!("../../assets/ui/default.theme.json")).unwrap();
+        let built_in = UiTheme::default();
+
+        assert_eq!(theme.font_sizes, built_in.font_sizes);
+        assert_eq!(theme.text, built_in.text);
+        assert_eq!(theme.medals[2], built_in.medals[2]);
+        assert_eq!(theme.row_gap, built_in.row_gap);
+    }
+}
@@ -0,0 +1,140 @@
+use bevy::prelude::*;
+
+use super::theme::{TextRoles, UiTheme};
+use super::widgets;
+use crate::accessibility::Palette;
+use crate::achievements::{achievement_definition, AchievementUnlocked};
+use crate::localization::Localization;
+use crate::shared::{render::FontHandles, stages};
+
+const TOAST_DURATION: f32 = 3.0;
+/// How long before a toast is removed that it starts to fade out.
+const TOAST_FADE_TIME: f32 = 0.5;
+
+/// Plugin for small notifications that pop up at the top of the screen, like when an achievement
+/// is unlocked.
+pub struct ToastPlugin;
+
+impl Plugin for ToastPlugin {
+    fn build(&self, app: &mut App) {
+        debug!("Building ToastPlugin...");
+        app.add_systems(Startup, spawn_toast_container).add_systems(
+            Update,
+            (spawn_achievement_toasts, update_toasts_system).in_set(stages::PrepareRenderSet),
+        );
+    }
+}
+
+#[derive(Component)]
+struct ToastContainer;
+
+#[derive(Component)]
+struct Toast {
+    timer: Timer,
+}
+
+fn spawn_toast_container(mut commands: Commands) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                top: Val::Px(10.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(5.0),
+                ..Default::default()
+            },
+            z_index: ZIndex::Global(2),
+            ..Default::default()
+        },
+        ToastContainer,
+    ));
+}
+
+fn spawn_achievement_toasts(
+    mut commands: Commands,
+    fonts: Res<FontHandles>,
+    theme: Res<UiTheme>,
+    palette: Res<Palette>,
+    localization: Res<Localization>,
+    mut achievement_unlocked_reader: EventReader<AchievementUnlocked>,
+    container_query: Query<Entity, With<ToastContainer>>,
+) {
+    let container = container_query
+        .get_single()
+        .expect("Could not find toast container.");
+
+    for unlocked_event in achievement_unlocked_reader.read() {
+        let achievement = achievement_definition(unlocked_event.id);
+
+        let toast = commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        padding: UiRect::all(Val::Px(10.0)),
+                        ..Default::default()
+                    },
+                    background_color: Color::rgba(1.0, 1.0, 1.0, 0.85).into(),
+                    ..Default::default()
+                },
+                Toast {
+                    timer: Timer::from_seconds(TOAST_DURATION, TimerMode::Once),
+                },
+            ))
+            .with_children(|builder| {
+                builder.spawn(widgets::colored_label(
+                    localization.format(
+                        "toast-achievement",
+                        &[("name", &localization.get(achievement.name_key))],
+                    ),
+                    TextRoles::Entry,
+                    palette.highlight,
+                    &fonts,
+                    &theme,
+                ));
+                // coloured on its own so a reloaded theme doesn't undo the fade
+                builder.spawn(widgets::colored_label(
+                    localization.get(achievement.description_key),
+                    TextRoles::Body,
+                    theme.text,
+                    &fonts,
+                    &theme,
+                ));
+            })
+            .id();
+
+        commands.entity(container).add_child(toast);
+    }
+}
+
+/// Fades toasts out at the end of their lifetime. Uses real time so toasts still go away while
+/// the game is paused or over.
+fn update_toasts_system(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut toast_query: Query<(Entity, &mut Toast, &mut BackgroundColor, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (entity, mut toast, mut background_color, children) in toast_query.iter_mut() {
+        toast.timer.tick(time.delta());
+
+        if toast.timer.finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        let alpha = (toast.timer.remaining_secs() / TOAST_FADE_TIME).min(1.0);
+        background_color.0.set_a(0.85 * alpha);
+
+        for &child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                for section in text.sections.iter_mut() {
+                    section.style.color.set_a(alpha);
+                }
+            }
+        }
+    }
+}
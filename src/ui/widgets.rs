@@ -0,0 +1,170 @@
+/**
+Building blocks for the UI, styled with the `UiTheme`. Screens are composed from these so they look
+the same and follow the theme when it is reloaded. Each helper returns a bundle that marker
+components can be added to, e.g. `builder.spawn((widgets::label(..), MyText))`.
+*/
+use bevy::prelude::*;
+
+use super::theme::{TextRoles, UiTheme};
+use crate::localization::{Localization, LocalizedText};
+use crate::shared::render::FontHandles;
+
+/// A text styled by the theme. Only the first section of the text follows the theme.
+#[derive(Debug, Component)]
+pub(crate) struct ThemedText {
+    role: TextRoles,
+    /// Texts coloured by something else, like the palette or leaderboard places, only get their
+    /// size from the theme.
+    sized_only: bool,
+}
+
+impl ThemedText {
+    pub fn apply(&self, theme: &UiTheme, text: &mut Text) {
+        let Some(section) = text.sections.first_mut() else {
+            return;
+        };
+
+        section.style.font_size = theme.font_size(self.role);
+        if !self.sized_only {
+            section.style.color = theme.text;
+        }
+    }
+}
+
+#[derive(Debug, Component)]
+pub(crate) struct ThemedPanel;
+
+#[derive(Debug, Component)]
+pub(crate) struct ThemedList;
+
+#[derive(Bundle)]
+pub(super) struct LabelBundle {
+    text: TextBundle,
+    themed_text: ThemedText,
+}
+
+impl LabelBundle {
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.text.style = style;
+        self
+    }
+
+    pub fn with_justify(mut self, justify: JustifyText) -> Self {
+        self.text.text.justify = justify;
+        self
+    }
+
+    fn themed_color(mut self) -> Self {
+        self.themed_text.sized_only = false;
+        self
+    }
+}
+
+/// Text in the theme's text colour.
+pub(super) fn label(
+    value: impl Into<String>,
+    role: TextRoles,
+    fonts: &FontHandles,
+    theme: &UiTheme,
+) -> LabelBundle {
+    colored_label(value, role, theme.text, fonts, theme).themed_color()
+}
+
+/// Text in the theme's text colour that shows a key in the current language.
+pub(super) fn localized_label(
+    key: &'static str,
+    role: TextRoles,
+    fonts: &FontHandles,
+    theme: &UiTheme,
+    localization: &Localization,
+) -> (LabelBundle, LocalizedText) {
+    (
+        label(localization.get(key), role, fonts, theme),
+        LocalizedText(key),
+    )
+}
+
+/// Text in a colour of its own, which the theme leaves alone.
+pub(super) fn colored_label(
+    value: impl Into<String>,
+    role: TextRoles,
+    color: Color,
+    fonts: &FontHandles,
+    theme: &UiTheme,
+) -> LabelBundle {
+    LabelBundle {
+        text: TextBundle::from_section(
+            value,
+            TextStyle {
+                font: fonts.main_font.clone(),
+                font_size: theme.font_size(role),
+                color,
+            },
+        ),
+        themed_text: ThemedText {
+            role,
+            sized_only: true,
+        },
+    }
+}
+
+/// A column of widgets on the theme's panel background. The layout of the panel itself, like
+/// where it is placed, comes from `style`.
+pub(super) fn panel(style: Style, theme: &UiTheme) -> (NodeBundle, ThemedPanel) {
+    (
+        NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(theme.panel_padding)),
+                row_gap: Val::Px(theme.row_gap),
+                ..style
+            },
+            background_color: theme.panel_background.into(),
+            ..Default::default()
+        },
+        ThemedPanel,
+    )
+}
+
+/// A column of widgets without a background.
+pub(super) fn list(style: Style, theme: &UiTheme) -> (NodeBundle, ThemedList) {
+    (
+        NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(theme.row_gap),
+                ..style
+            },
+            ..Default::default()
+        },
+        ThemedList,
+    )
+}
+
+/// A clickable area for a label. Screens highlight their selected buttons with
+/// `UiTheme::button_selected`.
+pub(super) fn button(style: Style) -> ButtonBundle {
+    ButtonBundle {
+        style,
+        background_color: Color::NONE.into(),
+        ..Default::default()
+    }
+}
+
+/// A hidden node covering the whole window with its content in the middle, for screens that are
+/// shown over the game.
+pub(super) fn screen() -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..Default::default()
+        },
+        visibility: Visibility::Hidden,
+        ..Default::default()
+    }
+}